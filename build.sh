@@ -1,5 +1,7 @@
 #!/bin/bash
 
+(cd ./src/rust/harness && cargo run --quiet --bin check-manifests) || exit 1
+
 for src in ./src/as/*; do
   (
     cd "$src"
@@ -16,6 +18,10 @@ for src in ./src/rust/*; do
   (
     cd "$src"
 
+    if [ ! -f ./res/source.json ]; then
+      exit 0
+    fi
+
     if [ "$(cat ./res/source.json | grep 'deprecated' | awk '{print $2}')" == "true" ]; then
       rm -rf ./package.aix
     else
@@ -0,0 +1,47 @@
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ValueCastError {
+	NotArray,
+	NotObject,
+	NotString,
+	NotNumber,
+	NotBool,
+	NotNode,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum NodeError {
+	ParseError,
+	ModifyError,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum AidokuErrorKind {
+	ValueCast(ValueCastError),
+	Unimplemented,
+	DefaultNotFound,
+	NodeError(NodeError),
+	JsonParseError,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct AidokuError {
+	pub reason: AidokuErrorKind,
+}
+
+impl From<ValueCastError> for AidokuError {
+	fn from(error: ValueCastError) -> Self {
+		Self {
+			reason: AidokuErrorKind::ValueCast(error),
+		}
+	}
+}
+
+impl From<NodeError> for AidokuError {
+	fn from(error: NodeError) -> Self {
+		Self {
+			reason: AidokuErrorKind::NodeError(error),
+		}
+	}
+}
+
+pub type Result<T> = core::result::Result<T, AidokuError>;
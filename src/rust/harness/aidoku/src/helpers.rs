@@ -0,0 +1,112 @@
+pub mod substring {
+	pub trait Substring {
+		fn substring_after<T: AsRef<str>>(&self, pat: T) -> Option<&str>;
+		fn substring_before<T: AsRef<str>>(&self, pat: T) -> Option<&str>;
+		fn substring_after_last<T: AsRef<str>>(&self, pat: T) -> Option<&str>;
+		fn substring_before_last<T: AsRef<str>>(&self, pat: T) -> Option<&str>;
+	}
+
+	impl Substring for str {
+		fn substring_after<T: AsRef<str>>(&self, pat: T) -> Option<&str> {
+			let pat = pat.as_ref();
+			self.find(pat).map(|index| &self[index + pat.len()..])
+		}
+
+		fn substring_before<T: AsRef<str>>(&self, pat: T) -> Option<&str> {
+			self.find(pat.as_ref()).map(|index| &self[..index])
+		}
+
+		fn substring_after_last<T: AsRef<str>>(&self, pat: T) -> Option<&str> {
+			let pat = pat.as_ref();
+			self.rfind(pat).map(|index| &self[index + pat.len()..])
+		}
+
+		fn substring_before_last<T: AsRef<str>>(&self, pat: T) -> Option<&str> {
+			self.rfind(pat.as_ref()).map(|index| &self[..index])
+		}
+	}
+}
+
+pub mod uri {
+	use core::fmt::{Display, Formatter, Result};
+
+	const URI_RESERVED: &str = ";,/?:@&=+$#";
+	const URI_MARK: &str = "-_.!~*'()";
+
+	fn encode(text: &str, keep: impl Fn(char) -> bool) -> String {
+		let mut result = String::new();
+		for char in text.chars() {
+			if char.is_ascii_alphanumeric() || keep(char) {
+				result.push(char);
+			} else {
+				let mut buf = [0; 4];
+				for byte in char.encode_utf8(&mut buf).bytes() {
+					result.push_str(&format!("%{:02X}", byte));
+				}
+			}
+		}
+		result
+	}
+
+	/// Same as JavaScript's `encodeURI`.
+	pub fn encode_uri<T: AsRef<str>>(text: T) -> String {
+		encode(text.as_ref(), |c| {
+			URI_RESERVED.contains(c) || URI_MARK.contains(c)
+		})
+	}
+
+	/// Same as JavaScript's `encodeURIComponent`.
+	pub fn encode_uri_component<T: AsRef<str>>(text: T) -> String {
+		encode(text.as_ref(), |c| URI_MARK.contains(c))
+	}
+
+	#[derive(Clone, Debug, Default)]
+	pub struct QueryParameters {
+		params: Vec<(String, Option<String>)>,
+	}
+
+	impl QueryParameters {
+		pub fn new() -> Self {
+			Self::default()
+		}
+
+		pub fn push<T: AsRef<str>>(&mut self, key: T, value: Option<T>) {
+			self.params.push((
+				encode_uri_component(key),
+				value.map(encode_uri_component),
+			));
+		}
+
+		pub fn push_encoded<T: AsRef<str>>(&mut self, key: T, value: Option<T>) {
+			self.params.push((
+				key.as_ref().into(),
+				value.map(|value| value.as_ref().into()),
+			));
+		}
+
+		pub fn remove_all<T: AsRef<str>>(&mut self, key: T) {
+			let key = encode_uri_component(key);
+			self.params.retain(|(name, _)| *name != key);
+		}
+
+		pub fn set<T: AsRef<str>>(&mut self, key: T, value: Option<T>) {
+			self.remove_all(key.as_ref());
+			self.push(key, value);
+		}
+	}
+
+	impl Display for QueryParameters {
+		fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+			for (index, (key, value)) in self.params.iter().enumerate() {
+				if index > 0 {
+					write!(f, "&")?;
+				}
+				match value {
+					Some(value) => write!(f, "{}={}", key, value)?,
+					None => write!(f, "{}", key)?,
+				}
+			}
+			Ok(())
+		}
+	}
+}
@@ -0,0 +1,30 @@
+//! Host implementation of the legacy `aidoku` crate.
+//!
+//! The harness workspace patches the real crate with this one, so sources
+//! compile natively and every request is answered from recorded fixtures
+//! instead of the network.
+
+extern crate alloc;
+
+pub mod error;
+#[cfg(feature = "helpers")]
+pub mod helpers;
+pub mod std;
+pub mod testing;
+
+mod structs;
+
+pub use structs::*;
+
+pub mod prelude {
+	pub use crate::println;
+	pub use aidoku_harness_macros::*;
+	pub use alloc::format;
+}
+
+#[macro_export]
+macro_rules! println {
+	($($arg:tt)*) => {
+		$crate::std::print(::alloc::format!($($arg)*))
+	};
+}
@@ -0,0 +1,68 @@
+//! Just enough of the app's date parsing for the formats sources pass in.
+
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+	let year = if month <= 2 { year - 1 } else { year };
+	let era = if year >= 0 { year } else { year - 399 } / 400;
+	let yoe = year - era * 400;
+	let doy = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+	let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+	era * 146097 + doe - 719468
+}
+
+fn timezone_offset(timezone: Option<&str>) -> i64 {
+	match timezone {
+		Some("Asia/Shanghai" | "Asia/Taipei" | "Asia/Hong_Kong" | "GMT+8" | "UTC+8") => 8 * 3600,
+		Some("Asia/Tokyo" | "Asia/Seoul") => 9 * 3600,
+		_ => 0,
+	}
+}
+
+/// Parses `text` with a Unicode date pattern such as `yyyy-MM-dd HH:mm:ss`.
+pub fn parse(text: &str, format: &str, timezone: Option<&str>) -> Option<f64> {
+	let text = text.trim().as_bytes();
+	let format = format.as_bytes();
+	let (mut year, mut month, mut day) = (1970, 1, 1);
+	let (mut hour, mut minute, mut second) = (0, 0, 0);
+	let (mut i, mut j) = (0, 0);
+
+	while j < format.len() {
+		let symbol = format[j];
+		let mut width = 1;
+		while j + width < format.len() && format[j + width] == symbol {
+			width += 1;
+		}
+		j += width;
+
+		if symbol.is_ascii_alphabetic() {
+			let start = i;
+			let max = if symbol == b'y' && width != 2 { 4 } else { 2 };
+			while i < text.len() && i - start < max && text[i].is_ascii_digit() {
+				i += 1;
+			}
+			if start == i {
+				return None;
+			}
+			let value = core::str::from_utf8(&text[start..i]).ok()?.parse::<i64>().ok()?;
+			match symbol {
+				b'y' if width == 2 => year = 2000 + value,
+				b'y' => year = value,
+				b'M' => month = value,
+				b'd' => day = value,
+				b'H' => hour = value,
+				b'm' => minute = value,
+				b's' => second = value,
+				_ => return None,
+			}
+		} else {
+			for _ in 0..width {
+				if text.get(i) != Some(&symbol) {
+					return None;
+				}
+				i += 1;
+			}
+		}
+	}
+
+	let seconds = days_from_civil(year, month, day) * 86400 + hour * 3600 + minute * 60 + second;
+	Some((seconds - timezone_offset(timezone)) as f64)
+}
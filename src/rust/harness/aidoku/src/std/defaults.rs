@@ -0,0 +1,10 @@
+use super::ValueRef;
+use crate::error::Result;
+
+pub fn defaults_get(key: &str) -> Result<ValueRef> {
+	Ok(crate::testing::default_value(key).unwrap_or_default())
+}
+
+pub fn defaults_set(key: &str, value: ValueRef) {
+	crate::testing::set_default(key, value);
+}
@@ -0,0 +1,381 @@
+use core::fmt;
+use std::rc::Rc;
+
+use ego_tree::{NodeId, NodeRef};
+use scraper::{ElementRef, Html, Node as HtmlNode, Selector};
+
+use super::{ArrayRef, String, StringRef, Value, ValueRef, Vec};
+use crate::error::Result;
+
+const BLOCK_TAGS: &[&str] = &[
+	"address", "article", "aside", "blockquote", "dd", "div", "dl", "dt", "fieldset",
+	"figcaption", "figure", "footer", "form", "h1", "h2", "h3", "h4", "h5", "h6", "header",
+	"hr", "li", "main", "nav", "ol", "p", "pre", "section", "table", "tbody", "td", "tfoot",
+	"th", "thead", "tr", "ul",
+];
+
+/// A set of elements in a parsed document, mirroring SwiftSoup's `Elements`.
+#[derive(Clone)]
+pub struct Node {
+	document: Rc<Html>,
+	base_uri: Rc<str>,
+	ids: Vec<NodeId>,
+}
+
+impl fmt::Debug for Node {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Node").field("ids", &self.ids).finish()
+	}
+}
+
+fn string(value: String) -> StringRef {
+	StringRef(ValueRef::from(value))
+}
+
+fn push_normalized(out: &mut String, text: &str) {
+	for char in text.chars() {
+		if char.is_whitespace() {
+			if !out.is_empty() && !out.ends_with(' ') {
+				out.push(' ');
+			}
+		} else {
+			out.push(char);
+		}
+	}
+}
+
+fn push_space(out: &mut String) {
+	if !out.is_empty() && !out.ends_with(' ') {
+		out.push(' ');
+	}
+}
+
+fn collect_text(node: NodeRef<'_, HtmlNode>, out: &mut String) {
+	match node.value() {
+		HtmlNode::Text(text) => push_normalized(out, text),
+		HtmlNode::Element(element) => {
+			let name = element.name();
+			let block = BLOCK_TAGS.contains(&name);
+			if name == "br" || block {
+				push_space(out);
+			}
+			for child in node.children() {
+				collect_text(child, out);
+			}
+			if block {
+				push_space(out);
+			}
+		}
+		HtmlNode::Document | HtmlNode::Fragment => {
+			for child in node.children() {
+				collect_text(child, out);
+			}
+		}
+		_ => {}
+	}
+}
+
+fn collect_data(node: NodeRef<'_, HtmlNode>, out: &mut String) {
+	let raw = matches!(
+		node.value(),
+		HtmlNode::Element(element) if matches!(element.name(), "script" | "style")
+	);
+	for child in node.children() {
+		match child.value() {
+			HtmlNode::Text(text) if raw => out.push_str(text),
+			HtmlNode::Comment(comment) => out.push_str(comment),
+			HtmlNode::Element(_) => collect_data(child, out),
+			_ => {}
+		}
+	}
+}
+
+fn resolve_url(base: &str, url: &str) -> String {
+	if url.is_empty() || url.contains("://") || url.starts_with("data:") {
+		return url.into();
+	}
+	let scheme = base.split("://").next().unwrap_or("https");
+	if let Some(rest) = url.strip_prefix("//") {
+		return format!("{}://{}", scheme, rest);
+	}
+	let origin_end = base
+		.find("://")
+		.map(|index| {
+			base[index + 3..]
+				.find('/')
+				.map(|end| index + 3 + end)
+				.unwrap_or(base.len())
+		})
+		.unwrap_or(0);
+	if url.starts_with('/') {
+		format!("{}{}", &base[..origin_end], url)
+	} else {
+		let path = base.split(['?', '#']).next().unwrap_or_default();
+		let dir = match path.rfind('/') {
+			Some(index) if index >= origin_end => &path[..=index],
+			_ => return format!("{}/{}", path, url),
+		};
+		format!("{}{}", dir, url)
+	}
+}
+
+impl Node {
+	fn parse(document: Html, base_uri: &str) -> Self {
+		let root = document.tree.root().id();
+		Self {
+			document: Rc::new(document),
+			base_uri: base_uri.into(),
+			ids: vec![root],
+		}
+	}
+
+	pub fn new<T: AsRef<[u8]>>(buf: T) -> Result<Self> {
+		Self::new_with_uri(buf, "")
+	}
+
+	pub fn new_with_uri<T: AsRef<[u8]>>(buf: T, uri: &str) -> Result<Self> {
+		let text = String::from_utf8_lossy(buf.as_ref());
+		Ok(Self::parse(Html::parse_document(&text), uri))
+	}
+
+	pub fn new_fragment<T: AsRef<[u8]>>(buf: T) -> Result<Self> {
+		Self::new_fragment_with_uri(buf, "")
+	}
+
+	pub fn new_fragment_with_uri<T: AsRef<[u8]>>(buf: T, uri: &str) -> Result<Self> {
+		let text = String::from_utf8_lossy(buf.as_ref());
+		Ok(Self::parse(Html::parse_fragment(&text), uri))
+	}
+
+	fn with_ids(&self, ids: Vec<NodeId>) -> Self {
+		Self {
+			document: self.document.clone(),
+			base_uri: self.base_uri.clone(),
+			ids,
+		}
+	}
+
+	fn nodes(&self) -> impl Iterator<Item = NodeRef<'_, HtmlNode>> {
+		self.ids.iter().filter_map(|id| self.document.tree.get(*id))
+	}
+
+	fn elements(&self) -> impl Iterator<Item = ElementRef<'_>> {
+		self.nodes().filter_map(ElementRef::wrap)
+	}
+
+	pub fn select<T: AsRef<str>>(&self, selector: T) -> Node {
+		let selector = match Selector::parse(selector.as_ref()) {
+			Ok(selector) => selector,
+			Err(_) => return self.with_ids(Vec::new()),
+		};
+		let mut ids: Vec<NodeId> = Vec::new();
+
+		for node in self.nodes() {
+			let matches = match ElementRef::wrap(node) {
+				Some(element) => {
+					let mut found = Vec::new();
+					if selector.matches(&element) {
+						found.push(element.id());
+					}
+					found.extend(element.select(&selector).map(|a| a.id()));
+					found
+				}
+				None => node
+					.descendants()
+					.filter_map(ElementRef::wrap)
+					.filter(|element| selector.matches(element))
+					.map(|element| element.id())
+					.collect(),
+			};
+			for id in matches {
+				if !ids.contains(&id) {
+					ids.push(id);
+				}
+			}
+		}
+
+		self.with_ids(ids)
+	}
+
+	pub fn attr<T: AsRef<str>>(&self, attr: T) -> StringRef {
+		let attr = attr.as_ref();
+		let (name, absolute) = match attr.strip_prefix("abs:") {
+			Some(name) => (name, true),
+			None => (attr, false),
+		};
+		let value = self
+			.elements()
+			.find_map(|element| element.value().attr(name))
+			.unwrap_or_default();
+
+		if absolute {
+			string(resolve_url(&self.base_uri, value))
+		} else {
+			string(value.into())
+		}
+	}
+
+	pub fn has_attr<T: AsRef<str>>(&self, attr: T) -> bool {
+		let attr = attr.as_ref();
+		self.elements()
+			.any(|element| element.value().attr(attr).is_some())
+	}
+
+	pub fn has_class<T: AsRef<str>>(&self, class: T) -> bool {
+		let class = class.as_ref();
+		self.elements()
+			.any(|element| element.value().classes().any(|a| a == class))
+	}
+
+	pub fn text(&self) -> StringRef {
+		let texts = self
+			.nodes()
+			.map(|node| {
+				let mut text = String::new();
+				collect_text(node, &mut text);
+				text.trim().to_string()
+			})
+			.filter(|text| !text.is_empty())
+			.collect::<Vec<String>>();
+		string(texts.join(" "))
+	}
+
+	pub fn untrimmed_text(&self) -> StringRef {
+		let mut text = String::new();
+		for node in self.nodes() {
+			for child in node.descendants() {
+				if let HtmlNode::Text(value) = child.value() {
+					text.push_str(value);
+				}
+			}
+		}
+		string(text)
+	}
+
+	pub fn own_text(&self) -> StringRef {
+		let mut text = String::new();
+		if let Some(node) = self.nodes().next() {
+			for child in node.children() {
+				match child.value() {
+					HtmlNode::Text(value) => push_normalized(&mut text, value),
+					HtmlNode::Element(element) if element.name() == "br" => push_space(&mut text),
+					_ => {}
+				}
+			}
+		}
+		string(text.trim().into())
+	}
+
+	pub fn data(&self) -> StringRef {
+		let mut data = String::new();
+		for node in self.nodes() {
+			collect_data(node, &mut data);
+		}
+		string(data)
+	}
+
+	pub fn html(&self) -> StringRef {
+		let html = self
+			.nodes()
+			.map(|node| match ElementRef::wrap(node) {
+				Some(element) => element.inner_html(),
+				None => self.document.html(),
+			})
+			.collect::<Vec<String>>();
+		string(html.join("\n"))
+	}
+
+	pub fn outer_html(&self) -> StringRef {
+		let html = self
+			.nodes()
+			.map(|node| match ElementRef::wrap(node) {
+				Some(element) => element.html(),
+				None => self.document.html(),
+			})
+			.collect::<Vec<String>>();
+		string(html.join("\n"))
+	}
+
+	pub fn array(&self) -> ArrayRef {
+		ArrayRef::from_items(
+			self.ids
+				.iter()
+				.map(|id| ValueRef::from_value(Value::Node(self.with_ids(vec![*id]))))
+				.collect(),
+		)
+	}
+
+	pub fn first(&self) -> Node {
+		self.with_ids(self.ids.first().copied().into_iter().collect())
+	}
+
+	pub fn last(&self) -> Node {
+		self.with_ids(self.ids.last().copied().into_iter().collect())
+	}
+
+	pub fn next(&self) -> Option<Node> {
+		let node = self.nodes().next()?;
+		let next = node.next_siblings().find_map(ElementRef::wrap)?;
+		Some(self.with_ids(vec![next.id()]))
+	}
+
+	pub fn prev(&self) -> Option<Node> {
+		let node = self.nodes().next()?;
+		let prev = node.prev_siblings().find_map(ElementRef::wrap)?;
+		Some(self.with_ids(vec![prev.id()]))
+	}
+
+	pub fn parent(&self) -> Node {
+		let ids = self
+			.nodes()
+			.next()
+			.and_then(|node| node.parent())
+			.filter(|node| node.value().is_element())
+			.map(|node| node.id())
+			.into_iter()
+			.collect();
+		self.with_ids(ids)
+	}
+
+	pub fn children(&self) -> Node {
+		let ids = self
+			.nodes()
+			.flat_map(|node| node.children().filter_map(ElementRef::wrap))
+			.map(|element| element.id())
+			.collect();
+		self.with_ids(ids)
+	}
+
+	pub fn id(&self) -> StringRef {
+		string(
+			self.elements()
+				.next()
+				.and_then(|element| element.value().id())
+				.unwrap_or_default()
+				.into(),
+		)
+	}
+
+	pub fn tag_name(&self) -> StringRef {
+		string(
+			self.elements()
+				.next()
+				.map(|element| element.value().name().to_string())
+				.unwrap_or_default(),
+		)
+	}
+
+	pub fn class_name(&self) -> StringRef {
+		string(
+			self.elements()
+				.next()
+				.and_then(|element| element.value().attr("class"))
+				.unwrap_or_default()
+				.into(),
+		)
+	}
+
+	pub fn base_uri(&self) -> StringRef {
+		string(self.base_uri.to_string())
+	}
+}
@@ -0,0 +1,32 @@
+use super::{String, Value, ValueRef, Vec};
+use crate::error::{AidokuError, AidokuErrorKind, Result};
+
+pub(crate) fn from_serde(value: serde_json::Value) -> ValueRef {
+	let value = match value {
+		serde_json::Value::Null => Value::Null,
+		serde_json::Value::Bool(value) => Value::Bool(value),
+		serde_json::Value::Number(number) => match number.as_i64() {
+			Some(value) => Value::Int(value),
+			None => Value::Float(number.as_f64().unwrap_or_default()),
+		},
+		serde_json::Value::String(value) => Value::String(value),
+		serde_json::Value::Array(items) => {
+			Value::Array(items.into_iter().map(from_serde).collect::<Vec<_>>())
+		}
+		serde_json::Value::Object(entries) => Value::Object(
+			entries
+				.into_iter()
+				.map(|(key, value)| (key, from_serde(value)))
+				.collect::<Vec<(String, ValueRef)>>(),
+		),
+	};
+	ValueRef::from_value(value)
+}
+
+pub fn parse<T: AsRef<[u8]>>(buf: T) -> Result<ValueRef> {
+	serde_json::from_slice::<serde_json::Value>(buf.as_ref())
+		.map(from_serde)
+		.map_err(|_| AidokuError {
+			reason: AidokuErrorKind::JsonParseError,
+		})
+}
@@ -0,0 +1,427 @@
+use core::{cell::RefCell, fmt};
+use std::rc::Rc;
+
+use crate::error::{AidokuError, AidokuErrorKind, Result, ValueCastError};
+
+pub use alloc::{string::String, vec::Vec};
+
+pub mod defaults;
+pub mod html;
+pub mod json;
+pub mod net;
+
+mod date;
+
+pub fn current_date() -> f64 {
+	crate::testing::now()
+}
+
+pub fn print<T: AsRef<str>>(string: T) {
+	crate::testing::log(string.as_ref());
+	eprintln!("{}", string.as_ref());
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+	Null,
+	Int,
+	Float,
+	String,
+	Bool,
+	Array,
+	Object,
+	Date,
+	Node,
+	Unknown,
+}
+
+#[derive(Debug)]
+pub(crate) enum Value {
+	Null,
+	Int(i64),
+	Float(f64),
+	String(String),
+	Bool(bool),
+	Array(Vec<ValueRef>),
+	Object(Vec<(String, ValueRef)>),
+	Node(html::Node),
+}
+
+/// A shared handle to a value, standing in for a descriptor owned by the app.
+#[derive(Clone, Debug)]
+pub struct ValueRef(Rc<RefCell<Value>>);
+
+impl Default for ValueRef {
+	fn default() -> Self {
+		Self::null()
+	}
+}
+
+fn cast_error(error: ValueCastError) -> AidokuError {
+	AidokuError {
+		reason: AidokuErrorKind::ValueCast(error),
+	}
+}
+
+impl ValueRef {
+	pub(crate) fn from_value(value: Value) -> Self {
+		Self(Rc::new(RefCell::new(value)))
+	}
+
+	pub fn null() -> Self {
+		Self::from_value(Value::Null)
+	}
+
+	pub fn kind(&self) -> Kind {
+		match &*self.0.borrow() {
+			Value::Null => Kind::Null,
+			Value::Int(_) => Kind::Int,
+			Value::Float(_) => Kind::Float,
+			Value::String(_) => Kind::String,
+			Value::Bool(_) => Kind::Bool,
+			Value::Array(_) => Kind::Array,
+			Value::Object(_) => Kind::Object,
+			Value::Node(_) => Kind::Node,
+		}
+	}
+
+	pub fn is_none(&self) -> bool {
+		self.kind() == Kind::Null
+	}
+
+	pub fn as_string(&self) -> Result<StringRef> {
+		match &*self.0.borrow() {
+			Value::String(_) => Ok(StringRef(self.clone())),
+			Value::Node(node) => Ok(node.text()),
+			_ => Err(cast_error(ValueCastError::NotString)),
+		}
+	}
+
+	pub fn as_object(&self) -> Result<ObjectRef> {
+		match &*self.0.borrow() {
+			Value::Object(_) => Ok(ObjectRef(self.clone())),
+			_ => Err(cast_error(ValueCastError::NotObject)),
+		}
+	}
+
+	pub fn as_array(&self) -> Result<ArrayRef> {
+		match &*self.0.borrow() {
+			Value::Array(items) => Ok(ArrayRef(self.clone(), 0, items.len())),
+			_ => Err(cast_error(ValueCastError::NotArray)),
+		}
+	}
+
+	pub fn as_int(&self) -> Result<i64> {
+		match &*self.0.borrow() {
+			Value::Int(value) => Ok(*value),
+			Value::Float(value) => Ok(*value as i64),
+			Value::Bool(value) => Ok(*value as i64),
+			Value::String(value) => value
+				.trim()
+				.parse::<f64>()
+				.map(|value| value as i64)
+				.map_err(|_| cast_error(ValueCastError::NotNumber)),
+			_ => Err(cast_error(ValueCastError::NotNumber)),
+		}
+	}
+
+	pub fn as_float(&self) -> Result<f64> {
+		match &*self.0.borrow() {
+			Value::Int(value) => Ok(*value as f64),
+			Value::Float(value) => Ok(*value),
+			Value::String(value) => value
+				.trim()
+				.parse::<f64>()
+				.map_err(|_| cast_error(ValueCastError::NotNumber)),
+			_ => Err(cast_error(ValueCastError::NotNumber)),
+		}
+	}
+
+	pub fn as_bool(&self) -> Result<bool> {
+		match &*self.0.borrow() {
+			Value::Bool(value) => Ok(*value),
+			Value::Int(value) => Ok(*value != 0),
+			_ => Err(cast_error(ValueCastError::NotBool)),
+		}
+	}
+
+	pub fn as_date<T: AsRef<str>>(
+		&self,
+		format: T,
+		locale: Option<T>,
+		timezone: Option<T>,
+	) -> Result<f64> {
+		let _ = locale;
+		match &*self.0.borrow() {
+			Value::String(value) => date::parse(
+				value,
+				format.as_ref(),
+				timezone.as_ref().map(|a| a.as_ref()),
+			)
+			.ok_or(cast_error(ValueCastError::NotString)),
+			_ => Err(cast_error(ValueCastError::NotString)),
+		}
+	}
+
+	pub fn as_node(&self) -> Result<html::Node> {
+		match &*self.0.borrow() {
+			Value::Node(node) => Ok(node.clone()),
+			_ => Err(cast_error(ValueCastError::NotNode)),
+		}
+	}
+}
+
+impl From<i32> for ValueRef {
+	fn from(value: i32) -> Self {
+		Self::from_value(Value::Int(value as i64))
+	}
+}
+
+impl From<i64> for ValueRef {
+	fn from(value: i64) -> Self {
+		Self::from_value(Value::Int(value))
+	}
+}
+
+impl From<f64> for ValueRef {
+	fn from(value: f64) -> Self {
+		Self::from_value(Value::Float(value))
+	}
+}
+
+impl From<bool> for ValueRef {
+	fn from(value: bool) -> Self {
+		Self::from_value(Value::Bool(value))
+	}
+}
+
+impl From<String> for ValueRef {
+	fn from(value: String) -> Self {
+		Self::from_value(Value::String(value))
+	}
+}
+
+impl From<&str> for ValueRef {
+	fn from(value: &str) -> Self {
+		Self::from_value(Value::String(value.into()))
+	}
+}
+
+impl From<ObjectRef> for ValueRef {
+	fn from(value: ObjectRef) -> Self {
+		value.0
+	}
+}
+
+impl From<ArrayRef> for ValueRef {
+	fn from(value: ArrayRef) -> Self {
+		value.0
+	}
+}
+
+impl From<StringRef> for ValueRef {
+	fn from(value: StringRef) -> Self {
+		value.0
+	}
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct StringRef(pub ValueRef);
+
+impl StringRef {
+	pub fn read(&self) -> String {
+		match &*self.0 .0.borrow() {
+			Value::String(value) => value.clone(),
+			_ => String::new(),
+		}
+	}
+}
+
+impl From<String> for StringRef {
+	fn from(value: String) -> Self {
+		Self(value.into())
+	}
+}
+
+impl From<&str> for StringRef {
+	fn from(value: &str) -> Self {
+		Self(value.into())
+	}
+}
+
+impl fmt::Display for StringRef {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.read())
+	}
+}
+
+#[derive(Clone, Debug)]
+pub struct ObjectRef(pub ValueRef);
+
+impl Default for ObjectRef {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl ObjectRef {
+	pub fn new() -> Self {
+		Self(ValueRef::from_value(Value::Object(Vec::new())))
+	}
+
+	fn with<R>(&self, f: impl FnOnce(&Vec<(String, ValueRef)>) -> R) -> R {
+		match &*self.0 .0.borrow() {
+			Value::Object(entries) => f(entries),
+			_ => f(&Vec::new()),
+		}
+	}
+
+	fn with_mut(&mut self, f: impl FnOnce(&mut Vec<(String, ValueRef)>)) {
+		if let Value::Object(entries) = &mut *self.0 .0.borrow_mut() {
+			f(entries)
+		}
+	}
+
+	pub fn len(&self) -> usize {
+		self.with(|entries| entries.len())
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	pub fn get(&self, key: &str) -> ValueRef {
+		self.with(|entries| {
+			entries
+				.iter()
+				.find(|(name, _)| name == key)
+				.map(|(_, value)| value.clone())
+				.unwrap_or_default()
+		})
+	}
+
+	pub fn set(&mut self, key: &str, value: ValueRef) {
+		self.with_mut(|entries| match entries.iter_mut().find(|(name, _)| name == key) {
+			Some(entry) => entry.1 = value,
+			None => entries.push((key.into(), value)),
+		})
+	}
+
+	pub fn remove(&mut self, key: &str) {
+		self.with_mut(|entries| entries.retain(|(name, _)| name != key))
+	}
+
+	pub fn keys(&self) -> ArrayRef {
+		let keys = self.with(|entries| {
+			entries
+				.iter()
+				.map(|(name, _)| ValueRef::from(name.as_str()))
+				.collect::<Vec<_>>()
+		});
+		ArrayRef::from_items(keys)
+	}
+
+	pub fn values(&self) -> ArrayRef {
+		let values = self.with(|entries| {
+			entries
+				.iter()
+				.map(|(_, value)| value.clone())
+				.collect::<Vec<_>>()
+		});
+		ArrayRef::from_items(values)
+	}
+}
+
+/// An array handle that doubles as an iterator over its items, as on device.
+#[derive(Clone, Debug)]
+pub struct ArrayRef(pub ValueRef, usize, usize);
+
+impl Default for ArrayRef {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl ArrayRef {
+	pub fn new() -> Self {
+		Self::from_items(Vec::new())
+	}
+
+	pub(crate) fn from_items(items: Vec<ValueRef>) -> Self {
+		let len = items.len();
+		Self(ValueRef::from_value(Value::Array(items)), 0, len)
+	}
+
+	fn items(&self) -> std::cell::Ref<'_, Vec<ValueRef>> {
+		std::cell::Ref::map(self.0 .0.borrow(), |value| match value {
+			Value::Array(items) => items,
+			_ => unreachable!(),
+		})
+	}
+
+	pub fn len(&self) -> usize {
+		self.items().len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	pub fn get(&self, index: usize) -> ValueRef {
+		self.items().get(index).cloned().unwrap_or_default()
+	}
+
+	pub fn set(&mut self, index: usize, value: ValueRef) {
+		if let Value::Array(items) = &mut *self.0 .0.borrow_mut() {
+			if index < items.len() {
+				items[index] = value;
+			}
+		}
+	}
+
+	pub fn insert(&mut self, value: ValueRef) {
+		if let Value::Array(items) = &mut *self.0 .0.borrow_mut() {
+			items.push(value);
+		}
+		self.2 += 1;
+	}
+
+	pub fn remove(&mut self, index: usize) {
+		if let Value::Array(items) = &mut *self.0 .0.borrow_mut() {
+			if index < items.len() {
+				items.remove(index);
+				self.2 -= 1;
+			}
+		}
+	}
+}
+
+impl Iterator for ArrayRef {
+	type Item = ValueRef;
+
+	fn next(&mut self) -> Option<ValueRef> {
+		if self.1 >= self.2 {
+			return None;
+		}
+		let item = self.get(self.1);
+		self.1 += 1;
+		Some(item)
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		let len = self.2 - self.1;
+		(len, Some(len))
+	}
+}
+
+impl DoubleEndedIterator for ArrayRef {
+	fn next_back(&mut self) -> Option<ValueRef> {
+		if self.1 >= self.2 {
+			return None;
+		}
+		self.2 -= 1;
+		Some(self.get(self.2))
+	}
+}
+
+impl ExactSizeIterator for ArrayRef {}
@@ -0,0 +1,166 @@
+use core::cell::RefCell;
+use std::rc::Rc;
+
+use super::{html::Node, String, StringRef, ValueRef, Vec};
+use crate::{error::Result, testing};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HttpMethod {
+	Get,
+	Post,
+	Head,
+	Put,
+	Delete,
+}
+
+impl HttpMethod {
+	pub(crate) fn as_str(&self) -> &'static str {
+		match self {
+			Self::Get => "GET",
+			Self::Post => "POST",
+			Self::Head => "HEAD",
+			Self::Put => "PUT",
+			Self::Delete => "DELETE",
+		}
+	}
+}
+
+#[derive(Debug)]
+struct State {
+	url: String,
+	method: HttpMethod,
+	headers: Vec<(String, String)>,
+	body: Option<Vec<u8>>,
+	response: Option<testing::Response>,
+}
+
+/// A request answered by the fixture registry in [`crate::testing`].
+///
+/// Clones share one request, like copies of the descriptor on device, so a
+/// test can inspect what `modify_image_request` did to it.
+#[derive(Clone, Debug)]
+pub struct Request(Rc<RefCell<State>>);
+
+impl Request {
+	pub fn new<T: AsRef<str>>(url: T, method: HttpMethod) -> Self {
+		Self(Rc::new(RefCell::new(State {
+			url: url.as_ref().into(),
+			method,
+			headers: Vec::new(),
+			body: None,
+			response: None,
+		})))
+	}
+
+	pub fn get<T: AsRef<str>>(url: T) -> Self {
+		Self::new(url, HttpMethod::Get)
+	}
+
+	pub fn post<T: AsRef<str>>(url: T) -> Self {
+		Self::new(url, HttpMethod::Post)
+	}
+
+	pub fn header<T: AsRef<str>>(self, key: T, val: T) -> Self {
+		self.0
+			.borrow_mut()
+			.headers
+			.push((key.as_ref().into(), val.as_ref().into()));
+		self
+	}
+
+	pub fn body<T: AsRef<[u8]>>(self, data: T) -> Self {
+		self.0.borrow_mut().body = Some(data.as_ref().to_vec());
+		self
+	}
+
+	pub fn set_rate_limit(rate_limit: i32) {
+		testing::set_rate_limit(rate_limit);
+	}
+
+	pub fn set_rate_limit_period(period: i32) {
+		testing::set_rate_limit_period(period);
+	}
+
+	pub fn url(&self) -> StringRef {
+		StringRef::from(self.0.borrow().url.as_str())
+	}
+
+	pub fn set_url<T: AsRef<str>>(&self, url: T) {
+		self.0.borrow_mut().url = url.as_ref().into();
+	}
+
+	pub fn send(&self) {
+		let mut state = self.0.borrow_mut();
+		if state.response.is_none() {
+			let body = state
+				.body
+				.as_ref()
+				.map(|body| String::from_utf8_lossy(body).into_owned())
+				.unwrap_or_default();
+			state.response = Some(testing::respond(
+				state.method.as_str(),
+				&state.url,
+				&state.headers,
+				&body,
+			));
+		}
+	}
+
+	pub fn close(self) {}
+
+	fn with_response<R>(&self, f: impl FnOnce(&testing::Response) -> R) -> R {
+		self.send();
+		f(self.0.borrow().response.as_ref().unwrap())
+	}
+
+	pub fn status_code(&self) -> i32 {
+		self.with_response(|response| response.status)
+	}
+
+	pub fn get_header<T: AsRef<str>>(&self, header: T) -> Option<StringRef> {
+		let header = header.as_ref();
+		self.with_response(|response| {
+			response
+				.headers
+				.iter()
+				.find(|(name, _)| name.eq_ignore_ascii_case(header))
+				.map(|(_, value)| StringRef::from(value.as_str()))
+		})
+	}
+
+	/// Headers the source attached, for asserting on `modify_image_request`.
+	pub fn headers(&self) -> Vec<(String, String)> {
+		self.0.borrow().headers.clone()
+	}
+
+	pub fn data(self) -> Vec<u8> {
+		self.with_response(|response| response.data.clone())
+	}
+
+	/// Decodes the body with the charset named in `Content-Type`, like the
+	/// app does, falling back to UTF-8.
+	fn text(&self) -> String {
+		let encoding = self
+			.get_header("Content-Type")
+			.map(|value| value.read())
+			.and_then(|value| {
+				let (_, charset) = value.split_once("charset=")?;
+				encoding_rs::Encoding::for_label(charset.trim().as_bytes())
+			})
+			.unwrap_or(encoding_rs::UTF_8);
+		self.with_response(|response| encoding.decode(&response.data).0.into_owned())
+	}
+
+	pub fn string(self) -> Result<String> {
+		Ok(self.text())
+	}
+
+	pub fn json(self) -> Result<ValueRef> {
+		super::json::parse(self.data())
+	}
+
+	pub fn html(self) -> Result<Node> {
+		let url = self.0.borrow().url.clone();
+		Node::new_with_uri(self.text(), &url)
+	}
+}
@@ -0,0 +1,119 @@
+use crate::std::{ObjectRef, String, ValueRef, Vec};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum MangaStatus {
+	#[default]
+	Unknown,
+	Ongoing,
+	Completed,
+	Cancelled,
+	Hiatus,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum MangaContentRating {
+	#[default]
+	Safe,
+	Suggestive,
+	Nsfw,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum MangaViewer {
+	#[default]
+	Default,
+	Rtl,
+	Ltr,
+	Vertical,
+	Scroll,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Manga {
+	pub id: String,
+	pub cover: String,
+	pub title: String,
+	pub author: String,
+	pub artist: String,
+	pub description: String,
+	pub url: String,
+	pub categories: Vec<String>,
+	pub status: MangaStatus,
+	pub nsfw: MangaContentRating,
+	pub viewer: MangaViewer,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MangaPageResult {
+	pub manga: Vec<Manga>,
+	pub has_more: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct Chapter {
+	pub id: String,
+	pub title: String,
+	pub volume: f32,
+	pub chapter: f32,
+	pub date_updated: f64,
+	pub scanlator: String,
+	pub url: String,
+	pub lang: String,
+}
+
+/// Same defaults as aidoku-rs: negative numbers and dates mean unknown.
+impl Default for Chapter {
+	fn default() -> Self {
+		Self {
+			id: String::new(),
+			title: String::new(),
+			volume: -1.0,
+			chapter: -1.0,
+			date_updated: -1.0,
+			scanlator: String::new(),
+			url: String::new(),
+			lang: String::from("en"),
+		}
+	}
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Page {
+	pub index: i32,
+	pub url: String,
+	pub base64: String,
+	pub text: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DeepLink {
+	pub manga: Option<Manga>,
+	pub chapter: Option<Chapter>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Listing {
+	pub name: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterType {
+	Base,
+	Group,
+	Text,
+	Check,
+	Select,
+	Sort,
+	SortSelection,
+	Title,
+	Author,
+	Genre,
+}
+
+#[derive(Clone, Debug)]
+pub struct Filter {
+	pub kind: FilterType,
+	pub name: String,
+	pub value: ValueRef,
+	pub object: ObjectRef,
+}
@@ -0,0 +1,344 @@
+//! Fixture registry and helpers for driving a source under `cargo test`.
+//!
+//! A source keeps its recordings in `fixtures/fixtures.json`:
+//!
+//! ```json
+//! [
+//!   { "url": "https://example.com/comic/1", "file": "details.html" },
+//!   { "method": "POST", "url": "https://example.com/api", "body": "\"id\":1", "file": "api.json" },
+//!   { "url": "https://example.com/login", "status": 401, "once": true, "text": "" }
+//! ]
+//! ```
+//!
+//! `method` defaults to `GET`, `body` matches a substring of the request body,
+//! `request_headers` lists headers the request must carry, `file` is read
+//! from the same directory (raw bytes, so Big5 pages stay Big5) and `text`
+//! inlines the response. Entries marked `once` are consumed by the first
+//! request that matches them, which is how retries and re-logins are
+//! recorded.
+
+use std::{cell::RefCell, collections::HashMap, fs, path::Path, path::PathBuf};
+
+use crate::{
+	std::{json, ObjectRef, String, ValueRef, Vec},
+	Filter, FilterType, Listing,
+};
+
+/// 2025-01-01T00:00:00Z, so relative dates in fixtures stay stable.
+pub const DEFAULT_NOW: f64 = 1735689600.0;
+
+#[derive(Clone, Debug)]
+pub struct Response {
+	pub status: i32,
+	pub headers: Vec<(String, String)>,
+	pub data: Vec<u8>,
+}
+
+#[derive(Clone, Debug)]
+struct Fixture {
+	method: String,
+	url: String,
+	body: Option<String>,
+	request_headers: Vec<(String, String)>,
+	once: bool,
+	response: Response,
+}
+
+struct Harness {
+	fixtures: Vec<Fixture>,
+	defaults: HashMap<String, ValueRef>,
+	requests: Vec<String>,
+	headers: Vec<Vec<(String, String)>>,
+	cookies: Vec<(String, String)>,
+	logs: Vec<String>,
+	now: f64,
+	rate_limit: i32,
+	rate_limit_period: i32,
+	sent: Vec<f64>,
+}
+
+thread_local! {
+	static HARNESS: RefCell<Harness> = RefCell::new(Harness {
+		fixtures: Vec::new(),
+		defaults: HashMap::new(),
+		requests: Vec::new(),
+		headers: Vec::new(),
+		cookies: Vec::new(),
+		logs: Vec::new(),
+		now: DEFAULT_NOW,
+		rate_limit: 0,
+		rate_limit_period: 0,
+		sent: Vec::new(),
+	});
+}
+
+fn read_json(path: &Path) -> Option<serde_json::Value> {
+	let data = fs::read(path).ok()?;
+	Some(serde_json::from_slice(&data).unwrap_or_else(|error| {
+		panic!("{} is not valid JSON: {}", path.display(), error)
+	}))
+}
+
+fn load_fixtures(dir: &Path) -> Vec<Fixture> {
+	let Some(entries) = read_json(&dir.join("fixtures.json")) else {
+		return Vec::new();
+	};
+	let entries = entries.as_array().cloned().unwrap_or_default();
+
+	entries
+		.into_iter()
+		.map(|entry| {
+			let field = |key: &str| entry.get(key).and_then(|a| a.as_str()).map(String::from);
+			let url = field("url").expect("fixture without url");
+			let data = match (field("file"), field("text")) {
+				(Some(file), _) => fs::read(dir.join(&file))
+					.unwrap_or_else(|_| panic!("missing fixture file {}", file)),
+				(None, Some(text)) => text.into_bytes(),
+				(None, None) => Vec::new(),
+			};
+			let headers = |key: &str| -> Vec<(String, String)> {
+				entry
+					.get(key)
+					.and_then(|a| a.as_object())
+					.map(|headers| {
+						headers
+							.iter()
+							.map(|(key, value)| (key.clone(), value.as_str().unwrap_or_default().into()))
+							.collect()
+					})
+					.unwrap_or_default()
+			};
+
+			Fixture {
+				method: field("method").unwrap_or_else(|| "GET".into()),
+				url,
+				body: field("body"),
+				request_headers: headers("request_headers"),
+				once: entry.get("once").and_then(|a| a.as_bool()).unwrap_or(false),
+				response: Response {
+					status: entry.get("status").and_then(|a| a.as_i64()).unwrap_or(200) as i32,
+					headers: headers("headers"),
+					data,
+				},
+			}
+		})
+		.collect()
+}
+
+fn collect_defaults(items: &serde_json::Value, defaults: &mut HashMap<String, ValueRef>) {
+	for item in items.as_array().into_iter().flatten() {
+		if let (Some(key), Some(value)) = (item.get("key").and_then(|a| a.as_str()), item.get("default")) {
+			defaults.insert(key.into(), json::from_serde(value.clone()));
+		}
+		if let Some(children) = item.get("items") {
+			collect_defaults(children, defaults);
+		}
+	}
+}
+
+/// Resets the harness for the source at `source_dir`: replays its
+/// `fixtures/` and seeds settings with the defaults from `res/settings.json`.
+pub fn load<P: AsRef<Path>>(source_dir: P) {
+	let source_dir: PathBuf = source_dir.as_ref().into();
+	let fixtures = load_fixtures(&source_dir.join("fixtures"));
+	let mut defaults = HashMap::new();
+
+	if let Some(settings) = read_json(&source_dir.join("res").join("settings.json")) {
+		collect_defaults(&settings, &mut defaults);
+	}
+
+	HARNESS.with(|harness| {
+		let mut harness = harness.borrow_mut();
+		harness.fixtures = fixtures;
+		harness.defaults = defaults;
+		harness.requests.clear();
+		harness.headers.clear();
+		harness.cookies.clear();
+		harness.logs.clear();
+		harness.now = DEFAULT_NOW;
+		harness.rate_limit = 0;
+		harness.rate_limit_period = 0;
+		harness.sent.clear();
+	});
+}
+
+/// Every request sent since the last [`load`], as `METHOD url`.
+pub fn requests() -> Vec<String> {
+	HARNESS.with(|harness| harness.borrow().requests.clone())
+}
+
+/// The headers of each request in [`requests`], in the same order.
+pub fn request_headers() -> Vec<Vec<(String, String)>> {
+	HARNESS.with(|harness| harness.borrow().headers.clone())
+}
+
+/// Every line the source printed since the last [`load`].
+pub fn logs() -> Vec<String> {
+	HARNESS.with(|harness| harness.borrow().logs.clone())
+}
+
+pub(crate) fn log(line: &str) {
+	HARNESS.with(|harness| harness.borrow_mut().logs.push(line.into()));
+}
+
+pub fn now() -> f64 {
+	HARNESS.with(|harness| harness.borrow().now)
+}
+
+pub fn set_now(now: f64) {
+	HARNESS.with(|harness| harness.borrow_mut().now = now);
+}
+
+pub(crate) fn set_rate_limit(limit: i32) {
+	HARNESS.with(|harness| harness.borrow_mut().rate_limit = limit);
+}
+
+pub(crate) fn set_rate_limit_period(period: i32) {
+	HARNESS.with(|harness| harness.borrow_mut().rate_limit_period = period);
+}
+
+/// Holds a request back the way the app's rate limit does, moving the clock
+/// on instead of sleeping: once `rate_limit` requests went out in the last
+/// `rate_limit_period` seconds, the next waits for the oldest to age out.
+fn throttle(harness: &mut Harness) {
+	let (limit, period) = (harness.rate_limit, harness.rate_limit_period as f64);
+	if limit > 0 && period > 0.0 {
+		let now = harness.now;
+		let recent = harness.sent.iter().filter(|a| **a > now - period).count();
+		if recent >= limit as usize {
+			harness.now = harness.sent[harness.sent.len() - limit as usize] + period;
+		}
+	}
+	let now = harness.now;
+	harness.sent.push(now);
+}
+
+/// Stores a cookie for `domain` and its subdomains the way the app's web view
+/// does once a challenge is passed there. The app sends it with every later
+/// request to the domain, after the cookies the request names itself.
+pub fn set_webview_cookie(domain: &str, cookie: &str) {
+	HARNESS.with(|harness| {
+		let mut harness = harness.borrow_mut();
+		harness
+			.cookies
+			.retain(|(a, b)| a != domain || !same_name(b, cookie));
+		harness.cookies.push((domain.into(), cookie.into()));
+	});
+}
+
+fn same_name(a: &str, b: &str) -> bool {
+	a.split('=').next() == b.split('=').next()
+}
+
+/// `headers` with the stored cookies for `url` added to its `Cookie` header,
+/// skipping any the request already names.
+fn with_cookies(
+	cookies: &[(String, String)],
+	url: &str,
+	headers: &[(String, String)],
+) -> Vec<(String, String)> {
+	let host = url
+		.split("://")
+		.nth(1)
+		.and_then(|a| a.split(['/', '?']).next())
+		.unwrap_or_default();
+	let mut headers = headers.to_vec();
+	for (domain, cookie) in cookies {
+		if host != domain && !host.ends_with(&format!(".{}", domain)) {
+			continue;
+		}
+		match headers
+			.iter_mut()
+			.find(|(key, _)| key.eq_ignore_ascii_case("Cookie"))
+		{
+			Some((_, value)) if value.split("; ").any(|a| same_name(a, cookie)) => {}
+			Some((_, value)) => *value = format!("{}; {}", value, cookie),
+			None => headers.push(("Cookie".into(), cookie.clone())),
+		}
+	}
+	headers
+}
+
+pub fn default_value(key: &str) -> Option<ValueRef> {
+	HARNESS.with(|harness| harness.borrow().defaults.get(key).cloned())
+}
+
+pub fn set_default<T: Into<ValueRef>>(key: &str, value: T) {
+	let value = value.into();
+	HARNESS.with(|harness| harness.borrow_mut().defaults.insert(key.into(), value));
+}
+
+pub(crate) fn respond(method: &str, url: &str, headers: &[(String, String)], body: &str) -> Response {
+	HARNESS.with(|harness| {
+		let mut harness = harness.borrow_mut();
+		throttle(&mut harness);
+		let headers = &with_cookies(&harness.cookies, url, headers);
+		harness.requests.push(format!("{} {}", method, url));
+		harness.headers.push(headers.to_vec());
+
+		let index = harness.fixtures.iter().position(|fixture| {
+			fixture.method == method
+				&& fixture.url == url
+				&& fixture.body.as_ref().is_none_or(|needle| body.contains(needle.as_str()))
+				&& fixture.request_headers.iter().all(|header| headers.contains(header))
+		});
+		let Some(index) = index else {
+			panic!("no fixture for {} {}\nbody: {}", method, url, body);
+		};
+
+		if harness.fixtures[index].once {
+			harness.fixtures.remove(index).response
+		} else {
+			harness.fixtures[index].response.clone()
+		}
+	})
+}
+
+pub fn listing(name: &str) -> Listing {
+	Listing { name: name.into() }
+}
+
+fn filter(kind: FilterType, name: &str, value: ValueRef) -> Filter {
+	let mut object = ObjectRef::new();
+	object.set("name", name.into());
+	object.set("value", value.clone());
+	Filter {
+		kind,
+		name: name.into(),
+		value,
+		object,
+	}
+}
+
+pub fn title_filter(query: &str) -> Filter {
+	filter(FilterType::Title, "Title", query.into())
+}
+
+pub fn author_filter(query: &str) -> Filter {
+	filter(FilterType::Author, "Author", query.into())
+}
+
+pub fn text_filter(name: &str, text: &str) -> Filter {
+	filter(FilterType::Text, name, text.into())
+}
+
+pub fn select_filter(name: &str, index: i32) -> Filter {
+	filter(FilterType::Select, name, index.into())
+}
+
+pub fn check_filter(name: &str, value: i32) -> Filter {
+	filter(FilterType::Check, name, value.into())
+}
+
+pub fn genre_filter(name: &str, id: &str, value: i32) -> Filter {
+	let mut filter = filter(FilterType::Genre, name, value.into());
+	filter.object.set("id", id.into());
+	filter
+}
+
+pub fn sort_filter(name: &str, index: i32, ascending: bool) -> Filter {
+	let mut value = ObjectRef::new();
+	value.set("index", index.into());
+	value.set("ascending", ascending.into());
+	filter(FilterType::Sort, name, value.into())
+}
@@ -0,0 +1,55 @@
+//! Host-side replacements for the aidoku entry point attributes.
+//!
+//! On device the attributes export the annotated function to the app. Under
+//! the harness they only make it `pub` so tests can call it directly.
+
+use proc_macro::{Ident, Span, TokenStream, TokenTree};
+
+fn export(item: TokenStream) -> TokenStream {
+	let mut tokens = Vec::new();
+	let mut is_pub = false;
+	let mut done = false;
+
+	for token in item {
+		if !done {
+			if let TokenTree::Ident(ident) = &token {
+				match ident.to_string().as_str() {
+					"pub" => is_pub = true,
+					"fn" => {
+						if !is_pub {
+							tokens.push(TokenTree::Ident(Ident::new("pub", Span::call_site())));
+						}
+						done = true;
+					}
+					_ => {}
+				}
+			}
+		}
+		tokens.push(token);
+	}
+
+	tokens.into_iter().collect()
+}
+
+macro_rules! entry_points {
+	($($name:ident),*) => {
+		$(
+			#[proc_macro_attribute]
+			pub fn $name(_: TokenStream, item: TokenStream) -> TokenStream {
+				export(item)
+			}
+		)*
+	};
+}
+
+entry_points!(
+	initialize,
+	get_manga_list,
+	get_manga_listing,
+	get_manga_details,
+	get_chapter_list,
+	get_page_list,
+	modify_image_request,
+	handle_url,
+	handle_notification
+);
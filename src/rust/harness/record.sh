@@ -0,0 +1,58 @@
+#!/bin/bash
+
+# Re-records the fixture files of the given sources (or all of them) from the
+# live sites, so the harness tests replay real markup instead of trimmed pages.
+#
+#   ./record.sh [zh.mkzhan zh.cartoonmad ...]
+#
+# Only successful GET entries backed by a `file` are fetched; entries that
+# simulate failures (`status`), inline `text` and POST bodies are left alone.
+# A file shared by several entries is recorded from the first of them. Files
+# are only replaced when the request succeeds. The harness tests run once the
+# pages are in, since live pages rarely match the ids the tests expect and
+# their assertions need updating to what was recorded.
+
+UA='Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1'
+
+cd "$(dirname "$0")/.." || exit 1
+
+if [ $# -eq 0 ]; then
+  set -- zh.*
+fi
+
+for src in "$@"; do
+  fixtures="./$src/fixtures/fixtures.json"
+
+  if [ ! -f "$fixtures" ]; then
+    continue
+  fi
+
+  while read -r entry; do
+    url="$(jq -r '.url' <<< "$entry")"
+    file="./$src/fixtures/$(jq -r '.file' <<< "$entry")"
+    headers=()
+
+    while read -r header; do
+      [ -n "$header" ] && headers+=(-H "$header")
+    done < <(jq -r '.request_headers // {} | to_entries[] | "\(.key): \(.value)"' <<< "$entry")
+
+    if curl -sSfL --compressed -A "$UA" "${headers[@]}" -o "$file.tmp" "$url"; then
+      mv "$file.tmp" "$file"
+      echo "recorded $file"
+    else
+      rm -f "$file.tmp"
+      echo "failed $url" >&2
+      failed=1
+    fi
+  done < <(jq -c '
+    map(select((.method // "GET") == "GET" and .file != null and .status == null and .body == null))
+    | unique_by(.file)
+    | .[]
+  ' "$fixtures")
+done
+
+if [ -n "$failed" ]; then
+  echo "some fixtures could not be recorded and were left as they were" >&2
+fi
+
+(cd ./harness && cargo test --quiet)
@@ -0,0 +1,31 @@
+//! Reports `res/` manifests that disagree with their source's code, exiting
+//! with a non-zero status if there are any.
+//!
+//! Usage: `cargo run --bin check-manifests [src/rust]`
+
+use std::{path::PathBuf, process::ExitCode};
+
+use zh_harness::manifest;
+
+fn main() -> ExitCode {
+	let root = std::env::args()
+		.nth(1)
+		.map(PathBuf::from)
+		.unwrap_or_else(|| {
+			PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+				.parent()
+				.unwrap()
+				.to_path_buf()
+		});
+	let problems = manifest::check_all(&root);
+
+	for problem in &problems {
+		eprintln!("{}", problem);
+	}
+	if problems.is_empty() {
+		ExitCode::SUCCESS
+	} else {
+		eprintln!("{} problem(s) found", problems.len());
+		ExitCode::FAILURE
+	}
+}
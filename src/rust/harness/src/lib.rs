@@ -0,0 +1,15 @@
+//! Shared setup for the source tests in `tests/sources`, and the manifest
+//! checker behind `cargo run --bin check-manifests`.
+
+use std::path::PathBuf;
+
+pub mod manifest;
+
+/// Points the harness at `src/rust/<source>` and replays its fixtures.
+pub fn load(source: &str) {
+	let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+		.parent()
+		.unwrap()
+		.join(source);
+	aidoku::testing::load(dir);
+}
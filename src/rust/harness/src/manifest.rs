@@ -0,0 +1,462 @@
+//! Checks that each source's `res/` manifests agree with its code.
+//!
+//! Select and sort options in `res/filters.json` are matched by position to
+//! the `FILTER_*` arrays read in the filter's `match filter.name` arm (or the
+//! `FilterType::Sort` branch), and listings in `res/source.json` are matched by
+//! name to the arms of `match listing.name`. Neither side is checked by the
+//! compiler, so a missing entry silently shifts every option after it.
+
+use std::{collections::HashMap, fs, path::Path};
+
+use serde_json::Value;
+
+/// A `const FILTER_*` array.
+struct Table {
+	values: Vec<String>,
+}
+
+/// A match arm: the names it matches and the text of its body.
+struct Arm<'a> {
+	names: Vec<String>,
+	body: &'a str,
+}
+
+/// Checks every packaged source under `root` (the `src/rust` directory),
+/// returning one line per problem.
+pub fn check_all(root: &Path) -> Vec<String> {
+	let mut dirs = fs::read_dir(root)
+		.unwrap_or_else(|error| panic!("cannot read {}: {}", root.display(), error))
+		.filter_map(|entry| entry.ok().map(|a| a.path()))
+		.filter(|path| path.join("res").join("source.json").is_file())
+		.collect::<Vec<_>>();
+	dirs.sort();
+
+	let mut problems = Vec::new();
+	for dir in dirs {
+		let name = dir.file_name().unwrap().to_string_lossy().into_owned();
+		let read = |path: &Path| fs::read_to_string(path).unwrap_or_default();
+		let source = read(&dir.join("res").join("source.json"));
+		let filters = read(&dir.join("res").join("filters.json"));
+		let code = rust_files(&dir.join("src"))
+			.iter()
+			.map(|path| read(path))
+			.collect::<Vec<_>>()
+			.join("\n");
+		problems.extend(
+			check_source(&source, &filters, &code)
+				.into_iter()
+				.map(|problem| format!("{}: {}", name, problem)),
+		);
+	}
+	problems
+}
+
+fn rust_files(dir: &Path) -> Vec<std::path::PathBuf> {
+	let mut files = Vec::new();
+	for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+		let path = entry.path();
+		if path.is_dir() {
+			files.extend(rust_files(&path));
+		} else if path.extension().is_some_and(|a| a == "rs") {
+			files.push(path);
+		}
+	}
+	files.sort();
+	files
+}
+
+/// Checks one source from the contents of its `source.json`, `filters.json`
+/// and Rust code. Deprecated sources are not packaged and are skipped.
+pub fn check_source(source: &str, filters: &str, code: &str) -> Vec<String> {
+	let mut problems = Vec::new();
+	let source = parse_json(source, "source.json", &mut problems);
+	if source["info"]["deprecated"].as_bool() == Some(true) {
+		return Vec::new();
+	}
+	let filters = if filters.trim().is_empty() {
+		Value::Array(Vec::new())
+	} else {
+		parse_json(filters, "filters.json", &mut problems)
+	};
+	let code = strip_comments(code);
+	let tables = tables(&code, &mut problems);
+
+	check_filters(&filters, &code, &tables, &mut problems);
+	check_listings(&source, &code, &mut problems);
+	problems
+}
+
+fn parse_json(text: &str, file: &str, problems: &mut Vec<String>) -> Value {
+	serde_json::from_str(text).unwrap_or_else(|error| {
+		problems.push(format!("{} is not valid JSON: {}", file, error));
+		Value::Null
+	})
+}
+
+fn check_filters(
+	filters: &Value,
+	code: &str,
+	tables: &HashMap<String, Table>,
+	problems: &mut Vec<String>,
+) {
+	let filters = filters.as_array().cloned().unwrap_or_default();
+	let arms = blocks(code, "match filter.name.as_str()")
+		.into_iter()
+		.flat_map(arms)
+		.collect::<Vec<_>>();
+	let sort_tables = blocks(code, "FilterType::Sort =>")
+		.into_iter()
+		.flat_map(table_names)
+		.collect::<Vec<_>>();
+
+	let mut names = Vec::new();
+	for filter in &filters {
+		let kind = filter["type"].as_str().unwrap_or_default();
+		if kind == "title" || kind == "author" {
+			continue;
+		}
+		let name = filter["name"].as_str().unwrap_or_default();
+		if names.contains(&name) {
+			problems.push(format!("duplicate filter name \"{}\"", name));
+		}
+		names.push(name);
+
+		let options = filter["options"]
+			.as_array()
+			.map(|options| {
+				options
+					.iter()
+					.map(|a| a.as_str().unwrap_or_default())
+					.collect::<Vec<_>>()
+			})
+			.unwrap_or_default();
+		for option in duplicates(&options) {
+			problems.push(format!(
+				"filter \"{}\" lists option \"{}\" twice",
+				name, option
+			));
+		}
+
+		let arm = arms.iter().find(|arm| arm.names.iter().any(|a| a == name));
+		let used = match (arm, kind) {
+			(Some(arm), _) => table_names(arm.body),
+			(None, "sort") => sort_tables.clone(),
+			(None, _) => {
+				problems.push(format!(
+					"filter \"{}\" has no arm in `match filter.name`",
+					name
+				));
+				continue;
+			}
+		};
+		for table_name in used {
+			let Some(table) = tables.get(&table_name) else {
+				continue;
+			};
+			if table.values.len() != options.len() {
+				problems.push(format!(
+					"filter \"{}\" has {} options but {} has {} entries",
+					name,
+					options.len(),
+					table_name,
+					table.values.len()
+				));
+			}
+		}
+	}
+
+	for arm in &arms {
+		for name in &arm.names {
+			if !names.contains(&name.as_str()) {
+				problems.push(format!(
+					"`match filter.name` handles unknown filter \"{}\"",
+					name
+				));
+			}
+		}
+	}
+}
+
+fn check_listings(source: &Value, code: &str, problems: &mut Vec<String>) {
+	let listings = source["listings"]
+		.as_array()
+		.map(|listings| {
+			listings
+				.iter()
+				.map(|a| a["name"].as_str().unwrap_or_default())
+				.collect::<Vec<_>>()
+		})
+		.unwrap_or_default();
+	for name in duplicates(&listings) {
+		problems.push(format!("listing \"{}\" is declared twice", name));
+	}
+
+	let handled = blocks(code, "match listing.name.as_str()")
+		.into_iter()
+		.flat_map(arms)
+		.flat_map(|arm| arm.names)
+		.collect::<Vec<_>>();
+	for name in &listings {
+		if !handled.iter().any(|a| a == name) {
+			problems.push(format!(
+				"listing \"{}\" has no arm in `match listing.name`",
+				name
+			));
+		}
+	}
+}
+
+/// Each value that appears more than once.
+fn duplicates<'a>(values: &[&'a str]) -> Vec<&'a str> {
+	let mut duplicates = Vec::new();
+	for (index, value) in values.iter().enumerate() {
+		if values[..index].contains(value) && !duplicates.contains(value) {
+			duplicates.push(*value);
+		}
+	}
+	duplicates
+}
+
+/// Reads every `const FILTER_*` array, flagging repeated entries, which would
+/// send two options to the same ID.
+fn tables(code: &str, problems: &mut Vec<String>) -> HashMap<String, Table> {
+	let mut tables = HashMap::new();
+	let mut rest = code;
+
+	while let Some(start) = rest.find("const FILTER_") {
+		rest = &rest[start + "const ".len()..];
+		let name = identifier(rest);
+		let Some(open) = rest.find("= [").map(|a| a + 2) else {
+			continue;
+		};
+		let Some(close) = closing(rest, open) else {
+			continue;
+		};
+		let values = split_top_level(&rest[open + 1..close]);
+		let refs = values.iter().map(String::as_str).collect::<Vec<_>>();
+		for value in duplicates(&refs) {
+			problems.push(format!("{} lists {} twice", name, value));
+		}
+		tables.insert(name, Table { values });
+		rest = &rest[close..];
+	}
+
+	tables
+}
+
+fn identifier(text: &str) -> String {
+	text.chars()
+		.take_while(|a| a.is_ascii_alphanumeric() || *a == '_')
+		.collect()
+}
+
+/// The names of the `FILTER_*` arrays indexed in `code`.
+fn table_names(code: &str) -> Vec<String> {
+	let mut names = Vec::new();
+	let mut rest = code;
+	while let Some(start) = rest.find("FILTER_") {
+		let boundary = rest[..start]
+			.chars()
+			.last()
+			.is_none_or(|a| !a.is_ascii_alphanumeric() && a != '_');
+		rest = &rest[start..];
+		let name = identifier(rest);
+		rest = &rest[name.len()..];
+		if boundary && rest.starts_with('[') && !names.contains(&name) {
+			names.push(name);
+		}
+	}
+	names
+}
+
+/// The bodies of the `{ … }` blocks that follow each occurrence of `marker`.
+fn blocks<'a>(code: &'a str, marker: &str) -> Vec<&'a str> {
+	let mut blocks = Vec::new();
+	let mut offset = 0;
+	while let Some(start) = code[offset..].find(marker) {
+		let start = offset + start + marker.len();
+		let Some(open) = code[start..].find('{').map(|a| start + a) else {
+			break;
+		};
+		let Some(close) = closing(code, open) else {
+			break;
+		};
+		blocks.push(&code[open + 1..close]);
+		offset = close;
+	}
+	blocks
+}
+
+/// The arms of a `match` block whose patterns are string literals, such as
+/// `"日榜" => …` or `"a" | "b" => …`.
+fn arms(block: &str) -> Vec<Arm<'_>> {
+	let mut arms: Vec<Arm> = Vec::new();
+	let mut names = Vec::new();
+	let mut body_start = None;
+	let mut depth = 0;
+	let mut index = 0;
+	let bytes = block.as_bytes();
+
+	while index < bytes.len() {
+		match bytes[index] {
+			b'{' | b'(' | b'[' => depth += 1,
+			b'}' | b')' | b']' => depth -= 1,
+			b'\'' => {
+				index = char_end(block, index);
+				continue;
+			}
+			b'"' => {
+				let end = string_end(block, index);
+				if depth == 0 {
+					let after = block[end..].trim_start();
+					if after.starts_with("=>") || after.starts_with('|') {
+						if let Some(start) = body_start.take() {
+							arms.push(Arm {
+								names: std::mem::take(&mut names),
+								body: &block[start..index],
+							});
+						}
+						names.push(block[index + 1..end - 1].to_string());
+						if after.starts_with("=>") {
+							body_start = Some(end);
+						}
+					}
+				}
+				index = end;
+				continue;
+			}
+			_ => {}
+		}
+		index += 1;
+	}
+	if let Some(start) = body_start {
+		arms.push(Arm {
+			names,
+			body: &block[start..],
+		});
+	}
+
+	arms
+}
+
+/// The index just past the string literal opening at `start`.
+fn string_end(text: &str, start: usize) -> usize {
+	let bytes = text.as_bytes();
+	let mut index = start + 1;
+	while index < bytes.len() {
+		match bytes[index] {
+			b'\\' => index += 2,
+			b'"' => return index + 1,
+			_ => index += 1,
+		}
+	}
+	bytes.len()
+}
+
+/// The index just past the character literal opening at `start`, or just
+/// past the quote when it starts a lifetime such as `'static`.
+fn char_end(text: &str, start: usize) -> usize {
+	let rest = &text[start + 1..];
+	let mut chars = rest.char_indices();
+	let end = match chars.next() {
+		Some((_, '\\')) => rest.find('\'').filter(|a| *a > 1),
+		Some((_, char)) => rest[char.len_utf8()..]
+			.starts_with('\'')
+			.then_some(char.len_utf8()),
+		None => None,
+	};
+	match end {
+		Some(end) => start + 1 + end + 1,
+		None => start + 1,
+	}
+}
+
+/// The index of the bracket closing the one at `open`, skipping strings.
+fn closing(text: &str, open: usize) -> Option<usize> {
+	let bytes = text.as_bytes();
+	let mut depth = 0;
+	let mut index = open;
+	while index < bytes.len() {
+		match bytes[index] {
+			b'{' | b'(' | b'[' => depth += 1,
+			b'}' | b')' | b']' => {
+				depth -= 1;
+				if depth == 0 {
+					return Some(index);
+				}
+			}
+			b'"' => {
+				index = string_end(text, index);
+				continue;
+			}
+			b'\'' => {
+				index = char_end(text, index);
+				continue;
+			}
+			_ => {}
+		}
+		index += 1;
+	}
+	None
+}
+
+/// Splits array items at top-level commas, dropping a trailing comma.
+fn split_top_level(items: &str) -> Vec<String> {
+	let mut values = Vec::new();
+	let mut depth = 0;
+	let mut start = 0;
+	let mut index = 0;
+	let bytes = items.as_bytes();
+	while index < bytes.len() {
+		match bytes[index] {
+			b'{' | b'(' | b'[' => depth += 1,
+			b'}' | b')' | b']' => depth -= 1,
+			b',' if depth == 0 => {
+				values.push(items[start..index].trim().to_string());
+				start = index + 1;
+			}
+			b'"' => {
+				index = string_end(items, index);
+				continue;
+			}
+			b'\'' => {
+				index = char_end(items, index);
+				continue;
+			}
+			_ => {}
+		}
+		index += 1;
+	}
+	let last = items[start..].trim();
+	if !last.is_empty() {
+		values.push(last.to_string());
+	}
+	values
+}
+
+/// Blanks out `//` comments so commented-out code is not read.
+fn strip_comments(code: &str) -> String {
+	code.lines()
+		.map(|line| {
+			let mut index = 0;
+			let bytes = line.as_bytes();
+			while index < bytes.len() {
+				match bytes[index] {
+					b'"' => {
+						index = string_end(line, index);
+						continue;
+					}
+					b'\'' => {
+						index = char_end(line, index);
+						continue;
+					}
+					b'/' if bytes.get(index + 1) == Some(&b'/') => return &line[..index],
+					_ => {}
+				}
+				index += 1;
+			}
+			line
+		})
+		.collect::<Vec<_>>()
+		.join("\n")
+}
@@ -0,0 +1,262 @@
+//! Unit tests for the shared `zh-common` helpers.
+
+use aidoku::{
+	error::{AidokuError, AidokuErrorKind, NodeError},
+	testing::{logs, set_now},
+};
+use zh_common::{
+	chapter::{self, ChapterNumber},
+	convert::{self, Conversion},
+	date, entity,
+	error::Error,
+	parse,
+};
+
+fn number(chapter: Option<f32>, volume: Option<f32>, extra: bool) -> ChapterNumber {
+	ChapterNumber {
+		chapter,
+		volume,
+		extra,
+	}
+}
+
+#[test]
+fn chapter_arabic() {
+	assert_eq!(chapter::parse("第12话"), number(Some(12.0), None, false));
+	assert_eq!(
+		chapter::parse("第12.5话 重逢"),
+		number(Some(12.5), None, false)
+	);
+	assert_eq!(chapter::parse("第 3 回"), number(Some(3.0), None, false));
+	assert_eq!(chapter::parse("第25"), number(Some(25.0), None, false));
+	assert_eq!(chapter::parse("12話"), number(Some(12.0), None, false));
+}
+
+#[test]
+fn chapter_full_width() {
+	assert_eq!(chapter::parse("第１２話"), number(Some(12.0), None, false));
+	assert_eq!(chapter::parse("Ｃｈ．７"), number(Some(7.0), None, false));
+}
+
+#[test]
+fn chapter_chinese_numerals() {
+	assert_eq!(chapter::parse("第三卷"), number(None, Some(3.0), false));
+	assert_eq!(chapter::parse("第十二话"), number(Some(12.0), None, false));
+	assert_eq!(
+		chapter::parse("第一百零五話"),
+		number(Some(105.0), None, false)
+	);
+	assert_eq!(chapter::parse("第二十回"), number(Some(20.0), None, false));
+	assert_eq!(
+		chapter::parse("第贰拾叁话"),
+		number(Some(23.0), None, false)
+	);
+	assert_eq!(chapter::parse("一起去看海"), number(None, None, false));
+}
+
+#[test]
+fn chapter_with_volume() {
+	assert_eq!(
+		chapter::parse("第1卷 第3话"),
+		number(Some(3.0), Some(1.0), false)
+	);
+	assert_eq!(
+		chapter::parse("Vol.2 Ch.10"),
+		number(Some(10.0), Some(2.0), false)
+	);
+	assert_eq!(chapter::parse("volume 4"), number(None, Some(4.0), false));
+	assert_eq!(chapter::parse("第03冊"), number(None, Some(3.0), false));
+}
+
+#[test]
+fn chapter_bare_number() {
+	assert_eq!(chapter::parse("001"), number(Some(1.0), None, false));
+	assert_eq!(chapter::parse("12.5 重逢"), number(Some(12.5), None, false));
+	assert_eq!(chapter::parse("2024年新春特辑"), number(None, None, false));
+	assert_eq!(chapter::parse("重逢 12"), number(None, None, false));
+}
+
+#[test]
+fn chapter_extras() {
+	assert_eq!(chapter::parse("番外1"), number(Some(1.0), None, true));
+	assert_eq!(
+		chapter::parse("番外篇 第2话"),
+		number(Some(2.0), None, true)
+	);
+	assert_eq!(chapter::parse("特别篇"), number(None, None, true));
+	assert_eq!(chapter::parse("休刊公告"), number(None, None, true));
+	assert_eq!(chapter::parse("番外1").chapter(), -1.0);
+	assert_eq!(chapter::parse("第3卷 番外").volume(), 3.0);
+}
+
+/// 2025-01-01 08:00 in China Standard Time.
+const NOW: f64 = 1735689600.0;
+
+#[test]
+fn date_absolute() {
+	set_now(NOW);
+	assert_eq!(date::parse("2024-01-05"), 1704384000.0);
+	assert_eq!(date::parse("2024/01/05"), 1704384000.0);
+	assert_eq!(date::parse("2024年1月5日"), 1704384000.0);
+	assert_eq!(date::parse("(2024年01月05日 更新)"), 1704384000.0);
+	assert_eq!(date::parse("2024-01-05 12:30"), 1704429000.0);
+	assert_eq!(date::parse("2024-01-05T00:00:00.000Z"), 1704412800.0);
+	assert_eq!(date::parse("２０２４－０１－０５"), 1704384000.0);
+}
+
+#[test]
+fn date_without_year() {
+	set_now(NOW);
+	assert_eq!(date::parse("12-31"), 1735574400.0);
+	assert_eq!(date::parse("01月01日"), 1735660800.0);
+	assert_eq!(date::parse("01月05日"), 1704384000.0);
+}
+
+#[test]
+fn date_relative() {
+	set_now(NOW);
+	assert_eq!(date::parse("刚刚"), NOW);
+	assert_eq!(date::parse("5分钟前"), NOW - 300.0);
+	assert_eq!(date::parse("2小时前"), NOW - 7200.0);
+	assert_eq!(date::parse("2 小時前"), NOW - 7200.0);
+	assert_eq!(date::parse("3天前"), NOW - 259200.0);
+	assert_eq!(date::parse("三天前"), NOW - 259200.0);
+	assert_eq!(date::parse("1周前"), NOW - 604800.0);
+	assert_eq!(date::parse("今天"), 1735660800.0);
+	assert_eq!(date::parse("昨天 12:30"), 1735619400.0);
+	assert_eq!(date::parse("前天"), 1735488000.0);
+}
+
+#[test]
+fn date_unknown() {
+	set_now(NOW);
+	assert_eq!(date::parse(""), -1.0);
+	assert_eq!(date::parse("更新"), -1.0);
+	assert_eq!(date::parse("2024-13-05"), -1.0);
+}
+
+#[test]
+fn convert_characters() {
+	assert_eq!(convert::to_simplified("連載中 已完結"), "连载中 已完结");
+	assert_eq!(convert::to_traditional("连载中 已完结"), "連載中 已完結");
+	assert_eq!(convert::to_simplified("進擊的巨人"), "进击的巨人");
+	assert_eq!(convert::to_traditional("Vol.2 第3话"), "Vol.2 第3話");
+}
+
+#[test]
+fn convert_phrases() {
+	assert_eq!(convert::to_traditional("头发"), "頭髮");
+	assert_eq!(convert::to_traditional("发现"), "發現");
+	assert_eq!(convert::to_traditional("皇后回来以后"), "皇后回來以後");
+	assert_eq!(convert::to_traditional("你在干什么"), "你在幹什麼");
+	assert_eq!(convert::to_traditional("这里有一千里"), "這裡有一千里");
+	assert_eq!(convert::to_simplified("著名的乾隆"), "著名的乾隆");
+	assert_eq!(convert::to_simplified("看著乾淨"), "看着干净");
+}
+
+#[test]
+fn convert_keep() {
+	assert_eq!(Conversion::Keep.apply("連載中 连载中"), "連載中 连载中");
+}
+
+#[test]
+fn convert_variants() {
+	assert_eq!(convert::variants("海贼"), ["海贼", "海賊"]);
+	assert_eq!(convert::variants("進擊"), ["進擊", "进击"]);
+	assert_eq!(convert::variants("One Piece"), ["One Piece"]);
+}
+
+#[test]
+fn entity_named() {
+	assert_eq!(entity::decode("&lt;开始&gt; &amp; &hearts;"), "<开始> & ♥");
+	assert_eq!(entity::decode("&ldquo;约定&rdquo;&hellip;"), "“约定”…");
+	assert_eq!(entity::decode("a&nbsp;b"), "a\u{a0}b");
+	assert_eq!(entity::decode("&NotNestedGreaterGreater;"), "⪢\u{338}");
+}
+
+#[test]
+fn entity_numeric() {
+	assert_eq!(entity::decode("&#12300;夏日&#x300D;"), "「夏日」");
+	assert_eq!(entity::decode("&#40;1&#41;"), "(1)");
+	assert_eq!(entity::decode("&#X4E00"), "一");
+	assert_eq!(entity::decode("&#150;"), "–");
+	assert_eq!(
+		entity::decode("&#0;&#xD800;&#99999999999;"),
+		"\u{fffd}\u{fffd}\u{fffd}"
+	);
+}
+
+#[test]
+fn entity_legacy_and_invalid() {
+	assert_eq!(entity::decode("&amp &copy2024"), "& ©2024");
+	assert_eq!(entity::decode("&notit;"), "¬it;");
+	assert_eq!(
+		entity::decode("AT&T &unknown; &# &#x; &"),
+		"AT&T &unknown; &# &#x; &"
+	);
+	assert_eq!(entity::decode("&amp;lt;"), "&lt;");
+}
+
+#[test]
+fn chapter_number_after_entities() {
+	assert_eq!(chapter::parse("&#31532;12&#35805;").chapter(), 12.0);
+	assert_eq!(chapter::parse("第3话 &#40;上&#41;").chapter(), 3.0);
+}
+
+#[test]
+fn error_from_status() {
+	assert_eq!(Error::from_status(401), Some(Error::LoginRequired));
+	assert_eq!(Error::from_status(429), Some(Error::RateLimited));
+	assert_eq!(Error::from_status(451), Some(Error::RegionBlocked));
+	assert_eq!(Error::from_status(200), None);
+	assert_eq!(Error::from_status(404), None);
+}
+
+#[test]
+fn error_kinds_differ() {
+	let errors = [
+		Error::MarkupChanged("#chapters".into()),
+		Error::LoginRequired,
+		Error::RateLimited,
+		Error::RegionBlocked,
+		Error::Cloudflare,
+		Error::PaidChapter,
+		Error::ImageLimit,
+	];
+	for (index, error) in errors.iter().enumerate() {
+		for other in &errors[index + 1..] {
+			assert_ne!(error.kind(), other.kind(), "{:?} and {:?}", error, other);
+		}
+	}
+	let error: AidokuError = Error::PaidChapter.into();
+	assert_eq!(error.reason, Error::PaidChapter.kind());
+	assert_eq!(logs(), ["该章节需要付费，请在官方应用中购买后阅读"]);
+}
+
+#[test]
+fn missing_marker_is_reported() {
+	let error = parse::between("var ccx = '';", "var ccy = '", "'").unwrap_err();
+	assert_eq!(
+		error.reason,
+		AidokuErrorKind::NodeError(NodeError::ParseError)
+	);
+	assert_eq!(logs(), ["网站页面已改版，找不到 var ccy = '"]);
+	assert_eq!(parse::between("a(1)b", "(", ")").unwrap(), "1");
+}
+
+#[test]
+fn json_string() {
+	assert_eq!(parse::json_string("鬼灭"), r#""鬼灭""#);
+	assert_eq!(parse::json_string(r#"a"b\c"#), r#""a\"b\\c""#);
+	assert_eq!(parse::json_string("a\nb"), r#""a\u000ab""#);
+}
+
+#[test]
+fn http_date() {
+	assert_eq!(
+		date::http("Wed, 21 Oct 2015 07:28:00 GMT"),
+		Some(1445412480.0)
+	);
+	assert_eq!(date::http("Wed, 21 Oct 2015 07:28:00 CST"), None);
+	assert_eq!(date::http("120"), None);
+}
@@ -0,0 +1,126 @@
+//! Tests for the `check-manifests` consistency checker, and the check itself
+//! run over every source.
+
+use std::path::PathBuf;
+
+use zh_harness::manifest::{check_all, check_source};
+
+const SOURCE: &str = r#"{
+  "info": { "id": "zh.test" },
+  "listings": [{ "name": "日榜" }, { "name": "周榜" }]
+}"#;
+
+const FILTERS: &str = r#"[
+  { "type": "title" },
+  { "type": "select", "name": "题材", "options": ["全部", "爱情", "冒险"] },
+  { "type": "sort", "name": "排序", "options": ["热门", "更新"], "canAscend": true, "default": { "index": 0, "ascending": false } }
+]"#;
+
+const CODE: &str = r#"
+const FILTER_THEME: [&str; 3] = ["", "aiqing", "maoxian"];
+const FILTER_ORDER: [&str; 2] = ["popular", "datetime_updated"];
+
+fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
+	for filter in filters {
+		match filter.kind {
+			FilterType::Select => {
+				let index = filter.value.as_int()? as usize;
+				match filter.name.as_str() {
+					"题材" => {
+						theme = FILTER_THEME[index].to_string();
+					}
+					_ => continue,
+				}
+			}
+			FilterType::Sort => {
+				order = FILTER_ORDER[index];
+			}
+			_ => continue,
+		}
+	}
+}
+
+fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
+	match listing.name.as_str() {
+		"日榜" | "周榜" => {}
+		_ => {}
+	}
+}
+"#;
+
+#[test]
+fn consistent_source() {
+	assert!(check_source(SOURCE, FILTERS, CODE).is_empty());
+}
+
+#[test]
+fn option_count_mismatch() {
+	let code = CODE.replace(r#"["", "aiqing", "maoxian"]"#, r#"["", "aiqing"]"#);
+	let sort = FILTERS.replace(r#"["热门", "更新"]"#, r#"["热门"]"#);
+	assert_eq!(
+		check_source(SOURCE, &sort, &code),
+		[
+			"filter \"题材\" has 3 options but FILTER_THEME has 2 entries",
+			"filter \"排序\" has 1 options but FILTER_ORDER has 2 entries"
+		]
+	);
+}
+
+#[test]
+fn unhandled_listing() {
+	let code = CODE.replace(r#""日榜" | "周榜""#, r#""日榜""#);
+	assert_eq!(
+		check_source(SOURCE, FILTERS, &code),
+		["listing \"周榜\" has no arm in `match listing.name`"]
+	);
+}
+
+#[test]
+fn unknown_filter_name() {
+	let filters = FILTERS.replace("题材", "类型");
+	assert_eq!(
+		check_source(SOURCE, &filters, CODE),
+		[
+			"filter \"类型\" has no arm in `match filter.name`",
+			"`match filter.name` handles unknown filter \"题材\""
+		]
+	);
+}
+
+#[test]
+fn duplicate_ids() {
+	let code = CODE.replace(r#""maoxian""#, r#""aiqing""#);
+	assert_eq!(
+		check_source(SOURCE, FILTERS, &code),
+		["FILTER_THEME lists \"aiqing\" twice"]
+	);
+}
+
+#[test]
+fn ignores_commented_out_code() {
+	let code = CODE.replace(
+		r#""日榜" | "周榜" => {}"#,
+		"\"日榜\" => {}\n// \"周榜\" => {}",
+	);
+	assert_eq!(
+		check_source(SOURCE, FILTERS, &code),
+		["listing \"周榜\" has no arm in `match listing.name`"]
+	);
+}
+
+#[test]
+fn skips_deprecated_sources() {
+	let source =
+		r#"{ "info": { "id": "zh.test", "deprecated": true }, "listings": [{ "name": "日榜" }] }"#;
+	assert!(check_source(source, FILTERS, "").is_empty());
+}
+
+#[test]
+fn sources_match_manifests() {
+	let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+		.parent()
+		.unwrap()
+		.to_path_buf();
+	let problems = check_all(&root);
+	assert!(problems.is_empty(), "{}", problems.join("\n"));
+}
@@ -0,0 +1,86 @@
+use aidoku::{testing::*, MangaStatus};
+use aidoku_18mh as source;
+
+fn load() {
+	zh_harness::load("zh.18mh");
+}
+
+#[test]
+fn manga_list() {
+	load();
+	let result = source::get_manga_list(Vec::new(), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(result.manga[0].id, "secret-class");
+	assert_eq!(result.manga[0].title, "秘密教學");
+	assert_eq!(
+		result.manga[0].cover,
+		"https://static.18mh.org/covers/secret-class.jpg"
+	);
+}
+
+#[test]
+fn search() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("秘密")], 1).unwrap();
+	assert_eq!(result.manga[1].id, "sister-neighbors");
+}
+
+#[test]
+fn manga_listing() {
+	load();
+	let result = source::get_manga_listing(listing("人气推荐"), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+}
+
+#[test]
+fn manga_details() {
+	load();
+	let manga = source::get_manga_details("secret-class".into()).unwrap();
+	assert_eq!(manga.id, "secret-class/1024");
+	assert_eq!(manga.title, "秘密教學");
+	assert_eq!(manga.author, "安打, 金");
+	assert_eq!(manga.categories, ["韓漫", "劇情"]);
+	assert_eq!(manga.status, MangaStatus::Ongoing);
+	assert_eq!(manga.description, "子豪從小寄住在親戚家裡。");
+}
+
+#[test]
+fn chapter_list() {
+	load();
+	let chapters = source::get_chapter_list("secret-class/1024".into()).unwrap();
+	let ids = chapters.iter().map(|a| a.id.as_str()).collect::<Vec<_>>();
+	assert_eq!(ids, ["10002", "10001"]);
+	assert_eq!(chapters[1].title, "第1話");
+	assert_eq!(chapters[1].date_updated, 1704384000.0);
+	assert_eq!(chapters[1].url, "https://18mh.org/manga/secret-class/1");
+}
+
+#[test]
+fn page_list() {
+	load();
+	let pages = source::get_page_list("secret-class/1024".into(), "10001".into()).unwrap();
+	let urls = pages.iter().map(|a| a.url.as_str()).collect::<Vec<_>>();
+	assert_eq!(
+		urls,
+		[
+			"https://img.18mh.org/1024/10001/1.jpg",
+			"https://img.18mh.org/1024/10001/2.jpg"
+		]
+	);
+}
+
+#[test]
+fn handle_manga_url() {
+	load();
+	let link = source::handle_url("https://18mh.org/manga/secret-class".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "secret-class/1024");
+	assert!(link.chapter.is_none());
+}
+
+#[test]
+fn handle_chapter_url() {
+	load();
+	let link = source::handle_url("https://18mh.org/manga/secret-class/1".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "secret-class/1024");
+	assert_eq!(link.chapter.unwrap().id, "10001");
+}
@@ -0,0 +1,95 @@
+use aidoku::{testing::*, MangaStatus};
+use aidoku_bakamh as source;
+
+fn load() {
+	zh_harness::load("zh.bakamh");
+}
+
+#[test]
+fn manga_list() {
+	load();
+	let result = source::get_manga_list(Vec::new(), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(result.manga[0].id, "wuxian-shengji");
+	assert_eq!(result.manga[0].title, "无限升级");
+	assert_eq!(
+		result.manga[0].cover,
+		"https://bakamh.com/wp-content/uploads/wuxian-shengji.jpg"
+	);
+}
+
+#[test]
+fn search() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("无限")], 1).unwrap();
+	assert_eq!(result.manga.len(), 1);
+	assert_eq!(result.manga[0].id, "wuxian-shengji");
+	assert_eq!(
+		result.manga[0].cover,
+		"https://bakamh.com/wp-content/uploads/wuxian-shengji.jpg"
+	);
+}
+
+#[test]
+fn manga_listing() {
+	load();
+	let result = source::get_manga_listing(listing("新作"), 1).unwrap();
+	assert_eq!(result.manga[1].id, "xiaoyuan-meishi");
+}
+
+#[test]
+fn manga_details() {
+	load();
+	let manga = source::get_manga_details("wuxian-shengji".into()).unwrap();
+	assert_eq!(
+		manga.cover,
+		"https://bakamh.com/wp-content/uploads/wuxian-shengji.jpg"
+	);
+	assert_eq!(manga.author, "张三, 李四");
+	assert_eq!(manga.categories, ["热血", "修仙"]);
+	assert_eq!(manga.status, MangaStatus::Ongoing);
+	assert_eq!(manga.description, "少年意外获得升级系统。");
+}
+
+#[test]
+fn chapter_list() {
+	load();
+	let chapters = source::get_chapter_list("wuxian-shengji".into()).unwrap();
+	assert_eq!(chapters.len(), 2);
+	assert_eq!(chapters[0].id, "chapter-2");
+	assert_eq!(chapters[0].title, "第2话");
+	assert_eq!(chapters[0].chapter, 2.0);
+	assert_eq!(chapters[1].chapter, 1.0);
+	assert_eq!(chapters[0].date_updated, 1704988800.0);
+}
+
+#[test]
+fn page_list() {
+	load();
+	let pages = source::get_page_list("wuxian-shengji".into(), "chapter-1".into()).unwrap();
+	let urls = pages.iter().map(|a| a.url.as_str()).collect::<Vec<_>>();
+	assert_eq!(
+		urls,
+		[
+			"https://img.bakamh.com/wuxian-shengji/1/01.jpg",
+			"https://img.bakamh.com/wuxian-shengji/1/02.jpg"
+		]
+	);
+}
+
+#[test]
+fn handle_manga_url() {
+	load();
+	let link = source::handle_url("https://bakamh.com/manga/wuxian-shengji/".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "wuxian-shengji");
+	assert!(link.chapter.is_none());
+}
+
+#[test]
+fn handle_chapter_url() {
+	load();
+	let link =
+		source::handle_url("https://bakamh.com/manga/wuxian-shengji/chapter-1/".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "wuxian-shengji");
+	assert_eq!(link.chapter.unwrap().id, "chapter-1");
+}
@@ -0,0 +1,131 @@
+use aidoku::{std::net::Request, testing::*, MangaStatus};
+use aidoku_baozimh1 as source;
+
+fn load() {
+	zh_harness::load("zh.baozimh1");
+}
+
+#[test]
+fn manga_list() {
+	load();
+	let result = source::get_manga_list(Vec::new(), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+	assert!(result.has_more);
+	assert_eq!(result.manga[0].id, "yaoshenji-taxuedongman");
+	assert_eq!(
+		result.manga[0].cover,
+		"https://static-tw.baozimh.com/cover/yaoshenji-taxuedongman.jpg?w=285&h=375&q=100"
+	);
+}
+
+#[test]
+fn filtered_list() {
+	load();
+	let filters = vec![
+		select_filter("类型", 0),
+		select_filter("地区", 2),
+		select_filter("状态", 0),
+	];
+	let result = source::get_manga_list(filters, 1).unwrap();
+	assert_eq!(result.manga[1].title, "鬼滅之刃");
+}
+
+#[test]
+fn search() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("妖神")], 1).unwrap();
+	assert!(!result.has_more);
+	assert_eq!(result.manga.len(), 1);
+	assert_eq!(result.manga[0].id, "yaoshenji-taxuedongman");
+	assert_eq!(result.manga[0].title, "妖神記");
+}
+
+#[test]
+fn search_converts_query_to_traditional() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("妖神记")], 1).unwrap();
+	assert_eq!(result.manga[0].id, "yaoshenji-taxuedongman");
+	assert_eq!(
+		requests(),
+		["GET https://www.baozimh.com/search/?q=%E5%A6%96%E7%A5%9E%E8%A8%98"]
+	);
+}
+
+#[test]
+fn manga_details() {
+	load();
+	let manga = source::get_manga_details("yaoshenji-taxuedongman".into()).unwrap();
+	assert_eq!(manga.id, "yaoshenji-taxuedongman");
+	assert_eq!(manga.title, "妖神記");
+	assert_eq!(manga.author, "發飆的蝸牛");
+	assert_eq!(
+		manga.description,
+		"聶離重生回到了少年時代, 這一世他要守護所愛之人"
+	);
+	assert_eq!(manga.categories, ["玄幻", "熱血"]);
+	assert_eq!(manga.status, MangaStatus::Ongoing);
+}
+
+#[test]
+fn chapter_list() {
+	load();
+	let chapters = source::get_chapter_list("yaoshenji-taxuedongman".into()).unwrap();
+	let ids = chapters.iter().map(|a| a.id.as_str()).collect::<Vec<_>>();
+	assert_eq!(ids, ["0_1", "0_0"]);
+	assert_eq!(chapters[1].title, "第1話 重生");
+	assert_eq!(chapters[0].date_updated, 1704988800.0);
+	assert_eq!(chapters[1].date_updated, -1.0);
+	assert_eq!(
+		chapters[1].url,
+		"https://www.baozimh.com/comic/chapter/yaoshenji-taxuedongman/0_0.html"
+	);
+}
+
+#[test]
+fn page_list() {
+	load();
+	let pages = source::get_page_list("yaoshenji-taxuedongman".into(), "0_0".into()).unwrap();
+	assert_eq!(pages.len(), 3);
+	assert_eq!(
+		pages[2].url,
+		"https://s1.baozimh.com/scomic/yaoshenji-taxuedongman/0_0/3.jpg"
+	);
+}
+
+#[test]
+fn handle_manga_url() {
+	load();
+	let link =
+		source::handle_url("https://cn.baozimh.com/comic/yaoshenji-taxuedongman".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "yaoshenji-taxuedongman");
+	assert!(link.chapter.is_none());
+}
+
+#[test]
+fn handle_chapter_url() {
+	load();
+	let link = source::handle_url(
+		"https://www.baozimh.com/comic/chapter/yaoshenji-taxuedongman/0_1.html".into(),
+	)
+	.unwrap();
+	assert_eq!(link.manga.unwrap().id, "yaoshenji-taxuedongman");
+	assert_eq!(link.chapter.unwrap().id, "0_1");
+}
+
+#[test]
+fn handle_page_direct_url() {
+	load();
+	let link = source::handle_url("https://tw.baozimh.com/user/page_direct?comic_id=yaoshenji-taxuedongman&section_slot=0&chapter_slot=1".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "yaoshenji-taxuedongman");
+	assert_eq!(link.chapter.unwrap().id, "0_1");
+}
+
+#[test]
+fn image_request() {
+	load();
+	set_default("cf_cookie", "cf_clearance=abc123");
+	let request = Request::get("https://static-tw.baozimh.com/scomic/yiquanchaoren/0/1-abcd/1.jpg");
+	source::modify_image_request(request.clone());
+	let cookie = ("Cookie".into(), "cf_clearance=abc123".into());
+	assert!(request.headers().contains(&cookie));
+}
@@ -0,0 +1,104 @@
+use aidoku::testing::*;
+use aidoku_baozimh2 as source;
+
+fn load() {
+	zh_harness::load("zh.baozimh2");
+}
+
+#[test]
+fn manga_list() {
+	load();
+	let result = source::get_manga_list(Vec::new(), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(result.manga[0].id, "wudongqiankun");
+	assert_eq!(
+		result.manga[0].cover,
+		"https://cover.g-mh.online/wudongqiankun.jpg"
+	);
+	assert_eq!(
+		result.manga[1].cover,
+		"https://cover.g-mh.online/doupo-cangqiong.jpg"
+	);
+}
+
+#[test]
+fn search() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("武动")], 1).unwrap();
+	assert_eq!(result.manga[1].title, "斗破苍穹");
+}
+
+#[test]
+fn manga_listing() {
+	load();
+	let result = source::get_manga_listing(listing("人气推荐"), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+}
+
+#[test]
+fn domain_setting() {
+	load();
+	set_default("domain", "baozimh.net");
+	source::get_manga_listing(listing("人气推荐"), 1).unwrap();
+	assert_eq!(requests(), ["GET https://baozimh.net/hots/page/1"]);
+	assert_eq!(
+		request_headers()[0]
+			.iter()
+			.find(|(key, _)| key == "Referer")
+			.map(|(_, value)| value.as_str()),
+		Some("https://baozimh.net")
+	);
+}
+
+#[test]
+fn manga_details() {
+	load();
+	let manga = source::get_manga_details("wudongqiankun".into()).unwrap();
+	assert_eq!(manga.id, "wudongqiankun/2048");
+	assert_eq!(manga.title, "武动乾坤");
+	assert_eq!(manga.cover, "https://cover.g-mh.online/wudongqiankun.jpg");
+	assert_eq!(manga.author, "天蚕土豆, 漫画工作室");
+	assert_eq!(manga.categories, ["国漫", "玄幻"]);
+	assert_eq!(manga.description, "林动在炎城林家长大。");
+}
+
+#[test]
+fn chapter_list() {
+	load();
+	let chapters = source::get_chapter_list("wudongqiankun/2048".into()).unwrap();
+	let ids = chapters.iter().map(|a| a.id.as_str()).collect::<Vec<_>>();
+	assert_eq!(ids, ["30002", "30001"]);
+	assert_eq!(chapters[1].title, "第1话 林动");
+	assert_eq!(chapters[1].date_updated, 1704412800.0);
+	assert_eq!(chapters[1].url, "https://baozimh.org/manga/wudongqiankun/1");
+}
+
+#[test]
+fn page_list() {
+	load();
+	let pages = source::get_page_list("wudongqiankun/2048".into(), "30001".into()).unwrap();
+	let urls = pages.iter().map(|a| a.url.as_str()).collect::<Vec<_>>();
+	assert_eq!(
+		urls,
+		[
+			"https://f40-1-4.g-mh.online/scomic/wudongqiankun/1/01.jpg",
+			"https://f40-1-4.g-mh.online/scomic/wudongqiankun/1/02.jpg"
+		]
+	);
+}
+
+#[test]
+fn handle_manga_url() {
+	load();
+	let link = source::handle_url("https://baozimh.org/manga/wudongqiankun".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "wudongqiankun/2048");
+	assert!(link.chapter.is_none());
+}
+
+#[test]
+fn handle_chapter_url() {
+	load();
+	let link = source::handle_url("https://baozimh.org/manga/wudongqiankun/1".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "wudongqiankun/2048");
+	assert_eq!(link.chapter.unwrap().id, "30001");
+}
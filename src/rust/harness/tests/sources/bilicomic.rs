@@ -0,0 +1,99 @@
+use aidoku::{testing::*, MangaStatus};
+use aidoku_bilicomic as source;
+
+fn load() {
+	zh_harness::load("zh.bilicomic");
+}
+
+#[test]
+fn manga_list() {
+	load();
+	let result = source::get_manga_list(Vec::new(), 1).unwrap();
+	assert!(result.has_more);
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(result.manga[0].id, "1001");
+	assert_eq!(result.manga[0].title, "葬送的芙莉莲");
+	assert_eq!(
+		result.manga[0].cover,
+		"https://img.bilimanga.net/cover/1001.jpg"
+	);
+}
+
+#[test]
+fn search() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("间谍")], 1).unwrap();
+	assert!(!result.has_more);
+	assert_eq!(result.manga.len(), 2);
+}
+
+#[test]
+fn search_single_result() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("芙莉莲")], 1).unwrap();
+	assert_eq!(result.manga.len(), 1);
+	assert_eq!(result.manga[0].id, "1001");
+	assert_eq!(result.manga[0].title, "葬送的芙莉蓮");
+}
+
+#[test]
+fn manga_listing() {
+	load();
+	let result = source::get_manga_listing(listing("月点击榜"), 1).unwrap();
+	assert!(!result.has_more);
+	assert_eq!(result.manga.len(), 2);
+}
+
+#[test]
+fn manga_details() {
+	load();
+	let manga = source::get_manga_details("1001".into()).unwrap();
+	assert_eq!(manga.title, "葬送的芙莉蓮");
+	assert_eq!(manga.author, "山田鐘人, 阿部司");
+	assert_eq!(manga.description, "勇者一行人打倒魔王之後的故事。");
+	assert_eq!(manga.categories, ["奇幻", "冒險"]);
+	assert_eq!(manga.status, MangaStatus::Ongoing);
+}
+
+#[test]
+fn chapter_list() {
+	load();
+	let chapters = source::get_chapter_list("1001".into()).unwrap();
+	assert_eq!(chapters.len(), 2);
+	assert_eq!(chapters[0].id, "50002");
+	assert_eq!(chapters[0].title, "第2話 僧侶的謊言");
+	assert_eq!(
+		chapters[0].url,
+		"https://www.bilimanga.net/read/1001/50002.html"
+	);
+}
+
+#[test]
+fn page_list() {
+	load();
+	let pages = source::get_page_list("1001".into(), "50001".into()).unwrap();
+	let urls = pages.iter().map(|a| a.url.as_str()).collect::<Vec<_>>();
+	assert_eq!(
+		urls,
+		[
+			"https://img.bilimanga.net/1001/50001/01.webp",
+			"https://img.bilimanga.net/1001/50001/02.webp"
+		]
+	);
+}
+
+#[test]
+fn handle_manga_url() {
+	load();
+	let link = source::handle_url("https://www.bilimanga.net/detail/1001.html".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "1001");
+	assert!(link.chapter.is_none());
+}
+
+#[test]
+fn handle_chapter_url() {
+	load();
+	let link = source::handle_url("https://www.bilimanga.net/read/1001/50002.html".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "1001");
+	assert_eq!(link.chapter.unwrap().id, "50002");
+}
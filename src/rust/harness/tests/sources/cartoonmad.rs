@@ -0,0 +1,114 @@
+use aidoku::testing::*;
+use aidoku_cartoonmad as source;
+
+fn load() {
+	zh_harness::load("zh.cartoonmad");
+}
+
+#[test]
+fn manga_list() {
+	load();
+	let result = source::get_manga_list(Vec::new(), 1).unwrap();
+	assert!(result.has_more);
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(result.manga[0].id, "1152");
+	assert_eq!(result.manga[0].title, "進擊的巨人");
+	assert_eq!(
+		result.manga[0].cover,
+		"https://www.cartoonmad.com/cartoonimg/coimg/1152.jpg"
+	);
+}
+
+#[test]
+fn search() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("進擊")], 1).unwrap();
+	assert!(!result.has_more);
+	assert_eq!(result.manga[1].title, "海賊王");
+}
+
+#[test]
+fn search_converts_query_to_traditional() {
+	load();
+	source::get_manga_list(vec![title_filter("进击")], 1).unwrap();
+	assert_eq!(
+		requests(),
+		["GET https://www.cartoonmad.com/m/?keyword=%E9%80%B2%E6%93%8A"]
+	);
+}
+
+#[test]
+fn manga_listing() {
+	load();
+	let result = source::get_manga_listing(listing("热门连载"), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+}
+
+#[test]
+fn manga_details() {
+	load();
+	let manga = source::get_manga_details("1152".into()).unwrap();
+	assert_eq!(manga.title, "進擊的巨人");
+	assert_eq!(
+		manga.cover,
+		"https://www.cartoonmad.com/cartoonimg/coimg/1152.jpg"
+	);
+	assert_eq!(manga.author, "諫山創");
+	assert_eq!(manga.description, "巨人吞食人類的世界。");
+	assert_eq!(manga.categories, ["熱血", "奇幻"]);
+}
+
+#[test]
+fn manga_details_simplified() {
+	load();
+	set_default("conversion", "1");
+	let manga = source::get_manga_details("1152".into()).unwrap();
+	assert_eq!(manga.title, "进击的巨人");
+	assert_eq!(manga.author, "谏山创");
+	assert_eq!(manga.description, "巨人吞食人类的世界。");
+	assert_eq!(manga.categories, ["热血", "奇幻"]);
+	let chapters = source::get_chapter_list("1152".into()).unwrap();
+	assert_eq!(chapters[0].title, "第 002 话");
+}
+
+#[test]
+fn chapter_list() {
+	load();
+	let chapters = source::get_chapter_list("1152".into()).unwrap();
+	assert_eq!(chapters.len(), 2);
+	assert_eq!(chapters[0].id, "115200022001001");
+	assert_eq!(chapters[0].title, "第 002 話");
+	assert_eq!(chapters[0].chapter, 2.0);
+}
+
+#[test]
+fn page_list() {
+	load();
+	let pages = source::get_page_list("1152".into(), "115200012001001".into()).unwrap();
+	let urls = pages.iter().map(|a| a.url.as_str()).collect::<Vec<_>>();
+	assert_eq!(
+		urls,
+		[
+			"https://web3.cartoonmad.com/c1s2f3/1152/001/001.jpg",
+			"https://web3.cartoonmad.com/c1s2f3/1152/001/002.jpg",
+			"https://web3.cartoonmad.com/c1s2f3/1152/001/003.jpg"
+		]
+	);
+}
+
+#[test]
+fn handle_manga_url() {
+	load();
+	let link = source::handle_url("https://www.cartoonmad.com/comic/1152.html".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "1152");
+	assert!(link.chapter.is_none());
+}
+
+#[test]
+fn handle_chapter_url() {
+	load();
+	let link = source::handle_url("https://www.cartoonmad.com/m/comic/115200022001001.html".into())
+		.unwrap();
+	assert_eq!(link.manga.unwrap().id, "1152");
+	assert_eq!(link.chapter.unwrap().id, "115200022001001");
+}
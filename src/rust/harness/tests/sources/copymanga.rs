@@ -0,0 +1,326 @@
+use aidoku::{
+	error::{AidokuErrorKind, NodeError},
+	testing::*,
+	MangaContentRating, MangaStatus, MangaViewer,
+};
+use aidoku_copymanga as source;
+
+fn load() {
+	zh_harness::load("zh.copymanga");
+}
+
+#[test]
+fn manga_list() {
+	load();
+	let result = source::get_manga_list(Vec::new(), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+	assert!(result.has_more);
+
+	let manga = &result.manga[1];
+	assert_eq!(manga.id, "yaoshenji");
+	assert_eq!(manga.title, "妖神记");
+	assert_eq!(manga.author, "发飙的蜗牛, 漫画A");
+	assert!(manga.artist.is_empty());
+	assert_eq!(manga.description, "簡介 妖神记");
+	assert_eq!(manga.categories, ["奇幻"]);
+	assert_eq!(manga.status, MangaStatus::Completed);
+	assert_eq!(manga.nsfw, MangaContentRating::Suggestive);
+}
+
+#[test]
+fn search() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("海賊")], 1).unwrap();
+	let ids = result
+		.manga
+		.iter()
+		.map(|a| a.id.as_str())
+		.collect::<Vec<_>>();
+	assert_eq!(ids, ["haizeiwang"]);
+	assert!(!result.has_more);
+	assert_eq!(
+		requests(),
+		["GET https://api.mangacopy.com/api/v3/search/comic?q=%E6%B5%B7%E8%B4%BC&q_type=&limit=20&offset=0"]
+	);
+}
+
+#[test]
+fn search_by_translator() {
+	load();
+	let filters = vec![title_filter("海贼"), select_filter("搜索类型", 3)];
+	let result = source::get_manga_list(filters, 1).unwrap();
+	assert_eq!(result.manga.len(), 1);
+	assert!(requests().iter().all(|a| a.contains("&q_type=local&")));
+}
+
+#[test]
+fn author_manga_list() {
+	load();
+	let result = source::get_manga_list(vec![author_filter("尾田榮一郎")], 1).unwrap();
+	let ids = result
+		.manga
+		.iter()
+		.map(|a| a.id.as_str())
+		.collect::<Vec<_>>();
+	assert_eq!(ids, ["haizeiwang", "haizeiwangfanwai"]);
+	assert!(result.has_more);
+	assert_eq!(
+		requests().last().unwrap(),
+		"GET https://api.mangacopy.com/api/v3/comics?author=weitianrongyilang&ordering=&limit=50&offset=0"
+	);
+}
+
+#[test]
+fn manga_listing() {
+	load();
+	let result = source::get_manga_listing(listing("日榜"), 1).unwrap();
+	let ids = result
+		.manga
+		.iter()
+		.map(|a| a.id.as_str())
+		.collect::<Vec<_>>();
+	assert_eq!(ids, ["yaoshenji", "haizeiwang"]);
+}
+
+#[test]
+fn manga_details() {
+	load();
+	let manga = source::get_manga_details("haizeiwang".into()).unwrap();
+	assert_eq!(manga.title, "海贼王");
+	assert_eq!(manga.author, "尾田荣一郎");
+	assert_eq!(manga.artist, "尾田荣一郎");
+	assert!(manga.cover.ends_with("/haizeiwang/cover/1.jpg.328x422.jpg"));
+	assert_eq!(manga.categories, ["冒險", "熱血"]);
+	assert_eq!(manga.status, MangaStatus::Ongoing);
+	assert_eq!(manga.nsfw, MangaContentRating::Suggestive);
+	assert_eq!(manga.viewer, MangaViewer::Rtl);
+	assert!(manga.description.starts_with("有個男人"));
+	assert_eq!(
+		requests(),
+		["GET https://api.mangacopy.com/api/v3/comic2/haizeiwang"]
+	);
+}
+
+#[test]
+fn korean_manga_details_scroll() {
+	load();
+	let manga = source::get_manga_details("woduzishengji".into()).unwrap();
+	assert_eq!(manga.author, "Chugong, DUBU");
+	assert_eq!(manga.status, MangaStatus::Completed);
+	assert_eq!(manga.nsfw, MangaContentRating::Safe);
+	assert_eq!(manga.viewer, MangaViewer::Scroll);
+}
+
+#[test]
+fn manga_details_reports_missing_comic() {
+	load();
+	assert!(source::get_manga_details("moved".into()).is_err());
+	assert_eq!(logs(), ["网站页面已改版，找不到 comic"]);
+}
+
+#[test]
+fn chapter_list() {
+	load();
+	let chapters = source::get_chapter_list("haizeiwang".into()).unwrap();
+	let titles = chapters
+		.iter()
+		.map(|a| (a.scanlator.as_str(), a.title.as_str()))
+		.collect::<Vec<_>>();
+	assert_eq!(
+		titles,
+		[
+			("單行本", "02"),
+			("單行本", "第01卷"),
+			("海賊漢化組", "第1話"),
+			("默認", "第2話"),
+			("默認", "第1話")
+		]
+	);
+	assert_eq!(chapters[0].volume, 2.0);
+	assert_eq!(chapters[0].chapter, -1.0);
+	assert_eq!(chapters[1].volume, 1.0);
+	assert_eq!(chapters[1].chapter, -1.0);
+	assert_eq!(chapters[3].chapter, 2.0);
+	assert_eq!(chapters[3].volume, -1.0);
+	assert!(chapters.iter().all(|a| a.date_updated > 0.0));
+	assert_eq!(
+		chapters[4].url,
+		"https://www.mangacopy.com/comic/haizeiwang/chapter/8a1c4a2e-5d6b-11ee-9d0a-00163e0ca5bd"
+	);
+}
+
+#[test]
+fn page_list() {
+	load();
+	let pages = source::get_page_list(
+		"haizeiwang".into(),
+		"8a1c4a2e-5d6b-11ee-9d0a-00163e0ca5bd".into(),
+	)
+	.unwrap();
+	assert_eq!(pages.len(), 3);
+	assert_eq!(pages[0].index, 0);
+	assert!(pages[2].url.ends_with("/03.jpg.c800x.webp"));
+}
+
+#[test]
+fn page_list_in_chosen_quality() {
+	load();
+	set_default("image_quality", "highest");
+	set_default("image_format", "jpg");
+	let pages = source::get_page_list(
+		"haizeiwang".into(),
+		"8a1c4a2e-5d6b-11ee-9d0a-00163e0ca5bd".into(),
+	)
+	.unwrap();
+	assert_eq!(
+		pages[0].url,
+		"https://hi77-overseas.mangafuna.xyz/haizeiwang/8a1c4a2e/01.jpg.c1500x.jpg"
+	);
+
+	source::get_manga_listing(listing("日榜"), 1).unwrap();
+	let jpeg = ("webp".into(), "0".into());
+	assert!(request_headers().last().unwrap().contains(&jpeg));
+}
+
+#[test]
+fn page_list_reports_missing_key() {
+	load();
+	assert!(source::get_page_list("haizeiwang".into(), "moved".into()).is_err());
+	assert_eq!(logs(), ["网站页面已改版，找不到 var ccy = '"]);
+}
+
+#[test]
+fn listing_reports_rate_limit() {
+	load();
+	let error = source::get_manga_listing(listing("周榜"), 1).unwrap_err();
+	assert_eq!(error.reason, AidokuErrorKind::NodeError(NodeError::ModifyError));
+	assert_eq!(requests().len(), 3);
+	assert_eq!(now(), DEFAULT_NOW + 10.0);
+	assert_eq!(logs(), ["请求过于频繁，请稍后再试"]);
+
+	source::get_manga_listing(listing("日榜"), 1).unwrap();
+	assert_eq!(now(), DEFAULT_NOW + 10.0);
+}
+
+#[test]
+fn retries_gateway_error() {
+	load();
+	let result = source::get_manga_listing(listing("月榜"), 1).unwrap();
+	assert!(!result.manga.is_empty());
+	assert_eq!(requests().len(), 2);
+	assert_eq!(now(), DEFAULT_NOW + 1.0);
+}
+
+#[test]
+fn bookshelf_signs_in_without_token() {
+	load();
+	set_default("username", "copyuser");
+	set_default("password", "secret");
+	let result = source::get_manga_listing(listing("我的书架"), 1).unwrap();
+	let ids = result
+		.manga
+		.iter()
+		.map(|a| a.id.as_str())
+		.collect::<Vec<_>>();
+	assert_eq!(ids, ["haizeiwang"]);
+	assert!(!result.has_more);
+	assert_eq!(
+		requests(),
+		[
+			"POST https://api.mangacopy.com/api/v3/login",
+			"GET https://api.mangacopy.com/api/v3/member/collect/comics?limit=30&offset=0&free_type=1&ordering=-datetime_modifier"
+		]
+	);
+	let authorization = ("authorization".into(), "Token token-from-login".into());
+	assert!(request_headers()[1].contains(&authorization));
+	let token = default_value("token").unwrap();
+	assert_eq!(token.as_string().unwrap().read(), "token-from-login");
+}
+
+#[test]
+fn history_signs_in_again_when_token_expired() {
+	load();
+	set_default("username", "copyuser");
+	set_default("password", "secret");
+	set_default("token", "expired");
+	let result = source::get_manga_listing(listing("浏览历史"), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(
+		requests(),
+		[
+			"GET https://api.mangacopy.com/api/v3/member/browse/comics?limit=30&offset=0&free_type=1",
+			"POST https://api.mangacopy.com/api/v3/login",
+			"GET https://api.mangacopy.com/api/v3/member/browse/comics?limit=30&offset=0&free_type=1"
+		]
+	);
+}
+
+#[test]
+fn bookshelf_asks_to_log_in_without_credentials() {
+	load();
+	let error = source::get_manga_listing(listing("我的书架"), 1).unwrap_err();
+	assert_eq!(error.reason, AidokuErrorKind::DefaultNotFound);
+	assert!(requests().is_empty());
+	assert_eq!(logs(), ["需要登录，请在设置中填写账号密码"]);
+}
+
+#[test]
+fn bookshelf_reports_rejected_password() {
+	load();
+	set_default("username", "wrong");
+	set_default("password", "secret");
+	let error = source::get_manga_listing(listing("我的书架"), 1).unwrap_err();
+	assert_eq!(error.reason, AidokuErrorKind::DefaultNotFound);
+	assert_eq!(requests(), ["POST https://api.mangacopy.com/api/v3/login"]);
+}
+
+#[test]
+fn sends_saved_token_to_api() {
+	load();
+	set_default("token", "saved");
+	source::get_manga_listing(listing("日榜"), 1).unwrap();
+	let authorization = ("authorization".into(), "Token saved".into());
+	assert!(request_headers()[0].contains(&authorization));
+}
+
+#[test]
+fn drops_rejected_token_without_credentials() {
+	load();
+	set_default("token", "stale");
+	let result = source::get_manga_listing(listing("日榜"), 1).unwrap();
+	assert!(!result.manga.is_empty());
+	assert_eq!(
+		requests(),
+		[
+			"GET https://api.mangacopy.com/api/v3/ranks?date_type=day&limit=30&offset=0",
+			"GET https://api.mangacopy.com/api/v3/ranks?date_type=day&limit=30&offset=0"
+		]
+	);
+	assert!(!request_headers()[1].iter().any(|(key, _)| key == "authorization"));
+	let token = default_value("token").unwrap();
+	assert_eq!(token.as_string().unwrap().read(), "");
+	assert!(logs().is_empty());
+}
+
+#[test]
+fn handle_manga_url() {
+	load();
+	let link = source::handle_url("https://www.copymanga.tv/comic/haizeiwang".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "haizeiwang");
+	assert!(link.chapter.is_none());
+}
+
+#[test]
+fn handle_chapter_url() {
+	load();
+	let link = source::handle_url(
+		"https://www.mangacopy.com/comic/haizeiwang/chapter/8a1c4a2e-5d6b-11ee-9d0a-00163e0ca5bd"
+			.into(),
+	)
+	.unwrap();
+	assert_eq!(link.manga.unwrap().id, "haizeiwang");
+	assert_eq!(
+		link.chapter.unwrap().id,
+		"8a1c4a2e-5d6b-11ee-9d0a-00163e0ca5bd"
+	);
+}
@@ -0,0 +1,117 @@
+use aidoku::{std::net::Request, testing::*};
+use aidoku_godamanga as source;
+
+fn load() {
+	zh_harness::load("zh.godamanga");
+}
+
+#[test]
+fn manga_list() {
+	load();
+	let result = source::get_manga_list(Vec::new(), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(result.manga[0].id, "wudongqiankun");
+	assert_eq!(
+		result.manga[0].cover,
+		"https://cover.g-mh.online/wudongqiankun.jpg"
+	);
+	assert_eq!(
+		result.manga[1].cover,
+		"https://cover.g-mh.online/doupo-cangqiong.jpg"
+	);
+}
+
+#[test]
+fn search() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("武动")], 1).unwrap();
+	assert_eq!(result.manga[1].title, "斗破苍穹");
+}
+
+#[test]
+fn manga_listing() {
+	load();
+	let result = source::get_manga_listing(listing("人气推荐"), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+}
+
+#[test]
+fn manga_details() {
+	load();
+	let manga = source::get_manga_details("wudongqiankun".into()).unwrap();
+	assert_eq!(manga.id, "wudongqiankun/2048");
+	assert_eq!(manga.title, "武动乾坤");
+	assert_eq!(manga.cover, "https://cover.g-mh.online/wudongqiankun.jpg");
+	assert_eq!(manga.author, "天蚕土豆, 漫画工作室");
+	assert_eq!(manga.categories, ["国漫", "玄幻"]);
+	assert_eq!(manga.description, "林动在炎城林家长大。");
+}
+
+#[test]
+fn chapter_list() {
+	load();
+	let chapters = source::get_chapter_list("wudongqiankun/2048".into()).unwrap();
+	let ids = chapters.iter().map(|a| a.id.as_str()).collect::<Vec<_>>();
+	assert_eq!(ids, ["30002", "30001"]);
+	assert_eq!(chapters[1].title, "第1话 林动");
+	assert_eq!(chapters[1].date_updated, 1704412800.0);
+	assert_eq!(chapters[1].url, "https://godamh.com/manga/wudongqiankun/1");
+}
+
+#[test]
+fn page_list() {
+	load();
+	let pages = source::get_page_list("wudongqiankun/2048".into(), "30001".into()).unwrap();
+	let urls = pages.iter().map(|a| a.url.as_str()).collect::<Vec<_>>();
+	assert_eq!(
+		urls,
+		[
+			"https://f40-1-4.g-mh.online/scomic/wudongqiankun/1/01.jpg",
+			"https://f40-1-4.g-mh.online/scomic/wudongqiankun/1/02.jpg"
+		]
+	);
+}
+
+#[test]
+fn reuses_clearance_cookies() {
+	load();
+	source::get_manga_listing(listing("人气推荐"), 1).unwrap();
+	source::get_manga_details("wudongqiankun".into()).unwrap();
+	let cookie = ("Cookie".into(), "cf_clearance=abc123; __cf_bm=bm456".into());
+	assert!(!request_headers()[0].contains(&cookie));
+	assert!(request_headers()[1].contains(&cookie));
+
+	let request = Request::get("https://cover.g-mh.online/wudongqiankun.jpg");
+	source::modify_image_request(request.clone());
+	assert!(request.headers().contains(&cookie));
+}
+
+#[test]
+fn uses_web_view_cookie_after_challenge() {
+	load();
+	set_default("cf_cookie", "cf_clearance=stale");
+	assert!(source::get_manga_list(vec![title_filter("斗破")], 1).is_err());
+	assert_eq!(logs(), ["需要通过 Cloudflare 验证，请在网页中打开"]);
+
+	set_webview_cookie("godamh.com", "cf_clearance=fresh");
+	let result = source::get_manga_list(vec![title_filter("斗破")], 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+	let cookie = ("Cookie".into(), "cf_clearance=fresh".into());
+	assert!(request_headers()[1].contains(&cookie));
+}
+
+#[test]
+fn handle_manga_url() {
+	load();
+	let link = source::handle_url("https://godamh.com/manga/wudongqiankun".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "wudongqiankun/2048");
+	assert!(link.chapter.is_none());
+}
+
+#[test]
+fn handle_chapter_url() {
+	load();
+	let link = source::handle_url("https://godamh.com/manga/wudongqiankun/1".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "wudongqiankun/2048");
+	assert_eq!(link.chapter.unwrap().id, "30001");
+}
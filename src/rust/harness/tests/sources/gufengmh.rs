@@ -0,0 +1,100 @@
+use aidoku::{testing::*, MangaStatus};
+use aidoku_gufengmh as source;
+
+fn load() {
+	zh_harness::load("zh.gufengmh");
+}
+
+#[test]
+fn manga_list() {
+	load();
+	let result = source::get_manga_list(Vec::new(), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(result.manga[0].id, "yirenzhixia");
+	assert_eq!(result.manga[0].title, "一人之下");
+	assert_eq!(
+		result.manga[0].cover,
+		"https://res.xiaoqinre.com/images/cover/yirenzhixia.jpg"
+	);
+}
+
+#[test]
+fn filtered_manga_list() {
+	load();
+	let filters = vec![
+		select_filter("地区", 1),
+		select_filter("进度", 2),
+		sort_filter("排序", 1, true),
+	];
+	let result = source::get_manga_list(filters, 2).unwrap();
+	assert_eq!(result.manga.len(), 2);
+}
+
+#[test]
+fn search() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("一人")], 1).unwrap();
+	assert_eq!(result.manga[1].title, "武动乾坤");
+}
+
+#[test]
+fn manga_listing() {
+	load();
+	let result = source::get_manga_listing(listing("日人气榜"), 1).unwrap();
+	assert!(!result.has_more);
+	assert_eq!(result.manga.len(), 2);
+}
+
+#[test]
+fn manga_details() {
+	load();
+	let manga = source::get_manga_details("yirenzhixia".into()).unwrap();
+	assert_eq!(manga.title, "一人之下");
+	assert_eq!(manga.author, "米二");
+	assert_eq!(manga.description, "异人界的故事。");
+	assert_eq!(manga.categories, ["热血", "冒险"]);
+	assert_eq!(manga.status, MangaStatus::Ongoing);
+}
+
+#[test]
+fn chapter_list() {
+	load();
+	let chapters = source::get_chapter_list("yirenzhixia".into()).unwrap();
+	assert_eq!(chapters.len(), 2);
+	assert_eq!(chapters[0].id, "1002");
+	assert_eq!(chapters[0].title, "第2话");
+	assert_eq!(chapters[0].chapter, 2.0);
+	assert_eq!(chapters[0].date_updated, 1704988800.0);
+	assert_eq!(chapters[1].date_updated, -1.0);
+}
+
+#[test]
+fn page_list() {
+	load();
+	let pages = source::get_page_list("yirenzhixia".into(), "1001".into()).unwrap();
+	let urls = pages.iter().map(|a| a.url.as_str()).collect::<Vec<_>>();
+	assert_eq!(
+		urls,
+		[
+			"https://res1.xiaoqinre.com/images/comic/12/1001/0001.jpg",
+			"https://res1.xiaoqinre.com/images/comic/12/1001/0002.jpg"
+		]
+	);
+}
+
+#[test]
+fn handle_manga_url() {
+	load();
+	let link = source::handle_url("https://www.gufengmh.com/manhua/yirenzhixia/".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "yirenzhixia");
+	assert!(link.chapter.is_none());
+}
+
+#[test]
+fn handle_chapter_url() {
+	load();
+	let link =
+		source::handle_url("https://www.gufengmh.com/manhua/yirenzhixia/1002.html".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "yirenzhixia");
+	assert_eq!(link.chapter.unwrap().id, "1002");
+}
@@ -0,0 +1,86 @@
+use aidoku::{testing::*, MangaContentRating};
+use aidoku_hanime1 as source;
+
+fn load() {
+	zh_harness::load("zh.hanime1");
+}
+
+#[test]
+fn manga_list() {
+	load();
+	let result = source::get_manga_list(Vec::new(), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(result.manga[0].id, "102938");
+	assert_eq!(result.manga[0].title, "夏日的回忆");
+	assert_eq!(
+		result.manga[0].cover,
+		"https://t.nhentai.net/galleries/102938/cover.jpg"
+	);
+}
+
+#[test]
+fn search() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("夏日")], 1).unwrap();
+	assert_eq!(result.manga[1].title, "秘密花园");
+}
+
+#[test]
+fn manga_listing() {
+	load();
+	let result = source::get_manga_listing(listing("周榜"), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+}
+
+#[test]
+fn manga_details() {
+	load();
+	let manga = source::get_manga_details("102938".into()).unwrap();
+	assert_eq!(manga.title, "夏日的回忆 Summer Memories");
+	assert_eq!(
+		manga.cover,
+		"https://t.nhentai.net/galleries/2233/cover.jpg"
+	);
+	assert_eq!(manga.author, "ABC");
+	assert_eq!(manga.description, "一段「夏日」的\u{a0}故事。");
+	assert_eq!(manga.categories, ["全彩", "中文"]);
+	assert_eq!(manga.nsfw, MangaContentRating::Nsfw);
+}
+
+#[test]
+fn chapter_list() {
+	load();
+	let chapters = source::get_chapter_list("102938".into()).unwrap();
+	assert_eq!(chapters.len(), 1);
+	assert_eq!(chapters[0].url, "https://hanime1.me/comic/102938/1");
+}
+
+#[test]
+fn page_list() {
+	load();
+	let pages = source::get_page_list("102938".into(), "102938".into()).unwrap();
+	let urls = pages.iter().map(|a| a.url.as_str()).collect::<Vec<_>>();
+	assert_eq!(
+		urls,
+		[
+			"https://i.nhentai.net/galleries/2233/1.jpg",
+			"https://i.nhentai.net/galleries/2233/2.png"
+		]
+	);
+}
+
+#[test]
+fn handle_manga_url() {
+	load();
+	let link = source::handle_url("https://hanime1.me/comic/102938".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "102938");
+	assert!(link.chapter.is_none());
+}
+
+#[test]
+fn handle_chapter_url() {
+	load();
+	let link = source::handle_url("https://hanime1.me/comic/102938/1".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "102938");
+	assert_eq!(link.chapter.unwrap().id, "102938");
+}
@@ -0,0 +1,119 @@
+use aidoku::testing::*;
+use aidoku_happymh as source;
+
+fn load() {
+	zh_harness::load("zh.happymh");
+}
+
+#[test]
+fn manga_list() {
+	load();
+	let result = source::get_manga_list(Vec::new(), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(result.manga[0].id, "haizeiwang");
+	assert_eq!(result.manga[0].title, "海贼王");
+	assert_eq!(
+		result.manga[0].cover,
+		"https://ruicdn.happymh.com/cover/haizeiwang.jpg"
+	);
+}
+
+#[test]
+fn filtered_manga_list() {
+	load();
+	let filters = vec![
+		select_filter("类型", 1),
+		select_filter("地区", 2),
+		select_filter("状态", 2),
+		sort_filter("排序", 1, false),
+	];
+	let result = source::get_manga_list(filters, 2).unwrap();
+	assert_eq!(result.manga.len(), 2);
+}
+
+#[test]
+fn search() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("海贼")], 1).unwrap();
+	assert_eq!(result.manga[1].title, "进击的巨人");
+}
+
+#[test]
+fn manga_listing() {
+	load();
+	let result = source::get_manga_listing(listing("日阅读"), 1).unwrap();
+	assert!(!result.has_more);
+	assert_eq!(result.manga[1].id, "jinjidejuren");
+	assert_eq!(result.manga[1].title, "进击的巨人");
+}
+
+#[test]
+fn manga_details() {
+	load();
+	let manga = source::get_manga_details("haizeiwang".into()).unwrap();
+	assert_eq!(manga.title, "海贼王");
+	assert_eq!(
+		manga.cover,
+		"https://ruicdn.happymh.com/cover/haizeiwang.jpg"
+	);
+	assert_eq!(manga.author, "尾田荣一郎");
+	assert_eq!(manga.description, "路飞的冒险。");
+	assert_eq!(manga.categories, ["热血", "冒险"]);
+}
+
+#[test]
+fn chapter_list() {
+	load();
+	let chapters = source::get_chapter_list("haizeiwang".into()).unwrap();
+	let ids = chapters.iter().map(|a| a.id.as_str()).collect::<Vec<_>>();
+	assert_eq!(ids, ["haizeiwang-3", "haizeiwang-2", "haizeiwang-1"]);
+	assert_eq!(chapters[0].chapter, 3.0);
+	assert_eq!(
+		chapters[0].url,
+		"https://m.happymh.com/mangaread/haizeiwang-3"
+	);
+}
+
+#[test]
+fn page_list() {
+	load();
+	let pages = source::get_page_list("haizeiwang".into(), "haizeiwang-1".into()).unwrap();
+	let urls = pages.iter().map(|a| a.url.as_str()).collect::<Vec<_>>();
+	assert_eq!(
+		urls,
+		[
+			"https://ruicdn.happymh.com/scans/1/01.jpg",
+			"https://ruicdn.happymh.com/scans/1/02.jpg"
+		]
+	);
+}
+
+#[test]
+fn reports_cloudflare_challenge() {
+	load();
+	assert!(source::get_manga_details("guarded".into()).is_err());
+	assert_eq!(logs(), ["需要通过 Cloudflare 验证，请在网页中打开"]);
+}
+
+#[test]
+fn plain_forbidden_page_is_not_a_challenge() {
+	load();
+	let _ = source::get_manga_details("hidden".into());
+	assert!(logs().is_empty());
+}
+
+#[test]
+fn handle_manga_url() {
+	load();
+	let link = source::handle_url("https://m.happymh.com/manga/haizeiwang".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "haizeiwang");
+	assert!(link.chapter.is_none());
+}
+
+#[test]
+fn handle_chapter_url() {
+	load();
+	let link = source::handle_url("https://m.happymh.com/mangaread/haizeiwang-1".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "haizeiwang");
+	assert_eq!(link.chapter.unwrap().id, "haizeiwang-1");
+}
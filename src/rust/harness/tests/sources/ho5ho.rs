@@ -0,0 +1,93 @@
+use aidoku::{testing::*, MangaStatus};
+use aidoku_ho5ho as source;
+
+fn load() {
+	zh_harness::load("zh.ho5ho");
+}
+
+#[test]
+fn manga_list() {
+	load();
+	let result = source::get_manga_list(Vec::new(), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(result.manga[0].id, "summer-days");
+	assert_eq!(result.manga[0].title, "夏日時光");
+	assert_eq!(
+		result.manga[0].cover,
+		"https://www.ho5ho.com/wp-content/uploads/%E5%B0%81%E9%9D%A2/summer-days-193x278.jpg"
+	);
+}
+
+#[test]
+fn filtered_manga_list() {
+	load();
+	let filters = vec![select_filter("类别", 6), sort_filter("排序", 2, false)];
+	let result = source::get_manga_list(filters, 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+}
+
+#[test]
+fn search() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("夏日")], 1).unwrap();
+	assert_eq!(result.manga[1].id, "secret-room");
+}
+
+#[test]
+fn manga_details() {
+	load();
+	let manga = source::get_manga_details("summer-days".into()).unwrap();
+	assert_eq!(manga.title, "夏日時光");
+	assert_eq!(
+		manga.cover,
+		"https://www.ho5ho.com/wp-content/uploads/%E5%B0%81%E9%9D%A2/summer-days-175x238.jpg"
+	);
+	assert_eq!(manga.author, "作者甲, 作者乙");
+	assert_eq!(manga.description, "第一段。\n第二段。");
+	assert_eq!(manga.categories, ["全彩", "同人"]);
+	assert_eq!(manga.status, MangaStatus::Completed);
+}
+
+#[test]
+fn chapter_list() {
+	load();
+	let chapters = source::get_chapter_list("summer-days".into()).unwrap();
+	let ids = chapters.iter().map(|a| a.id.as_str()).collect::<Vec<_>>();
+	assert_eq!(ids, ["ch-1", "ch-2"]);
+	assert_eq!(chapters[0].title, "第1話");
+	assert_eq!(chapters[0].date_updated, 1704384000.0);
+}
+
+#[test]
+fn page_list() {
+	load();
+	let pages = source::get_page_list("summer-days".into(), "ch-1".into()).unwrap();
+	let urls = pages.iter().map(|a| a.url.as_str()).collect::<Vec<_>>();
+	assert_eq!(
+		urls,
+		[
+			"https://img.ho5ho.com/summer-days/1/01.jpg",
+			"https://img.ho5ho.com/summer-days/1/02.jpg"
+		]
+	);
+}
+
+#[test]
+fn handle_manga_url() {
+	load();
+	let link = source::handle_url(
+		"https://www.ho5ho.com/%E4%B8%AD%E5%AD%97h%E6%BC%AB/summer-days/".into(),
+	)
+	.unwrap();
+	assert_eq!(link.manga.unwrap().id, "summer-days");
+	assert!(link.chapter.is_none());
+}
+
+#[test]
+fn handle_chapter_url() {
+	load();
+	let link =
+		source::handle_url("https://www.ho5ho.com/中字h漫/summer-days/ch-2/".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "summer-days");
+	assert_eq!(link.chapter.unwrap().id, "ch-2");
+}
@@ -0,0 +1,185 @@
+use aidoku::{error::AidokuErrorKind, std::net::Request, testing::*, MangaStatus};
+use aidoku_komiic as source;
+
+fn load() {
+	zh_harness::load("zh.komiic");
+}
+
+#[test]
+fn manga_list() {
+	load();
+	let filters = vec![select_filter("类型", 1), select_filter("状态", 2)];
+	let result = source::get_manga_list(filters, 1).unwrap();
+	assert!(result.has_more);
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(result.manga[0].id, "101");
+	assert_eq!(result.manga[0].title, "鬼灭之刃");
+	assert_eq!(
+		result.manga[0].cover,
+		"https://komiic.com/api/comics/101/cover"
+	);
+}
+
+#[test]
+fn search() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("鬼灭")], 1).unwrap();
+	assert!(!result.has_more);
+	assert_eq!(result.manga.len(), 1);
+}
+
+#[test]
+fn search_reports_rate_limit() {
+	load();
+	assert!(source::get_manga_list(vec![title_filter("繁忙")], 1).is_err());
+	assert_eq!(requests().len(), 3);
+	assert_eq!(now(), DEFAULT_NOW + 3.0);
+	assert_eq!(logs(), ["请求过于频繁，请稍后再试"]);
+}
+
+#[test]
+fn manga_listing() {
+	load();
+	let result = source::get_manga_listing(listing("本月热门"), 1).unwrap();
+	assert_eq!(result.manga[0].title, "咒术回战");
+	let result = source::get_manga_listing(listing("最近更新"), 1).unwrap();
+	assert_eq!(result.manga.len(), 1);
+}
+
+#[test]
+fn manga_details() {
+	load();
+	let manga = source::get_manga_details("101".into()).unwrap();
+	assert_eq!(manga.title, "鬼灭之刃");
+	assert_eq!(manga.author, "吾峠呼世晴");
+	assert_eq!(manga.categories, ["热血", "冒险"]);
+	assert_eq!(manga.status, MangaStatus::Completed);
+	assert_eq!(manga.url, "https://komiic.com/comic/101");
+}
+
+#[test]
+fn chapter_list() {
+	load();
+	let chapters = source::get_chapter_list("101".into()).unwrap();
+	let ids = chapters.iter().map(|a| a.id.as_str()).collect::<Vec<_>>();
+	assert_eq!(ids, ["5002", "5001"]);
+	assert_eq!(chapters[0].date_updated, 1704672000.0);
+	assert_eq!(
+		chapters[0].url,
+		"https://komiic.com/comic/101/chapter/5002/images/all"
+	);
+}
+
+#[test]
+fn page_list() {
+	load();
+	let pages = source::get_page_list("101".into(), "5001".into()).unwrap();
+	let urls = pages.iter().map(|a| a.url.as_str()).collect::<Vec<_>>();
+	assert_eq!(
+		urls,
+		[
+			"https://komiic.com/api/image/k-aaa?mangaId=101&chapterId=5001",
+			"https://komiic.com/api/image/k-bbb?mangaId=101&chapterId=5001"
+		]
+	);
+}
+
+#[test]
+fn image_request() {
+	load();
+	let request = Request::get("https://komiic.com/api/image/k-aaa?mangaId=101&chapterId=5001");
+	source::modify_image_request(request.clone());
+	assert!(request.headers().contains(&(
+		"Referer".into(),
+		"https://komiic.com/comic/101/chapter/5001/images/all".into()
+	)));
+}
+
+#[test]
+fn image_request_signed_in() {
+	load();
+	set_default("token", "jwt-from-login");
+	let request = Request::get("https://komiic.com/api/image/k-aaa?mangaId=101&chapterId=5001");
+	source::modify_image_request(request.clone());
+	assert!(request
+		.headers()
+		.contains(&("Authorization".into(), "Bearer jwt-from-login".into())));
+}
+
+#[test]
+fn signs_in_with_account() {
+	load();
+	set_default("username", "reader@example.com");
+	set_default("password", "secret");
+	source::get_page_list("101".into(), "5001".into()).unwrap();
+	assert_eq!(requests().len(), 3);
+	let authorization = ("Authorization".into(), "Bearer jwt-from-login".into());
+	assert!(!request_headers()[0].contains(&authorization));
+	assert!(request_headers()[1..]
+		.iter()
+		.all(|a| a.contains(&authorization)));
+	let token = default_value("token").unwrap();
+	assert_eq!(token.as_string().unwrap().read(), "jwt-from-login");
+}
+
+#[test]
+fn signs_in_again_when_token_expired() {
+	load();
+	set_default("username", "reader@example.com");
+	set_default("password", "secret");
+	set_default("token", "expired");
+	let pages = source::get_page_list("101".into(), "5001".into()).unwrap();
+	assert_eq!(pages.len(), 2);
+	assert_eq!(requests().len(), 4);
+}
+
+#[test]
+fn drops_rejected_token_without_account() {
+	load();
+	set_default("token", "expired");
+	let pages = source::get_page_list("101".into(), "5001".into()).unwrap();
+	assert_eq!(pages.len(), 2);
+	assert_eq!(requests().len(), 3);
+	assert!(request_headers()[1..]
+		.iter()
+		.all(|a| !a.iter().any(|(key, _)| key == "Authorization")));
+	let token = default_value("token").unwrap();
+	assert_eq!(token.as_string().unwrap().read(), "");
+	assert!(logs().is_empty());
+}
+
+#[test]
+fn reports_rejected_password() {
+	load();
+	set_default("username", "wrong@example.com");
+	set_default("password", "secret");
+	let error = source::get_page_list("101".into(), "5001".into()).unwrap_err();
+	assert_eq!(error.reason, AidokuErrorKind::DefaultNotFound);
+	assert_eq!(requests().len(), 1);
+}
+
+#[test]
+fn reports_image_limit() {
+	load();
+	set_default("token", "limited");
+	assert!(source::get_page_list("101".into(), "5001".into()).is_err());
+	assert_eq!(requests().len(), 1);
+	assert_eq!(logs(), ["今日图片额度已用完，请登录账号或明天再试"]);
+}
+
+#[test]
+fn handle_manga_url() {
+	load();
+	let link = source::handle_url("https://komiic.com/comic/101".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "101");
+	assert!(link.chapter.is_none());
+}
+
+#[test]
+fn handle_chapter_url() {
+	load();
+	let link =
+		source::handle_url("https://komiic.com/comic/101/chapter/5002/images/all".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "101");
+	assert_eq!(link.chapter.unwrap().id, "5002");
+}
@@ -0,0 +1,28 @@
+//! Replays each source's `fixtures/` through its entry points. Deprecated
+//! sources are not built or tested.
+
+mod _18mh;
+mod bakamh;
+mod baozimh1;
+mod baozimh2;
+mod bilicomic;
+mod cartoonmad;
+mod copymanga;
+mod godamanga;
+mod gufengmh;
+mod hanime1;
+mod happymh;
+mod ho5ho;
+mod komiic;
+mod manhuabika;
+mod manhuadb;
+mod manhuaren;
+mod mkzhan;
+mod mxshm;
+mod mycomic;
+mod noy1;
+mod se8;
+mod wnacg;
+mod yandanshe;
+mod zaimanhua;
+mod zerobywns;
@@ -0,0 +1,260 @@
+use aidoku::{error::AidokuErrorKind, std::ArrayRef, testing::*, MangaStatus, MangaViewer};
+use aidoku_manhuabika as source;
+
+fn load() {
+	zh_harness::load("zh.manhuabika");
+}
+
+#[test]
+fn manga_list() {
+	load();
+	let result = source::get_manga_list(Vec::new(), 1).unwrap();
+	assert!(result.has_more);
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(result.manga[0].id, "5f1");
+	assert_eq!(
+		result.manga[0].cover,
+		"https://storage1.picacomic.com/static/tobeimg/5f1.jpg"
+	);
+	assert_eq!(result.manga[1].viewer, MangaViewer::Scroll);
+}
+
+#[test]
+fn signs_in_without_token() {
+	load();
+	source::get_manga_list(Vec::new(), 1).unwrap();
+	assert_eq!(
+		requests(),
+		[
+			"POST https://picaapi.picacomic.com/auth/sign-in",
+			"GET https://picaapi.picacomic.com/comics?page=1&s=dd"
+		]
+	);
+	let token = default_value("token").unwrap();
+	assert_eq!(token.as_string().unwrap().read(), "token-from-sign-in");
+}
+
+#[test]
+fn signs_in_again_when_token_expired() {
+	load();
+	set_default("token", "expired");
+	let result = source::get_manga_listing(listing("随机本子"), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(
+		requests(),
+		[
+			"GET https://picaapi.picacomic.com/comics/random",
+			"POST https://picaapi.picacomic.com/auth/sign-in",
+			"GET https://picaapi.picacomic.com/comics/random"
+		]
+	);
+}
+
+#[test]
+fn asks_to_log_in_without_credentials() {
+	load();
+	set_default("username", "");
+	let error = source::get_manga_list(Vec::new(), 1).unwrap_err();
+	assert_eq!(error.reason, AidokuErrorKind::DefaultNotFound);
+	assert_eq!(logs(), ["需要登录，请在设置中填写账号密码"]);
+}
+
+#[test]
+fn does_not_retry_sign_in() {
+	load();
+	set_default("username", "busy");
+	assert!(source::get_manga_list(Vec::new(), 1).is_err());
+	assert_eq!(
+		requests(),
+		["POST https://picaapi.picacomic.com/auth/sign-in"]
+	);
+}
+
+#[test]
+fn filtered_manga_list() {
+	load();
+	let filters = vec![select_filter("类别", 2), sort_filter("排序", 2, false)];
+	let result = source::get_manga_list(filters, 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+}
+
+#[test]
+fn search() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("夏日")], 1).unwrap();
+	assert_eq!(result.manga[0].title, "夏日泳装");
+}
+
+#[test]
+fn manga_listing() {
+	load();
+	let result = source::get_manga_listing(listing("日榜"), 1).unwrap();
+	assert!(!result.has_more);
+	assert_eq!(result.manga[0].id, "5f2");
+}
+
+#[test]
+fn manga_details() {
+	load();
+	let manga = source::get_manga_details("5f1".into()).unwrap();
+	assert_eq!(manga.title, "夏日泳装");
+	assert_eq!(manga.author, "作者A, 作者B");
+	assert_eq!(manga.description, "一个夏天的故事。");
+	assert_eq!(manga.categories, ["全彩", "長篇"]);
+	assert_eq!(manga.status, MangaStatus::Ongoing);
+	assert_eq!(manga.url, "https://manhuabika.com/pcomicview/?cid=5f1");
+}
+
+#[test]
+fn chapter_list() {
+	load();
+	let chapters = source::get_chapter_list("5f1".into()).unwrap();
+	let ids = chapters.iter().map(|a| a.id.as_str()).collect::<Vec<_>>();
+	assert_eq!(ids, ["3", "2", "1"]);
+	assert_eq!(chapters[2].title, "第1話");
+	assert_eq!(chapters[2].chapter, 1.0);
+	assert_eq!(chapters[0].date_updated, 1704240000.0);
+}
+
+#[test]
+fn page_list() {
+	load();
+	let pages = source::get_page_list("5f1".into(), "1".into()).unwrap();
+	let pages = pages
+		.iter()
+		.map(|a| (a.index, a.url.as_str()))
+		.collect::<Vec<_>>();
+	assert_eq!(
+		pages,
+		[
+			(0, "https://storage1.picacomic.com/static/tobs/01.jpg"),
+			(1, "https://storage1.picacomic.com/static/tobs/02.jpg"),
+			(2, "https://storage1.picacomic.com/static/tobs/03.jpg")
+		]
+	);
+}
+
+#[test]
+fn requests_chosen_image_quality() {
+	load();
+	source::get_page_list("5f1".into(), "1".into()).unwrap();
+	let original = ("image-quality".into(), "original".into());
+	assert!(request_headers().iter().all(|a| a.contains(&original)));
+
+	load();
+	set_default("image_quality", "low");
+	source::get_page_list("5f1".into(), "1".into()).unwrap();
+	let low = ("image-quality".into(), "low".into());
+	assert!(request_headers().iter().all(|a| a.contains(&low)));
+}
+
+#[test]
+fn search_with_category_and_sort() {
+	load();
+	let filters = vec![
+		title_filter("夏日"),
+		select_filter("类别", 2),
+		sort_filter("排序", 2, false),
+	];
+	let result = source::get_manga_list(filters, 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(
+		requests().last().unwrap(),
+		"POST https://picaapi.picacomic.com/comics/advanced-search?page=1&s=ld"
+	);
+}
+
+#[test]
+fn search_by_tag() {
+	load();
+	let filters = vec![title_filter("夏日"), text_filter("标签", "校服")];
+	let result = source::get_manga_list(filters, 1).unwrap();
+	assert_eq!(result.manga.len(), 1);
+	assert_eq!(result.manga[0].title, "夏日泳装");
+	assert!(result.has_more);
+	assert_eq!(
+		requests().last().unwrap(),
+		"GET https://picaapi.picacomic.com/comics?page=1&t=%E6%A0%A1%E6%9C%8D&s=dd"
+	);
+}
+
+#[test]
+fn tag_manga_list() {
+	load();
+	let result = source::get_manga_list(vec![text_filter("标签", "校服")], 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+}
+
+#[test]
+fn author_and_uploader_manga_list() {
+	load();
+	let filters = vec![
+		select_filter("类别", 2),
+		author_filter("ぽんこつわーくす"),
+		text_filter("上传者", "5e0a"),
+		sort_filter("排序", 3, false),
+	];
+	let result = source::get_manga_list(filters, 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+}
+
+#[test]
+fn favourite_listing() {
+	load();
+	let result = source::get_manga_listing(listing("我的收藏"), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+	assert!(result.has_more);
+	assert_eq!(
+		requests().last().unwrap(),
+		"GET https://picaapi.picacomic.com/users/favourite?s=dd&page=1"
+	);
+}
+
+#[test]
+fn favourite_listing_follows_sort_setting() {
+	load();
+	set_default("favourite_sort", "ld");
+	source::get_manga_listing(listing("我的收藏"), 1).unwrap();
+	assert_eq!(
+		requests().last().unwrap(),
+		"GET https://picaapi.picacomic.com/users/favourite?s=ld&page=1"
+	);
+}
+
+#[test]
+fn hides_blocked_categories() {
+	load();
+	let mut blocked = ArrayRef::new();
+	blocked.insert("WEBTOON".into());
+	set_default("blocked_categories", blocked);
+
+	let result = source::get_manga_list(Vec::new(), 1).unwrap();
+	let ids = result
+		.manga
+		.iter()
+		.map(|a| a.id.as_str())
+		.collect::<Vec<_>>();
+	assert_eq!(ids, ["5f1"]);
+	assert!(result.has_more);
+
+	let result = source::get_manga_listing(listing("日榜"), 1).unwrap();
+	assert_eq!(result.manga[0].id, "5f1");
+	assert_eq!(result.manga.len(), 1);
+}
+
+#[test]
+fn handle_manga_url() {
+	load();
+	let link = source::handle_url("https://manhuabika.com/pcomicview/?cid=5f1".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "5f1");
+	assert!(link.chapter.is_none());
+}
+
+#[test]
+fn handle_chapter_url() {
+	load();
+	let link =
+		source::handle_url("https://manhuabika.com/pchapter/?cid=5f1&chapter=2".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "5f1");
+	assert_eq!(link.chapter.unwrap().id, "2");
+}
@@ -0,0 +1,95 @@
+use aidoku::{testing::*, MangaStatus};
+use aidoku_manhuadb as source;
+
+fn load() {
+	zh_harness::load("zh.manhuadb");
+}
+
+#[test]
+fn manga_list() {
+	load();
+	let result = source::get_manga_list(Vec::new(), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(result.manga[0].id, "10112");
+	assert_eq!(result.manga[0].title, "火影忍者");
+	assert_eq!(
+		result.manga[0].cover,
+		"https://www.manhuadb.com/static/covers/10112.jpg"
+	);
+	assert_eq!(
+		result.manga[1].cover,
+		"https://i2.manhuadb.com/covers/10113.jpg"
+	);
+}
+
+#[test]
+fn filtered_manga_list() {
+	load();
+	let filters = vec![
+		select_filter("原作地区", 1),
+		select_filter("面向读者", 1),
+		select_filter("连载状态", 2),
+		select_filter("类型", 1),
+	];
+	let result = source::get_manga_list(filters, 2).unwrap();
+	assert_eq!(result.manga.len(), 2);
+}
+
+#[test]
+fn search() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("火影")], 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(result.manga[1].title, "海贼王");
+}
+
+#[test]
+fn manga_details() {
+	load();
+	let manga = source::get_manga_details("10112".into()).unwrap();
+	assert_eq!(manga.title, "火影忍者");
+	assert_eq!(manga.author, "岸本齐史");
+	assert_eq!(manga.description, "鸣人的忍者之路。");
+	assert_eq!(manga.categories, ["热血", "冒险"]);
+	assert_eq!(manga.status, MangaStatus::Completed);
+}
+
+#[test]
+fn chapter_list() {
+	load();
+	let chapters = source::get_chapter_list("10112".into()).unwrap();
+	let ids = chapters.iter().map(|a| a.id.as_str()).collect::<Vec<_>>();
+	assert_eq!(ids, ["1330_14025", "1330_14024"]);
+	assert_eq!(chapters[1].title, "第01卷");
+}
+
+#[test]
+fn page_list() {
+	load();
+	let pages = source::get_page_list("10112".into(), "1330_14024".into()).unwrap();
+	let urls = pages.iter().map(|a| a.url.as_str()).collect::<Vec<_>>();
+	assert_eq!(
+		urls,
+		[
+			"https://i2.manhuadb.com/static/1330/14024/001.jpg",
+			"https://i2.manhuadb.com/static/1330/14024/002.jpg"
+		]
+	);
+}
+
+#[test]
+fn handle_manga_url() {
+	load();
+	let link = source::handle_url("https://www.manhuadb.com/manhua/10112".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "10112");
+	assert!(link.chapter.is_none());
+}
+
+#[test]
+fn handle_chapter_url() {
+	load();
+	let link =
+		source::handle_url("https://www.manhuadb.com/manhua/10112/1330_14024.html".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "10112");
+	assert_eq!(link.chapter.unwrap().id, "1330_14024");
+}
@@ -0,0 +1,228 @@
+use aidoku::{std::net::Request, testing::*, MangaStatus};
+use aidoku_manhuaren as source;
+
+fn load() {
+	zh_harness::load("zh.manhuaren");
+}
+
+#[test]
+fn manga_list() {
+	load();
+	let result = source::get_manga_list(Vec::new(), 1).unwrap();
+	assert!(result.has_more);
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(result.manga[0].id, "1");
+	assert_eq!(result.manga[0].title, "海贼王");
+	assert_eq!(result.manga[0].cover, "https://mhfm1.cdndm5.com/1/pic.jpg");
+}
+
+#[test]
+fn filtered_manga_list() {
+	load();
+	let filters = vec![
+		select_filter("类别", 1),
+		select_filter("状态", 2),
+		sort_filter("排序", 1, false),
+	];
+	let result = source::get_manga_list(filters, 2).unwrap();
+	assert_eq!(result.manga[1].status, MangaStatus::Completed);
+}
+
+#[test]
+fn search() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("海贼")], 1).unwrap();
+	assert!(result.has_more);
+	assert_eq!(result.manga.len(), 1);
+}
+
+#[test]
+fn registers_guest_on_first_use() {
+	load();
+	source::get_manga_details("1".into()).unwrap();
+	assert_eq!(
+		requests(),
+		[
+			"POST https://mangaapi.manhuaren.com/v1/user/createAnonyUser2",
+			"GET https://mangaapi.manhuaren.com/v1/manga/getDetail?mangaId=1&gak=ios_manhuaren2&gft=json&gui=486922991&gsn=baab76a4a43cb258da13eb05db4d2050"
+		]
+	);
+	let authorization = ("Authorization".into(), "YINGQISTS2 guest-token".into());
+	assert!(request_headers()[1].contains(&authorization));
+	let uid = default_value("uid").unwrap();
+	assert_eq!(uid.as_string().unwrap().read(), "486922991");
+	assert!(default_value("device_id").is_some());
+}
+
+#[test]
+fn refreshes_expired_token() {
+	load();
+	set_default("uid", "486922991");
+	set_default("token", "expired");
+	let result = source::get_manga_list(vec![title_filter("海贼")], 1).unwrap();
+	assert_eq!(result.manga.len(), 1);
+	let requests = requests();
+	assert_eq!(requests.len(), 3);
+	assert_eq!(
+		requests[1],
+		"POST https://mangaapi.manhuaren.com/v1/user/refreshToken"
+	);
+	let authorization = ("Authorization".into(), "YINGQISTS2 refreshed-token".into());
+	assert!(request_headers()[2].contains(&authorization));
+	let token = default_value("token").unwrap();
+	assert_eq!(token.as_string().unwrap().read(), "refreshed-token");
+}
+
+#[test]
+fn registers_again_when_refresh_refused() {
+	load();
+	set_default("uid", "486922991");
+	set_default("token", "revoked");
+	let result = source::get_manga_list(vec![title_filter("海贼")], 1).unwrap();
+	assert_eq!(result.manga.len(), 1);
+	let requests = requests();
+	assert_eq!(requests.len(), 4);
+	assert_eq!(
+		requests[2],
+		"POST https://mangaapi.manhuaren.com/v1/user/createAnonyUser2"
+	);
+	let token = default_value("token").unwrap();
+	assert_eq!(token.as_string().unwrap().read(), "guest-token");
+	assert!(logs().is_empty());
+}
+
+#[test]
+fn manga_details() {
+	load();
+	let manga = source::get_manga_details("1".into()).unwrap();
+	assert_eq!(manga.title, "海贼王");
+	assert_eq!(manga.author, "尾田荣一郎");
+	assert_eq!(manga.description, "路飞的冒险。");
+	assert_eq!(manga.categories, ["热血", "冒险"]);
+	assert_eq!(manga.status, MangaStatus::Ongoing);
+	assert_eq!(manga.url, "https://www.manhuaren.com/manhua-1/");
+}
+
+#[test]
+fn chapter_list() {
+	load();
+	let chapters = source::get_chapter_list("1".into()).unwrap();
+	let titles = chapters
+		.iter()
+		.map(|a| a.title.as_str())
+		.collect::<Vec<_>>();
+	assert_eq!(
+		titles,
+		["第2话 祖石", "第1话", "🔒 第1卷 东海篇", "番外1 乔巴"]
+	);
+	assert_eq!(chapters[1].chapter, 1.0);
+	assert_eq!(chapters[2].volume, 1.0);
+	assert_eq!(chapters[2].chapter, -1.0);
+	assert_eq!(chapters[2].scanlator, "单行本");
+	assert_eq!(chapters[3].chapter, -1.0);
+	assert_eq!(chapters[3].scanlator, "番外");
+	assert_eq!(chapters[0].id, "1002");
+	assert_eq!(chapters[0].date_updated, 1704384000.0);
+	assert_eq!(chapters[0].url, "https://www.manhuaren.com/m1002/");
+}
+
+#[test]
+fn chapter_list_hides_paid() {
+	load();
+	set_default("hide_paid", true);
+	let chapters = source::get_chapter_list("1".into()).unwrap();
+	assert!(chapters.iter().all(|a| a.id != "2001"));
+	assert_eq!(chapters.len(), 3);
+}
+
+#[test]
+fn page_list_reports_paid_chapter() {
+	load();
+	assert!(source::get_page_list("1".into(), "2001".into()).is_err());
+	assert_eq!(logs(), ["该章节需要付费，请在官方应用中购买后阅读"]);
+}
+
+#[test]
+fn page_list() {
+	load();
+	let pages = source::get_page_list("1".into(), "1001".into()).unwrap();
+	let urls = pages.iter().map(|a| a.url.as_str()).collect::<Vec<_>>();
+	assert_eq!(
+		urls,
+		[
+			"https://manhua1.cdndm5.com/1/1001/1.jpg?cid=1001&key=abc#https://manhua1.cdndm5.com,https://manhua2.cdndm5.com",
+			"https://manhua1.cdndm5.com/1/1001/2.jpg?cid=1001&key=abc#https://manhua1.cdndm5.com,https://manhua2.cdndm5.com"
+		]
+	);
+	assert_eq!(requests().len(), 2);
+}
+
+#[test]
+fn image_request_keeps_working_host() {
+	load();
+	let pages = source::get_page_list("1".into(), "1001".into()).unwrap();
+	let urls = (0..2)
+		.map(|_| {
+			let request = Request::get(&pages[0].url);
+			source::modify_image_request(request.clone());
+			request.url().read()
+		})
+		.collect::<Vec<_>>();
+	assert_eq!(
+		urls,
+		[
+			"https://manhua1.cdndm5.com/1/1001/1.jpg?cid=1001&key=abc",
+			"https://manhua1.cdndm5.com/1/1001/1.jpg?cid=1001&key=abc"
+		]
+	);
+	assert!(default_value("last_image_host").is_none());
+}
+
+#[test]
+fn image_request_rotates_host_on_failure() {
+	load();
+	let pages = source::get_page_list("1".into(), "1003".into()).unwrap();
+	let urls = (0..3)
+		.map(|_| {
+			let request = Request::get(&pages[0].url);
+			source::modify_image_request(request.clone());
+			request.url().read()
+		})
+		.collect::<Vec<_>>();
+	assert_eq!(
+		urls,
+		[
+			"https://manhua3.cdndm5.com/1/1003/1.jpg?cid=1003&key=def",
+			"https://manhua2.cdndm5.com/1/1003/1.jpg?cid=1003&key=def",
+			"https://manhua2.cdndm5.com/1/1003/1.jpg?cid=1003&key=def"
+		]
+	);
+	let last = default_value("last_image_host").unwrap();
+	assert_eq!(
+		last.as_string().unwrap().read(),
+		"https://manhua2.cdndm5.com"
+	);
+
+	let pages = source::get_page_list("1".into(), "1003".into()).unwrap();
+	assert!(pages[1]
+		.url
+		.starts_with("https://manhua2.cdndm5.com/1/1003/2.jpg"));
+}
+
+#[test]
+fn page_list_prefers_chosen_host() {
+	load();
+	set_default("image_host", "manhua2.cdndm5.com");
+	let pages = source::get_page_list("1".into(), "1001".into()).unwrap();
+	assert!(pages[0]
+		.url
+		.starts_with("https://manhua2.cdndm5.com/1/1001/1.jpg?cid=1001&key=abc#"));
+}
+
+#[test]
+fn handle_chapter_url() {
+	load();
+	let link = source::handle_url("https://www.manhuaren.com/m1002/".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "1");
+	assert_eq!(link.chapter.unwrap().id, "1002");
+}
@@ -0,0 +1,100 @@
+use aidoku::{testing::*, MangaStatus};
+use aidoku_mkzhan as source;
+
+fn load() {
+	zh_harness::load("zh.mkzhan");
+}
+
+#[test]
+fn manga_list() {
+	load();
+	let result = source::get_manga_list(Vec::new(), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(result.manga[0].id, "209871");
+	assert_eq!(result.manga[0].title, "斗罗大陆");
+	assert_eq!(
+		result.manga[0].cover,
+		"https://oss.mkzcdn.com/comic/cover/209871.jpg"
+	);
+}
+
+#[test]
+fn filtered_manga_list() {
+	load();
+	let filters = vec![
+		select_filter("题材", 1),
+		select_filter("进度", 2),
+		select_filter("受众", 1),
+		select_filter("资费", 1),
+		sort_filter("排序", 1, false),
+	];
+	let result = source::get_manga_list(filters, 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+}
+
+#[test]
+fn search() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("斗罗")], 1).unwrap();
+	assert_eq!(result.manga[1].title, "妖神记");
+}
+
+#[test]
+fn manga_listing() {
+	load();
+	let result = source::get_manga_listing(listing("付费榜"), 1).unwrap();
+	assert_eq!(result.manga.len(), 1);
+}
+
+#[test]
+fn manga_details() {
+	load();
+	let manga = source::get_manga_details("209871".into()).unwrap();
+	assert_eq!(manga.title, "斗罗大陆");
+	assert_eq!(manga.cover, "https://oss.mkzcdn.com/comic/cover/209871.jpg");
+	assert_eq!(manga.author, "唐家三少");
+	assert_eq!(manga.description, "唐三的斗罗大陆之旅。");
+	assert_eq!(manga.categories, ["玄幻", "热血"]);
+	assert_eq!(manga.status, MangaStatus::Ongoing);
+}
+
+#[test]
+fn chapter_list() {
+	load();
+	let chapters = source::get_chapter_list("209871".into()).unwrap();
+	let ids = chapters.iter().map(|a| a.id.as_str()).collect::<Vec<_>>();
+	assert_eq!(ids, ["1002", "1001"]);
+	assert_eq!(chapters[0].chapter, 2.0);
+	assert_eq!(chapters[0].date_updated, 1705017600.0);
+	assert_eq!(chapters[0].url, "https://www.mkzhan.com/209871/1002.html");
+}
+
+#[test]
+fn page_list() {
+	load();
+	let pages = source::get_page_list("209871".into(), "1001".into()).unwrap();
+	let urls = pages.iter().map(|a| a.url.as_str()).collect::<Vec<_>>();
+	assert_eq!(
+		urls,
+		[
+			"https://mkzcdn.com/209871/1001/01.jpg",
+			"https://mkzcdn.com/209871/1001/02.jpg"
+		]
+	);
+}
+
+#[test]
+fn handle_manga_url() {
+	load();
+	let link = source::handle_url("https://www.mkzhan.com/209871/".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "209871");
+	assert!(link.chapter.is_none());
+}
+
+#[test]
+fn handle_chapter_url() {
+	load();
+	let link = source::handle_url("https://www.mkzhan.com/209871/1002.html".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "209871");
+	assert_eq!(link.chapter.unwrap().id, "1002");
+}
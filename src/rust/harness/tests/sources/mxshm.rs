@@ -0,0 +1,98 @@
+use aidoku::{testing::*, MangaStatus};
+use aidoku_mxshm as source;
+
+fn load() {
+	zh_harness::load("zh.mxshm");
+}
+
+#[test]
+fn manga_list() {
+	load();
+	let result = source::get_manga_list(Vec::new(), 1).unwrap();
+	assert!(result.has_more);
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(result.manga[0].id, "511");
+	assert_eq!(result.manga[0].title, "同居生活");
+	assert_eq!(result.manga[0].cover, "https://img.mxs13.cc/cover/511.jpg");
+}
+
+#[test]
+fn filtered_manga_list() {
+	load();
+	let filters = vec![
+		select_filter("题材", 1),
+		select_filter("地区", 1),
+		select_filter("进度", 2),
+	];
+	let result = source::get_manga_list(filters, 2).unwrap();
+	assert_eq!(result.manga.len(), 2);
+}
+
+#[test]
+fn search() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("同居")], 1).unwrap();
+	assert!(!result.has_more);
+	assert_eq!(result.manga[1].title, "秘密教学");
+}
+
+#[test]
+fn uses_domain_setting() {
+	load();
+	set_default("url", "https://www.mxs12.cc");
+	source::get_manga_list(Vec::new(), 1).unwrap();
+	assert_eq!(
+		requests(),
+		["GET https://www.mxs12.cc/booklist?tag=&area=&end=&page=1"]
+	);
+}
+
+#[test]
+fn manga_details() {
+	load();
+	let manga = source::get_manga_details("511".into()).unwrap();
+	assert_eq!(manga.title, "同居生活");
+	assert_eq!(manga.author, "作者甲, 作者乙");
+	assert_eq!(manga.description, "两人同居的故事。");
+	assert_eq!(manga.categories, ["青春", "暧昧"]);
+	assert_eq!(manga.status, MangaStatus::Ongoing);
+}
+
+#[test]
+fn chapter_list() {
+	load();
+	let chapters = source::get_chapter_list("511".into()).unwrap();
+	let ids = chapters.iter().map(|a| a.id.as_str()).collect::<Vec<_>>();
+	assert_eq!(ids, ["9002", "9001"]);
+	assert_eq!(chapters[0].url, "https://www.mxs13.cc/chapter/9002");
+}
+
+#[test]
+fn page_list() {
+	load();
+	let pages = source::get_page_list("511".into(), "9001".into()).unwrap();
+	let urls = pages.iter().map(|a| a.url.as_str()).collect::<Vec<_>>();
+	assert_eq!(
+		urls,
+		[
+			"https://img.mxs13.cc/9001/01.jpg",
+			"https://img.mxs13.cc/9001/02.jpg"
+		]
+	);
+}
+
+#[test]
+fn handle_manga_url() {
+	load();
+	let link = source::handle_url("https://www.mxs12.cc/book/511".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "511");
+	assert!(link.chapter.is_none());
+}
+
+#[test]
+fn handle_chapter_url() {
+	load();
+	let link = source::handle_url("https://www.mxs13.cc/chapter/9001".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "511");
+	assert_eq!(link.chapter.unwrap().id, "9001");
+}
@@ -0,0 +1,90 @@
+use aidoku::{testing::*, MangaStatus};
+use aidoku_mycomic as source;
+
+fn load() {
+	zh_harness::load("zh.mycomic");
+}
+
+#[test]
+fn manga_list() {
+	load();
+	let result = source::get_manga_list(Vec::new(), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(result.manga[0].id, "9527");
+	assert_eq!(result.manga[0].title, "电锯人");
+	assert_eq!(result.manga[0].cover, "https://biccam.com/cover/9527.jpg");
+	assert_eq!(result.manga[1].cover, "https://biccam.com/cover/9528.jpg");
+}
+
+#[test]
+fn filtered_manga_list() {
+	load();
+	let filters = vec![
+		select_filter("类型", 1),
+		select_filter("地区", 1),
+		select_filter("年份", 2),
+		select_filter("进度", 2),
+		sort_filter("排序", 2, false),
+	];
+	let result = source::get_manga_list(filters, 2).unwrap();
+	assert_eq!(result.manga.len(), 2);
+}
+
+#[test]
+fn search() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("电锯")], 1).unwrap();
+	assert_eq!(result.manga[1].title, "间谍过家家");
+}
+
+#[test]
+fn manga_details() {
+	load();
+	let manga = source::get_manga_details("9527".into()).unwrap();
+	assert_eq!(manga.title, "电锯人");
+	assert_eq!(manga.cover, "https://biccam.com/cover/9527.jpg");
+	assert_eq!(manga.author, "藤本树");
+	assert_eq!(manga.description, "电次与波奇塔的故事。");
+	assert_eq!(manga.categories, ["热血", "恐怖"]);
+	assert_eq!(manga.status, MangaStatus::Ongoing);
+}
+
+#[test]
+fn chapter_list() {
+	load();
+	let chapters = source::get_chapter_list("9527".into()).unwrap();
+	let ids = chapters.iter().map(|a| a.id.as_str()).collect::<Vec<_>>();
+	assert_eq!(ids, ["30002", "30001"]);
+	assert_eq!(chapters[0].chapter, 2.0);
+	assert_eq!(chapters[0].url, "https://mycomic.com/cn/chapters/30002");
+}
+
+#[test]
+fn page_list() {
+	load();
+	let pages = source::get_page_list("9527".into(), "30001".into()).unwrap();
+	let urls = pages.iter().map(|a| a.url.as_str()).collect::<Vec<_>>();
+	assert_eq!(
+		urls,
+		[
+			"https://biccam.com/30001/01.jpg",
+			"https://biccam.com/30001/02.jpg"
+		]
+	);
+}
+
+#[test]
+fn handle_manga_url() {
+	load();
+	let link = source::handle_url("https://mycomic.com/cn/comics/9527".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "9527");
+	assert!(link.chapter.is_none());
+}
+
+#[test]
+fn handle_chapter_url() {
+	load();
+	let link = source::handle_url("https://mycomic.com/cn/chapters/30001".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "9527");
+	assert_eq!(link.chapter.unwrap().id, "30001");
+}
@@ -0,0 +1,122 @@
+use aidoku::testing::*;
+use aidoku_noy1 as source;
+
+fn load() {
+	zh_harness::load("zh.noy1");
+	set_default("username", "reader");
+	set_default("password", "secret");
+}
+
+#[test]
+fn manga_list() {
+	load();
+	let result = source::get_manga_list(Vec::new(), 1).unwrap();
+	assert!(result.has_more);
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(result.manga[0].id, "1201");
+	assert_eq!(result.manga[0].title, "夏日祭");
+	assert_eq!(result.manga[0].cover, "https://img.noy.asia/1201/m1.webp");
+}
+
+#[test]
+fn logs_in_without_session() {
+	load();
+	source::get_manga_list(Vec::new(), 1).unwrap();
+	assert_eq!(
+		requests(),
+		[
+			"POST https://noy1.top/api/login",
+			"POST https://noy1.top/api/booklist_v2"
+		]
+	);
+	let session = default_value("session").unwrap();
+	assert_eq!(session.as_string().unwrap().read(), "session-from-login");
+}
+
+#[test]
+fn logs_in_again_when_session_expired() {
+	load();
+	set_default("session", "stale");
+	let result = source::get_manga_list(Vec::new(), 2).unwrap();
+	assert!(result.has_more);
+	assert_eq!(
+		requests(),
+		[
+			"POST https://noy1.top/api/booklist_v2",
+			"POST https://noy1.top/api/login",
+			"POST https://noy1.top/api/booklist_v2"
+		]
+	);
+}
+
+#[test]
+fn login_required() {
+	zh_harness::load("zh.noy1");
+	assert!(source::get_manga_list(Vec::new(), 1).is_err());
+	assert!(requests().is_empty());
+}
+
+#[test]
+fn filtered_manga_list() {
+	load();
+	let filters = vec![select_filter("标签", 2), sort_filter("排序", 1, false)];
+	let result = source::get_manga_list(filters, 1).unwrap();
+	assert!(!result.has_more);
+	assert_eq!(result.manga.len(), 1);
+}
+
+#[test]
+fn search() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("夏日")], 1).unwrap();
+	assert_eq!(result.manga[0].title, "夏日祭");
+}
+
+#[test]
+fn manga_listing() {
+	load();
+	let result = source::get_manga_listing(listing("周阅读榜"), 1).unwrap();
+	assert!(!result.has_more);
+	assert_eq!(result.manga[0].id, "1202");
+}
+
+#[test]
+fn manga_details() {
+	load();
+	let manga = source::get_manga_details("1201".into()).unwrap();
+	assert_eq!(manga.title, "夏日祭");
+	assert_eq!(manga.author, "作者丙");
+	assert_eq!(manga.categories, ["全彩", "原創"]);
+	assert_eq!(manga.url, "https://noy1.top/#/book/1201");
+}
+
+#[test]
+fn page_list() {
+	load();
+	let pages = source::get_page_list("1201".into(), "1201".into()).unwrap();
+	let urls = pages.iter().map(|a| a.url.as_str()).collect::<Vec<_>>();
+	assert_eq!(
+		urls,
+		[
+			"https://img.noy.asia/1201/1.webp",
+			"https://img.noy.asia/1201/2.webp",
+			"https://img.noy.asia/1201/3.webp"
+		]
+	);
+}
+
+#[test]
+fn handle_manga_url() {
+	load();
+	let link = source::handle_url("https://noy1.top/#/book/1201".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "1201");
+	assert!(link.chapter.is_none());
+}
+
+#[test]
+fn handle_chapter_url() {
+	load();
+	let link = source::handle_url("https://noy1.top/#/read/1201".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "1201");
+	assert_eq!(link.chapter.unwrap().id, "1201");
+}
@@ -0,0 +1,89 @@
+use aidoku::testing::*;
+use aidoku_se8 as source;
+
+fn load() {
+	zh_harness::load("zh.se8");
+}
+
+#[test]
+fn manga_list() {
+	load();
+	let result = source::get_manga_list(Vec::new(), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(result.manga[0].id, "yeonae");
+	assert_eq!(result.manga[0].title, "恋爱辅助器");
+	assert_eq!(result.manga[0].cover, "https://img.se8.us/cover/yeonae.jpg");
+}
+
+#[test]
+fn filtered_manga_list() {
+	load();
+	let filters = vec![
+		select_filter("标签", 1),
+		select_filter("进度", 2),
+		sort_filter("排序", 1, false),
+	];
+	let result = source::get_manga_list(filters, 2).unwrap();
+	assert_eq!(result.manga.len(), 2);
+}
+
+#[test]
+fn search() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("恋爱")], 1).unwrap();
+	assert_eq!(result.manga[1].cover, "https://img.se8.us/cover/gym.jpg");
+}
+
+#[test]
+fn manga_details() {
+	load();
+	let manga = source::get_manga_details("yeonae".into()).unwrap();
+	assert_eq!(manga.id, "3344");
+	assert_eq!(manga.title, "恋爱辅助器");
+	assert_eq!(manga.cover, "https://img.se8.us/cover/yeonae.jpg");
+	assert_eq!(manga.author, "作者甲, 作者乙");
+	assert_eq!(manga.description, "一台神奇的机器。");
+	assert_eq!(manga.categories, ["韩漫", "剧情"]);
+}
+
+#[test]
+fn chapter_list() {
+	load();
+	let chapters = source::get_chapter_list("3344".into()).unwrap();
+	let titles = chapters
+		.iter()
+		.map(|a| a.title.as_str())
+		.collect::<Vec<_>>();
+	assert_eq!(titles, ["第2话 “约定”", "第1话 <开始>"]);
+	assert_eq!(chapters[0].url, "https://se8.us/index.php/chapter/88002");
+}
+
+#[test]
+fn page_list() {
+	load();
+	let pages = source::get_page_list("3344".into(), "88001".into()).unwrap();
+	let urls = pages.iter().map(|a| a.url.as_str()).collect::<Vec<_>>();
+	assert_eq!(
+		urls,
+		[
+			"https://img.se8.us/88001/01.jpg",
+			"https://img.se8.us/88001/02.jpg"
+		]
+	);
+}
+
+#[test]
+fn handle_manga_url() {
+	load();
+	let link = source::handle_url("https://se8.us/index.php/comic/yeonae".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "3344");
+	assert!(link.chapter.is_none());
+}
+
+#[test]
+fn handle_chapter_url() {
+	load();
+	let link = source::handle_url("https://se8.us/index.php/chapter/88001".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "3344");
+	assert_eq!(link.chapter.unwrap().id, "88001");
+}
@@ -0,0 +1,139 @@
+use aidoku::{std::net::Request, testing::*};
+use aidoku_wnacg as source;
+
+fn load() {
+	zh_harness::load("zh.wnacg");
+}
+
+#[test]
+fn manga_list() {
+	load();
+	let result = source::get_manga_list(Vec::new(), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(result.manga[0].id, "250001");
+	assert_eq!(result.manga[0].title, "[中文] 夏天");
+	assert_eq!(result.manga[1].title, "[中文] 冬天 & 雪");
+	assert_eq!(
+		result.manga[0].cover,
+		"https://img5.qy0.ru/data/t/250001.jpg"
+	);
+}
+
+#[test]
+fn filtered_manga_list() {
+	load();
+	let filters = vec![select_filter("类别", 1), select_filter("语言", 2)];
+	let result = source::get_manga_list(filters, 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+}
+
+#[test]
+fn search() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("夏天")], 1).unwrap();
+	assert_eq!(result.manga[1].id, "250002");
+}
+
+#[test]
+fn manga_listing() {
+	load();
+	let result = source::get_manga_listing(listing("Cosplay"), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+}
+
+#[test]
+fn manga_details() {
+	load();
+	let manga = source::get_manga_details("250001".into()).unwrap();
+	assert_eq!(manga.title, "[中文] 夏天");
+	assert_eq!(manga.cover, "https://img5.qy0.ru/data/t/250001.jpg");
+	assert_eq!(manga.categories, ["同人誌", "漢化", "全彩", "巨乳"]);
+}
+
+#[test]
+fn chapter_list() {
+	load();
+	let chapters = source::get_chapter_list("250001".into()).unwrap();
+	assert_eq!(chapters.len(), 1);
+	assert_eq!(
+		chapters[0].url,
+		"https://www.wnacg01.cc/photos-index-aid-250001.html"
+	);
+}
+
+#[test]
+fn page_list() {
+	load();
+	let pages = source::get_page_list("250001".into(), "250001".into()).unwrap();
+	let urls = pages.iter().map(|a| a.url.as_str()).collect::<Vec<_>>();
+	assert_eq!(
+		urls,
+		[
+			"https://img5.qy0.ru/data/250001/01.jpg",
+			"https://img5.qy0.ru/data/250001/02.jpg"
+		]
+	);
+}
+
+#[test]
+fn handle_manga_url() {
+	load();
+	let link =
+		source::handle_url("https://www.wnacg.com/photos-index-aid-250001.html".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "250001");
+	assert!(link.chapter.is_none());
+}
+
+#[test]
+fn handle_chapter_url() {
+	load();
+	let link =
+		source::handle_url("https://www.wnacg01.cc/photos-slide-aid-250001.html".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "250001");
+	assert_eq!(link.chapter.unwrap().id, "250001");
+}
+
+#[test]
+fn fails_over_to_next_mirror() {
+	load();
+	let result = source::get_manga_list(Vec::new(), 2).unwrap();
+	assert_eq!(result.manga.len(), 2);
+	source::get_manga_details("250001".into()).unwrap();
+	assert_eq!(
+		requests(),
+		[
+			"GET https://www.wnacg01.cc/albums-index-page-2-cate-.html",
+			"GET https://www.wnacg.com/albums-index-page-2-cate-.html",
+			"GET https://www.wnacg.ru/albums-index-page-2-cate-.html",
+			"GET https://www.wnacg.ru/photos-index-aid-250001.html"
+		]
+	);
+	let mirror = default_value("mirror").unwrap();
+	assert_eq!(mirror.as_string().unwrap().read(), "wnacg.ru");
+}
+
+#[test]
+fn domain_setting_overrides_mirrors() {
+	load();
+	set_default("domain", "https://www.wnacg.ru/");
+	let manga = source::get_manga_details("250001".into()).unwrap();
+	assert_eq!(
+		manga.url,
+		"https://www.wnacg.ru/photos-index-aid-250001.html"
+	);
+	assert_eq!(
+		requests(),
+		["GET https://www.wnacg.ru/photos-index-aid-250001.html"]
+	);
+}
+
+#[test]
+fn image_request() {
+	load();
+	set_default("cf_cookie", "cf_clearance=abc123");
+	let request = Request::get("https://img5.qy0.ru/data/2401/01/01.jpg");
+	source::modify_image_request(request.clone());
+	let headers = request.headers();
+	assert!(headers.contains(&("Referer".into(), "https://www.wnacg01.cc".into())));
+	assert!(headers.contains(&("Cookie".into(), "cf_clearance=abc123".into())));
+}
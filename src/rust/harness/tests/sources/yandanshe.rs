@@ -0,0 +1,98 @@
+use aidoku::{testing::*, MangaStatus};
+use aidoku_yandanshe as source;
+
+fn load() {
+	zh_harness::load("zh.yandanshe");
+}
+
+#[test]
+fn manga_list() {
+	load();
+	let result = source::get_manga_list(Vec::new(), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(result.manga[0].id, "12345");
+	assert_eq!(result.manga[0].title, "夜晚的约定");
+	assert_eq!(
+		result.manga[0].cover,
+		"https://yandanshe.com/wp-content/uploads/12345.jpg"
+	);
+}
+
+#[test]
+fn filtered_manga_list() {
+	load();
+	let filters = vec![
+		select_filter("分类", 1),
+		select_filter("状态", 1),
+		select_filter("标签", 1),
+		sort_filter("排序", 1, false),
+	];
+	let result = source::get_manga_list(filters, 2).unwrap();
+	assert_eq!(result.manga.len(), 2);
+}
+
+#[test]
+fn search() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("夜晚")], 1).unwrap();
+	assert_eq!(result.manga[1].title, "雨中的告白");
+}
+
+#[test]
+fn manga_details() {
+	load();
+	let manga = source::get_manga_details("12345".into()).unwrap();
+	assert_eq!(manga.title, "夜晚的约定");
+	assert_eq!(manga.author, "作者甲");
+	assert_eq!(manga.description, "两个人的夜晚。");
+	assert_eq!(manga.categories, ["BL", "韓漫", "校園", "職場"]);
+	assert_eq!(manga.status, MangaStatus::Ongoing);
+}
+
+#[test]
+fn chapter_list() {
+	load();
+	let chapters = source::get_chapter_list("12345".into()).unwrap();
+	let ids = chapters.iter().map(|a| a.id.as_str()).collect::<Vec<_>>();
+	assert_eq!(ids, ["2", "1"]);
+	assert_eq!(chapters[0].title, "第 2 话");
+	assert_eq!(chapters[0].url, "https://yandanshe.com/12345/2/");
+}
+
+#[test]
+fn single_chapter() {
+	load();
+	let chapters = source::get_chapter_list("12346".into()).unwrap();
+	assert_eq!(chapters.len(), 1);
+	assert_eq!(chapters[0].url, "https://yandanshe.com/12346/1/");
+}
+
+#[test]
+fn page_list() {
+	load();
+	let pages = source::get_page_list("12345".into(), "1".into()).unwrap();
+	let urls = pages.iter().map(|a| a.url.as_str()).collect::<Vec<_>>();
+	assert_eq!(
+		urls,
+		[
+			"https://yandanshe.com/img/12345/1/01.jpg",
+			"https://yandanshe.com/img/12345/1/02.jpg"
+		]
+	);
+}
+
+#[test]
+fn handle_manga_url() {
+	load();
+	let link = source::handle_url("https://yandanshe.com/12345".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "12345");
+	assert!(link.chapter.is_none());
+}
+
+#[test]
+fn handle_chapter_url() {
+	load();
+	let link = source::handle_url("https://yandanshe.com/12345/2/".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "12345");
+	assert_eq!(link.chapter.unwrap().id, "2");
+}
@@ -0,0 +1,119 @@
+use aidoku::{testing::*, MangaStatus};
+use aidoku_zaimanhua as source;
+
+fn load() {
+	zh_harness::load("zh.zaimanhua");
+}
+
+#[test]
+fn manga_list() {
+	load();
+	let result = source::get_manga_list(Vec::new(), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(result.manga[0].id, "66632");
+	assert_eq!(result.manga[0].title, "镖人");
+	assert_eq!(
+		result.manga[0].cover,
+		"https://images.zaimanhua.com/cover/66632.jpg"
+	);
+}
+
+#[test]
+fn filtered_manga_list() {
+	load();
+	let filters = vec![
+		select_filter("状态", 2),
+		select_filter("受众", 1),
+		select_filter("题材", 1),
+		select_filter("类别", 1),
+		select_filter("字母", 1),
+	];
+	let result = source::get_manga_list(filters, 2).unwrap();
+	assert_eq!(result.manga.len(), 2);
+}
+
+#[test]
+fn search() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("镖人")], 1).unwrap();
+	assert_eq!(result.manga.len(), 1);
+	assert_eq!(result.manga[0].title, "镖人");
+}
+
+#[test]
+fn manga_listing() {
+	load();
+	let result = source::get_manga_listing(listing("月人气排行"), 1).unwrap();
+	assert_eq!(result.manga[0].id, "66633");
+}
+
+#[test]
+fn manga_details() {
+	load();
+	let manga = source::get_manga_details("66632".into()).unwrap();
+	assert_eq!(manga.title, "镖人");
+	assert_eq!(manga.author, "许先哲");
+	assert_eq!(manga.description, "隋末的江湖。");
+	assert_eq!(manga.categories, ["武侠", "历史"]);
+	assert_eq!(manga.status, MangaStatus::Ongoing);
+	assert_eq!(
+		manga.url,
+		"https://m.zaimanhua.com/pages/comic/detail?id=66632"
+	);
+}
+
+#[test]
+fn chapter_list() {
+	load();
+	let chapters = source::get_chapter_list("66632".into()).unwrap();
+	let ids = chapters.iter().map(|a| a.id.as_str()).collect::<Vec<_>>();
+	assert_eq!(ids, ["1002", "1001"]);
+	assert_eq!(chapters[0].chapter, 2.0);
+	assert_eq!(chapters[0].date_updated, 1705017600.0);
+}
+
+#[test]
+fn manga_details_traditional() {
+	load();
+	set_default("conversion", "2");
+	let manga = source::get_manga_details("66632".into()).unwrap();
+	assert_eq!(manga.title, "鏢人");
+	assert_eq!(manga.author, "許先哲");
+	assert_eq!(manga.categories, ["武俠", "歷史"]);
+	let chapters = source::get_chapter_list("66632".into()).unwrap();
+	assert_eq!(chapters[0].title, "第2話");
+}
+
+#[test]
+fn page_list() {
+	load();
+	let pages = source::get_page_list("66632".into(), "1001".into()).unwrap();
+	let urls = pages.iter().map(|a| a.url.as_str()).collect::<Vec<_>>();
+	assert_eq!(
+		urls,
+		[
+			"https://images.zaimanhua.com/1001/01.jpg",
+			"https://images.zaimanhua.com/1001/02.jpg"
+		]
+	);
+}
+
+#[test]
+fn handle_manga_url() {
+	load();
+	let link =
+		source::handle_url("https://m.zaimanhua.com/pages/comic/detail?id=66632".into()).unwrap();
+	assert_eq!(link.manga.unwrap().id, "66632");
+	assert!(link.chapter.is_none());
+}
+
+#[test]
+fn handle_chapter_url() {
+	load();
+	let link = source::handle_url(
+		"https://m.zaimanhua.com/pages/comic/page?comic_id=66632&chapter_id=1002".into(),
+	)
+	.unwrap();
+	assert_eq!(link.manga.unwrap().id, "66632");
+	assert_eq!(link.chapter.unwrap().id, "1002");
+}
@@ -0,0 +1,115 @@
+use aidoku::{testing::*, MangaStatus};
+use aidoku_zerobywns as source;
+
+fn load() {
+	zh_harness::load("zh.zerobywns");
+}
+
+#[test]
+fn manga_list() {
+	load();
+	let result = source::get_manga_list(Vec::new(), 1).unwrap();
+	assert_eq!(result.manga.len(), 2);
+	assert_eq!(result.manga[0].id, "101");
+	assert_eq!(result.manga[0].title, "辉夜大小姐想让我告白");
+	assert_eq!(
+		result.manga[0].cover,
+		"http://www.zerobywav.com/cover/101.jpg"
+	);
+}
+
+#[test]
+fn filtered_manga_list() {
+	load();
+	let filters = vec![
+		select_filter("分类", 1),
+		select_filter("进度", 2),
+		select_filter("性质", 3),
+		select_filter("地区", 1),
+		sort_filter("排序", 1, true),
+	];
+	let result = source::get_manga_list(filters, 2).unwrap();
+	assert_eq!(result.manga.len(), 2);
+}
+
+#[test]
+fn search() {
+	load();
+	let result = source::get_manga_list(vec![title_filter("辉夜")], 1).unwrap();
+	assert_eq!(result.manga[1].id, "102");
+	assert_eq!(result.manga[1].title, "咒术回战");
+}
+
+#[test]
+fn manga_details() {
+	load();
+	let manga = source::get_manga_details("101".into()).unwrap();
+	assert_eq!(manga.title, "辉夜大小姐想让我告白");
+	assert_eq!(manga.author, "赤坂明, 其他");
+	assert_eq!(manga.description, "天才们的恋爱头脑战。");
+	assert_eq!(manga.categories, ["恋爱", "校园"]);
+	assert_eq!(manga.status, MangaStatus::Completed);
+}
+
+#[test]
+fn chapter_list() {
+	load();
+	let chapters = source::get_chapter_list("101".into()).unwrap();
+	let ids = chapters.iter().map(|a| a.id.as_str()).collect::<Vec<_>>();
+	assert_eq!(ids, ["5002", "5001"]);
+	assert_eq!(
+		chapters[0].url,
+		"http://www.zerobywav.com/plugin.php?id=jameson_manhua&a=read&zjid=5002"
+	);
+}
+
+#[test]
+fn page_list_logs_in() {
+	load();
+	set_default("username", "reader");
+	set_default("password", "secret");
+	let pages = source::get_page_list("101".into(), "5001".into()).unwrap();
+	let urls = pages.iter().map(|a| a.url.as_str()).collect::<Vec<_>>();
+	assert_eq!(
+		urls,
+		[
+			"http://www.zerobywav.com/img/5001/01.jpg",
+			"http://www.zerobywav.com/img/5001/02.jpg"
+		]
+	);
+	let cookie = default_value("cookie").unwrap();
+	assert_eq!(
+		cookie.as_string().unwrap().read(),
+		"Ckng_2132_auth=4uth;Ckng_2132_saltkey=s4lt"
+	);
+}
+
+#[test]
+fn page_list_without_account() {
+	load();
+	assert!(source::get_page_list("101".into(), "5001".into()).is_err());
+}
+
+#[test]
+fn handle_manga_url() {
+	load();
+	let link = source::handle_url(
+		"http://www.zerobywav.com/plugin.php?id=jameson_manhua&c=index&a=bofang&kuid=101".into(),
+	)
+	.unwrap();
+	assert_eq!(link.manga.unwrap().id, "101");
+	assert!(link.chapter.is_none());
+}
+
+#[test]
+fn handle_chapter_url() {
+	load();
+	set_default("username", "reader");
+	set_default("password", "secret");
+	let link = source::handle_url(
+		"http://www.zerobywav.com/plugin.php?id=jameson_manhua&a=read&zjid=5001".into(),
+	)
+	.unwrap();
+	assert_eq!(link.manga.unwrap().id, "101");
+	assert_eq!(link.chapter.unwrap().id, "5001");
+}
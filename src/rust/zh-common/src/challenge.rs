@@ -0,0 +1,92 @@
+//! Cloudflare challenge pages, and the cookies that let later requests
+//! through once the challenge has been passed.
+
+use aidoku::std::{
+	defaults::{defaults_get, defaults_set},
+	String, StringRef, Vec,
+};
+use alloc::string::ToString;
+
+/// Where the Cloudflare cookies are kept, as a `Cookie` header value.
+const COOKIE_KEY: &str = "cf_cookie";
+/// The cookies Cloudflare checks instead of showing the challenge again.
+const COOKIE_NAMES: &[&str] = &["cf_clearance", "__cf_bm"];
+/// Text that only challenge pages contain.
+const MARKERS: &[&str] = &["cf-chl", "Just a moment...", "__cf_bm"];
+
+/// Whether a response with `status` may be a challenge, and so needs its
+/// body read before the source parses it.
+pub(crate) fn possible(status: i32) -> bool {
+	status == 403 || status == 503
+}
+
+/// Whether a response is a challenge page rather than the site's own error
+/// page.
+pub(crate) fn detected(status: i32, data: &[u8]) -> bool {
+	if !possible(status) {
+		return false;
+	}
+	let text = String::from_utf8_lossy(data);
+	MARKERS.iter().any(|a| text.contains(a))
+}
+
+/// The saved cookies, to send along with `cookie`, the request's own
+/// `Cookie` header.
+pub(crate) fn cookie(cookie: Option<&str>) -> Option<String> {
+	let saved = defaults_get(COOKIE_KEY)
+		.and_then(|a| a.as_string())
+		.map(|a| a.read())
+		.unwrap_or_default();
+	match (cookie.filter(|a| !a.is_empty()), saved.is_empty()) {
+		(Some(cookie), false) => Some([cookie, saved.as_str()].join("; ")),
+		(Some(cookie), true) => Some(cookie.to_string()),
+		(None, false) => Some(saved),
+		(None, true) => None,
+	}
+}
+
+/// Saves the Cloudflare cookies in a `Set-Cookie` header, keeping the ones
+/// it does not replace.
+pub(crate) fn remember(set_cookie: &str) {
+	let found = COOKIE_NAMES
+		.iter()
+		.filter_map(|name| value(set_cookie, name).map(|value| (*name, value)))
+		.collect::<Vec<_>>();
+	if found.is_empty() {
+		return;
+	}
+
+	let saved = cookie(None).unwrap_or_default();
+	let cookies = COOKIE_NAMES
+		.iter()
+		.filter_map(|name| {
+			let value = match found.iter().find(|(found, _)| found == name) {
+				Some((_, value)) => Some(value.clone()),
+				None => value(&saved, name),
+			};
+			value.map(|value| [*name, "=", &value].concat())
+		})
+		.collect::<Vec<String>>()
+		.join("; ");
+	defaults_set(COOKIE_KEY, StringRef::from(cookies).0);
+}
+
+/// Drops the saved cookies once a challenge shows they no longer pass.
+///
+/// The user passes the challenge in the app's web view, which keeps the new
+/// cookies itself and adds them to later requests, except for names the
+/// request already sends. A stale saved copy would hide them.
+pub(crate) fn forget() {
+	defaults_set(COOKIE_KEY, StringRef::from("").0);
+}
+
+/// The value of cookie `name` in a `Cookie` or `Set-Cookie` header, where
+/// several cookies may be joined by `;` or `,`.
+fn value(header: &str, name: &str) -> Option<String> {
+	header
+		.split([';', ','])
+		.filter_map(|a| a.trim().split_once('='))
+		.find(|(key, _)| *key == name)
+		.map(|(_, value)| value.to_string())
+		.filter(|a| !a.is_empty())
+}
@@ -0,0 +1,258 @@
+//! Chapter and volume numbers read from chapter titles.
+
+use aidoku::std::{String, Vec};
+
+use crate::entity;
+
+const CHAPTER_UNITS: &[char] = &['话', '話', '回', '章', '集', '节', '節'];
+const VOLUME_UNITS: &[char] = &['卷', '册', '冊'];
+const CHAPTER_PREFIXES: &[&str] = &["chapter", "ch", "episode", "ep", "#"];
+const VOLUME_PREFIXES: &[&str] = &["volume", "vol"];
+const EXTRA_KEYWORDS: &[&str] = &[
+	"番外",
+	"特别篇",
+	"特別篇",
+	"特别话",
+	"特別話",
+	"特典",
+	"外传",
+	"外傳",
+	"休刊",
+	"公告",
+	"通知",
+	"请假",
+	"請假",
+	"预告",
+	"預告",
+	"彩蛋",
+	"附录",
+	"附錄",
+	"后记",
+	"後記",
+];
+/// Suffixes that make a leading number a date rather than a chapter.
+const DATE_UNITS: &[char] = &['年', '月', '日'];
+
+/// The numbers found in a chapter title.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ChapterNumber {
+	pub chapter: Option<f32>,
+	pub volume: Option<f32>,
+	/// Side stories, specials and notices (番外, 特别篇, 休刊公告, …), which
+	/// should not take a place in the main numbering.
+	pub extra: bool,
+}
+
+impl ChapterNumber {
+	/// The value for `Chapter::chapter`: `-1` when unknown or an extra.
+	pub fn chapter(&self) -> f32 {
+		match self.chapter {
+			Some(chapter) if !self.extra => chapter,
+			_ => -1.0,
+		}
+	}
+
+	/// Like [`chapter`](Self::chapter), but falls back to a number the site
+	/// provides, such as an upload order, for titles without one.
+	pub fn chapter_or(&self, fallback: f32) -> f32 {
+		match self.chapter {
+			None if !self.extra => fallback,
+			_ => self.chapter(),
+		}
+	}
+
+	/// The value for `Chapter::volume`: `-1` when unknown.
+	pub fn volume(&self) -> f32 {
+		self.volume.unwrap_or(-1.0)
+	}
+}
+
+struct Token {
+	start: usize,
+	end: usize,
+	value: f32,
+	chinese: bool,
+}
+
+pub(crate) fn normalize(title: &str) -> Vec<char> {
+	title
+		.chars()
+		.map(|a| match a {
+			'\u{3000}' => ' ',
+			'\u{ff01}'..='\u{ff5e}' => char::from_u32(a as u32 - 0xfee0).unwrap_or(a),
+			_ => a,
+		})
+		.map(|a| a.to_ascii_lowercase())
+		.collect()
+}
+
+fn chinese_digit(char: char) -> Option<u32> {
+	match char {
+		'零' | '〇' => Some(0),
+		'一' | '壹' => Some(1),
+		'二' | '两' | '兩' | '贰' | '貳' => Some(2),
+		'三' | '叁' | '參' => Some(3),
+		'四' | '肆' => Some(4),
+		'五' | '伍' => Some(5),
+		'六' | '陆' | '陸' => Some(6),
+		'七' | '柒' => Some(7),
+		'八' | '捌' => Some(8),
+		'九' | '玖' => Some(9),
+		_ => None,
+	}
+}
+
+fn chinese_unit(char: char) -> Option<u32> {
+	match char {
+		'十' | '拾' => Some(10),
+		'百' | '佰' => Some(100),
+		'千' | '仟' => Some(1000),
+		'万' | '萬' => Some(10000),
+		_ => None,
+	}
+}
+
+pub(crate) fn is_chinese_numeral(char: char) -> bool {
+	chinese_digit(char).is_some() || chinese_unit(char).is_some()
+}
+
+/// Reads `十二`, `一百零五` and digit-by-digit forms like `二〇`.
+pub(crate) fn chinese_value(chars: &[char]) -> u32 {
+	if chars.iter().all(|a| chinese_digit(*a).is_some()) {
+		return chars
+			.iter()
+			.filter_map(|a| chinese_digit(*a))
+			.fold(0, |total, digit| total * 10 + digit);
+	}
+
+	let mut total = 0;
+	let mut section = 0;
+	let mut number = 0;
+	for char in chars {
+		if let Some(digit) = chinese_digit(*char) {
+			number = digit;
+		} else if let Some(unit) = chinese_unit(*char) {
+			if unit == 10000 {
+				total += (section + number) * unit;
+				section = 0;
+			} else {
+				section += number.max(1) * unit;
+			}
+			number = 0;
+		}
+	}
+	total + section + number
+}
+
+fn tokens(chars: &[char]) -> Vec<Token> {
+	let mut tokens = Vec::new();
+	let mut index = 0;
+
+	while index < chars.len() {
+		let start = index;
+		if chars[index].is_ascii_digit() {
+			while index < chars.len() && chars[index].is_ascii_digit() {
+				index += 1;
+			}
+			if index + 1 < chars.len() && chars[index] == '.' && chars[index + 1].is_ascii_digit() {
+				index += 1;
+				while index < chars.len() && chars[index].is_ascii_digit() {
+					index += 1;
+				}
+			}
+			let text = chars[start..index].iter().collect::<String>();
+			if let Ok(value) = text.parse::<f32>() {
+				tokens.push(Token {
+					start,
+					end: index,
+					value,
+					chinese: false,
+				});
+			}
+		} else if is_chinese_numeral(chars[index]) {
+			while index < chars.len() && is_chinese_numeral(chars[index]) {
+				index += 1;
+			}
+			tokens.push(Token {
+				start,
+				end: index,
+				value: chinese_value(&chars[start..index]) as f32,
+				chinese: true,
+			});
+		} else {
+			index += 1;
+		}
+	}
+
+	tokens
+}
+
+/// The first non-space character after `index`.
+fn next_char(chars: &[char], index: usize) -> Option<char> {
+	chars[index..].iter().find(|a| !a.is_whitespace()).copied()
+}
+
+/// The text before `index` with trailing spaces and dots removed, so that
+/// `Vol. 2` and `ch.10` both expose their prefix.
+fn prefix(chars: &[char], index: usize) -> String {
+	let mut end = index;
+	while end > 0 && (chars[end - 1].is_whitespace() || chars[end - 1] == '.') {
+		end -= 1;
+	}
+	chars[..end].iter().collect()
+}
+
+fn has_prefix(prefix: &str, words: &[&str]) -> bool {
+	words.iter().any(|word| {
+		prefix.ends_with(word)
+			&& !prefix[..prefix.len() - word.len()]
+				.chars()
+				.last()
+				.is_some_and(|a| a.is_ascii_alphabetic())
+	})
+}
+
+/// Pulls the chapter and volume numbers out of titles such as `第12.5话`,
+/// `第三卷`, `Vol.2 Ch.10` or `番外1`, including full-width digits and
+/// Chinese numerals.
+pub fn parse<T: AsRef<str>>(title: T) -> ChapterNumber {
+	let chars = normalize(&entity::decode(title));
+	let text = chars.iter().collect::<String>();
+	let mut number = ChapterNumber {
+		extra: EXTRA_KEYWORDS.iter().any(|a| text.contains(a)),
+		..Default::default()
+	};
+	let tokens = tokens(&chars);
+
+	for token in &tokens {
+		let next = next_char(&chars, token.end);
+		let prefix = prefix(&chars, token.start);
+		let ordinal = prefix.ends_with('第');
+
+		let volume = next.is_some_and(|a| VOLUME_UNITS.contains(&a))
+			|| (!token.chinese && has_prefix(&prefix, VOLUME_PREFIXES));
+		let chapter = next.is_some_and(|a| CHAPTER_UNITS.contains(&a))
+			|| (!token.chinese && has_prefix(&prefix, CHAPTER_PREFIXES))
+			|| (ordinal && !volume);
+
+		if volume && number.volume.is_none() {
+			number.volume = Some(token.value);
+		} else if chapter && number.chapter.is_none() {
+			number.chapter = Some(token.value);
+		}
+	}
+
+	if number.chapter.is_none() {
+		// Bare numbers: a title that starts with one (`12`, `12.5 重逢`), or
+		// the first one in an extra (`番外1`).
+		number.chapter = tokens
+			.iter()
+			.filter(|a| !a.chinese)
+			.filter(|a| !next_char(&chars, a.end).is_some_and(|a| DATE_UNITS.contains(&a)))
+			.find(|a| number.extra || chars[..a.start].iter().all(|a| a.is_whitespace()))
+			.filter(|_| number.volume.is_none() || number.extra)
+			.map(|a| a.value);
+	}
+
+	number
+}
@@ -0,0 +1,125 @@
+//! Simplified/Traditional Chinese conversion for source metadata, chosen
+//! through each source's `conversion` setting.
+
+use aidoku::{
+	std::{defaults::defaults_get, String, Vec},
+	Chapter, Manga,
+};
+
+use crate::entity;
+
+mod table;
+
+/// How to convert text, from the `conversion` setting.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Conversion {
+	/// Text stays as the site serves it.
+	#[default]
+	Keep,
+	Simplified,
+	Traditional,
+}
+
+impl Conversion {
+	/// Reads the `conversion` setting: `1` for Simplified, `2` for
+	/// Traditional, anything else keeps the original text.
+	pub fn from_settings() -> Self {
+		let value = defaults_get("conversion")
+			.and_then(|a| a.as_string())
+			.map(|a| a.read())
+			.unwrap_or_default();
+		match value.as_str() {
+			"1" => Self::Simplified,
+			"2" => Self::Traditional,
+			_ => Self::Keep,
+		}
+	}
+
+	pub fn apply<T: AsRef<str>>(&self, text: T) -> String {
+		let text = text.as_ref();
+		match self {
+			Self::Keep => String::from(text),
+			Self::Simplified => convert(text, table::T2S_CHARS, table::T2S_PHRASES),
+			Self::Traditional => convert(text, table::S2T_CHARS, table::S2T_PHRASES),
+		}
+	}
+}
+
+/// Converts phrase by phrase, preferring the longest phrase that matches
+/// and falling back to single characters.
+fn convert(text: &str, chars: &[(char, char)], phrases: &[(&str, &str)]) -> String {
+	let mut result = String::with_capacity(text.len());
+	let mut rest = text;
+
+	while let Some(char) = rest.chars().next() {
+		let phrase = phrases
+			.iter()
+			.filter(|(from, _)| rest.starts_with(from))
+			.max_by_key(|(from, _)| from.len());
+		if let Some((from, to)) = phrase {
+			result.push_str(to);
+			rest = &rest[from.len()..];
+			continue;
+		}
+		match chars.binary_search_by_key(&char, |(from, _)| *from) {
+			Ok(index) => result.push(chars[index].1),
+			Err(_) => result.push(char),
+		}
+		rest = &rest[char.len_utf8()..];
+	}
+
+	result
+}
+
+/// Converts a string to Simplified Chinese.
+pub fn to_simplified<T: AsRef<str>>(text: T) -> String {
+	Conversion::Simplified.apply(text)
+}
+
+/// Converts a string to Traditional Chinese.
+pub fn to_traditional<T: AsRef<str>>(text: T) -> String {
+	Conversion::Traditional.apply(text)
+}
+
+/// A search query as typed, followed by its Simplified and Traditional forms
+/// where they differ, for sites that index titles in both scripts.
+pub fn variants<T: AsRef<str>>(query: T) -> Vec<String> {
+	let query = query.as_ref();
+	let mut variants = Vec::from([String::from(query)]);
+	for variant in [to_simplified(query), to_traditional(query)] {
+		if !variants.contains(&variant) {
+			variants.push(variant);
+		}
+	}
+	variants
+}
+
+/// Decodes entities in the text of a manga's details and applies the
+/// `conversion` setting.
+pub fn manga(manga: Manga) -> Manga {
+	let conversion = Conversion::from_settings();
+	let text = |text: &str| conversion.apply(entity::decode(text));
+	Manga {
+		title: text(&manga.title),
+		author: text(&manga.author),
+		artist: text(&manga.artist),
+		description: text(&manga.description),
+		categories: manga.categories.iter().map(|a| text(a)).collect(),
+		..manga
+	}
+}
+
+/// Decodes entities in chapter titles and scanlators and applies the
+/// `conversion` setting.
+pub fn chapters(chapters: Vec<Chapter>) -> Vec<Chapter> {
+	let conversion = Conversion::from_settings();
+	let text = |text: &str| conversion.apply(entity::decode(text));
+	chapters
+		.into_iter()
+		.map(|chapter| Chapter {
+			title: text(&chapter.title),
+			scanlator: text(&chapter.scanlator),
+			..chapter
+		})
+		.collect()
+}
@@ -0,0 +1,163 @@
+//! Chapter dates as the sites print them, absolute or relative.
+
+use aidoku::std::{current_date, String, Vec};
+
+use crate::chapter;
+
+/// Sites print local times in China Standard Time.
+const OFFSET: i64 = 8 * 3600;
+const DAY: i64 = 86400;
+
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+	let year = if month <= 2 { year - 1 } else { year };
+	let era = year.div_euclid(400);
+	let yoe = year.rem_euclid(400);
+	let doy = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+	let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+	era * 146097 + doe - 719468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+	let days = days + 719468;
+	let era = days.div_euclid(146097);
+	let doe = days.rem_euclid(146097);
+	let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+	let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+	let mp = (5 * doy + 2) / 153;
+	let day = doy - (153 * mp + 2) / 5 + 1;
+	let month = if mp < 10 { mp + 3 } else { mp - 9 };
+	let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+	(year, month, day)
+}
+
+/// Runs of digits, or of Chinese numerals when there are no digits.
+fn numbers(chars: &[char]) -> Vec<i64> {
+	let mut numbers = Vec::new();
+	let mut current = String::new();
+	for char in chars.iter().chain([' '].iter()) {
+		if char.is_ascii_digit() {
+			current.push(*char);
+		} else if !current.is_empty() {
+			numbers.push(current.parse::<i64>().unwrap_or_default());
+			current.clear();
+		}
+	}
+	if numbers.is_empty() {
+		let mut start = None;
+		for (index, char) in chars.iter().chain([' '].iter()).enumerate() {
+			match (chapter::is_chinese_numeral(*char), start) {
+				(true, None) => start = Some(index),
+				(false, Some(from)) => {
+					numbers.push(chapter::chinese_value(&chars[from..index]) as i64);
+					start = None;
+				}
+				_ => {}
+			}
+		}
+	}
+	numbers
+}
+
+/// Seconds into the day for `HH:mm[:ss]` values.
+fn time_of_day(numbers: &[i64]) -> Option<i64> {
+	let hour = numbers.first().copied().unwrap_or_default();
+	let minute = numbers.get(1).copied().unwrap_or_default();
+	let second = numbers.get(2).copied().unwrap_or_default();
+	(hour < 24 && minute < 60 && second < 60).then_some(hour * 3600 + minute * 60 + second)
+}
+
+fn relative(text: &str, numbers: &[i64], now: i64) -> Option<i64> {
+	let units: &[(&[&str], i64)] = &[
+		(&["秒"], 1),
+		(&["分"], 60),
+		(&["时", "時", "钟头", "鐘頭"], 3600),
+		(&["天", "日"], DAY),
+		(&["周", "週", "星期", "礼拜", "禮拜"], 7 * DAY),
+		(&["月"], 30 * DAY),
+		(&["年"], 365 * DAY),
+	];
+	let (_, unit) = units
+		.iter()
+		.find(|(words, _)| words.iter().any(|a| text.contains(a)))?;
+	Some(now - numbers.first().copied().unwrap_or(1) * unit)
+}
+
+fn absolute(text: &str, numbers: &[i64], now: i64) -> Option<i64> {
+	let (this_year, _, _) = civil_from_days((now + OFFSET).div_euclid(DAY));
+	let (year, month, day, time) = match numbers {
+		[year, month, day, time @ ..] if *year >= 1000 => (Some(*year), *month, *day, time),
+		[month, day, time @ ..] => (None, *month, *day, time),
+		_ => return None,
+	};
+	if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+		return None;
+	}
+	let offset = if text.ends_with('z') { 0 } else { OFFSET };
+	let time = time_of_day(time)? - offset;
+	let date = days_from_civil(year.unwrap_or(this_year), month, day) * DAY + time;
+
+	// `MM-dd` without a year is the most recent such day.
+	if year.is_none() && date > now + DAY {
+		return Some(days_from_civil(this_year - 1, month, day) * DAY + time);
+	}
+	Some(date)
+}
+
+/// Reads a chapter date such as `3天前`, `昨天 12:30`, `2小时前`,
+/// `2024-01-05`, `2024年1月5日` or `01月05日` into seconds since the epoch,
+/// or `-1` when the text is not a date. Relative forms count back from
+/// `current_date()`.
+pub fn parse<T: AsRef<str>>(text: T) -> f64 {
+	let chars = chapter::normalize(text.as_ref().trim());
+	let text = chars.iter().collect::<String>();
+	let numbers = numbers(&chars);
+	let now = current_date() as i64;
+	let midnight = (now + OFFSET).div_euclid(DAY) * DAY - OFFSET;
+
+	let date = if ["刚刚", "剛剛", "刚才", "剛才", "just now"]
+		.iter()
+		.any(|a| text.contains(a))
+	{
+		Some(now)
+	} else if let Some(days) = [
+		("前天", 2),
+		("昨天", 1),
+		("昨日", 1),
+		("今天", 0),
+		("今日", 0),
+	]
+	.iter()
+	.find(|(word, _)| text.contains(word))
+	.map(|(_, days)| days)
+	{
+		time_of_day(&numbers).map(|time| midnight - days * DAY + time)
+	} else if text.ends_with('前') {
+		relative(&text, &numbers, now)
+	} else {
+		absolute(&text, &numbers, now)
+	};
+
+	date.map(|a| a as f64).unwrap_or(-1.0)
+}
+
+/// Reads an HTTP date such as `Wed, 21 Oct 2015 07:28:00 GMT`, as sent in
+/// `Retry-After` and `Expires` headers, into seconds since the epoch.
+pub fn http<T: AsRef<str>>(text: T) -> Option<f64> {
+	const MONTHS: [&str; 12] = [
+		"jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+	];
+	let text = text.as_ref().to_ascii_lowercase();
+	let (_, date) = text.split_once(", ")?;
+	let parts = date.split_whitespace().collect::<Vec<&str>>();
+	let [day, month, year, time, "gmt"] = parts.as_slice() else {
+		return None;
+	};
+	let month = MONTHS.iter().position(|a| a == month)? as i64 + 1;
+	let numbers = time
+		.split(':')
+		.map(|a| a.parse::<i64>().ok())
+		.collect::<Option<Vec<i64>>>()?;
+	let time = time_of_day(&numbers)?;
+	let date = days_from_civil(year.parse().ok()?, month, day.parse().ok()?) * DAY + time;
+	Some(date as f64)
+}
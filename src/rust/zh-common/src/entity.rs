@@ -0,0 +1,130 @@
+//! HTML character reference decoding for text that sites escape twice or
+//! serve outside of HTML, such as JSON fields and `content` attributes.
+
+use aidoku::{
+	std::{String, Vec},
+	Manga,
+};
+
+mod table;
+
+/// The longest name in the table, `CounterClockwiseContourIntegral;`.
+const MAX_NAME_LEN: usize = 32;
+
+/// What browsers show for `&#128;` to `&#159;`, which pages mean as
+/// Windows-1252 rather than as C1 control characters.
+const WINDOWS_1252: [char; 32] = [
+	'€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8d}', 'Ž', '\u{8f}',
+	'\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9d}', 'ž', 'Ÿ',
+];
+
+/// Decodes named (`&amp;`, `&hearts;`), decimal (`&#12300;`) and hex
+/// (`&#x300C;`) character references the way browsers do, including the
+/// legacy names accepted without a `;`. Anything else is left as is.
+pub fn decode<T: AsRef<str>>(text: T) -> String {
+	let text = text.as_ref();
+	if !text.contains('&') {
+		return String::from(text);
+	}
+
+	let mut result = String::with_capacity(text.len());
+	let mut rest = text;
+	while let Some(index) = rest.find('&') {
+		result.push_str(&rest[..index]);
+		rest = &rest[index..];
+		if let Some((char, len)) = numeric(rest) {
+			result.push(char);
+			rest = &rest[len..];
+		} else if let Some((value, len)) = named(rest) {
+			result.push_str(value);
+			rest = &rest[len..];
+		} else {
+			result.push('&');
+			rest = &rest[1..];
+		}
+	}
+	result.push_str(rest);
+
+	result
+}
+
+/// Decodes the titles of a page of results, which are not passed through
+/// [`convert::manga`](crate::convert::manga) like details are.
+pub fn titles(manga: Vec<Manga>) -> Vec<Manga> {
+	manga
+		.into_iter()
+		.map(|manga| Manga {
+			title: decode(&manga.title),
+			..manga
+		})
+		.collect()
+}
+
+/// Reads `&#…;` or `&#x…;` at the start of `text`, returning the character
+/// and the length of the reference. The `;` is optional.
+fn numeric(text: &str) -> Option<(char, usize)> {
+	let rest = text.strip_prefix("&#")?;
+	let (radix, prefix) = match rest.as_bytes().first() {
+		Some(b'x' | b'X') => (16, 3),
+		_ => (10, 2),
+	};
+	let digits = text[prefix..]
+		.bytes()
+		.take_while(|a| (*a as char).is_digit(radix))
+		.count();
+	if digits == 0 {
+		return None;
+	}
+
+	let value = text[prefix..prefix + digits]
+		.chars()
+		.try_fold(0u32, |total, digit| {
+			total
+				.checked_mul(radix)?
+				.checked_add(digit.to_digit(radix)?)
+		})
+		.unwrap_or(u32::MAX);
+	let char = match value {
+		0x80..=0x9f => WINDOWS_1252[(value - 0x80) as usize],
+		0 => char::REPLACEMENT_CHARACTER,
+		_ => char::from_u32(value).unwrap_or(char::REPLACEMENT_CHARACTER),
+	};
+	let len = prefix + digits;
+	let len = if text[len..].starts_with(';') {
+		len + 1
+	} else {
+		len
+	};
+
+	Some((char, len))
+}
+
+/// Reads a named reference at the start of `text`, returning its value and
+/// length. Without a `;`, the longest legacy name that fits is used, so
+/// `&notit` reads as `¬it`.
+fn named(text: &str) -> Option<(&'static str, usize)> {
+	let name_len = text[1..]
+		.bytes()
+		.take(MAX_NAME_LEN)
+		.take_while(|a| a.is_ascii_alphanumeric())
+		.count();
+	if name_len == 0 {
+		return None;
+	}
+
+	if text[1 + name_len..].starts_with(';') {
+		if let Some(value) = lookup(&text[1..name_len + 2]) {
+			return Some((value, name_len + 2));
+		}
+	}
+	(2..=name_len)
+		.rev()
+		.find_map(|len| lookup(&text[1..1 + len]).map(|value| (value, len + 1)))
+}
+
+fn lookup(name: &str) -> Option<&'static str> {
+	table::ENTITIES
+		.binary_search_by(|(key, _)| (*key).cmp(name))
+		.ok()
+		.map(|index| table::ENTITIES[index].1)
+}
@@ -0,0 +1,112 @@
+//! Failures that sources report to the user instead of an empty screen.
+//!
+//! The app only knows its own error kinds and carries no message with them,
+//! so each [`Error`] is reported under a kind of its own, which tells the
+//! failures apart, and writes its message to the source log for the user.
+
+use aidoku::{
+	error::{AidokuError, AidokuErrorKind, NodeError, Result, ValueCastError},
+	prelude::*,
+	std::String,
+};
+use alloc::string::ToString;
+
+/// Why a source could not read a site.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+	/// The selector or marker the source relies on is gone from the page.
+	MarkupChanged(String),
+	/// The site wants an account, or the saved one was rejected.
+	LoginRequired,
+	RateLimited,
+	/// The site refuses visitors from the user's region.
+	RegionBlocked,
+	/// The site answered with a Cloudflare challenge that only a browser can
+	/// pass.
+	Cloudflare,
+	/// The chapter is sold on the site and cannot be read for free.
+	PaidChapter,
+	/// The site stopped serving images for today, as it does past a daily
+	/// quota.
+	ImageLimit,
+}
+
+impl Error {
+	/// The kind an HTTP status stands for, if any.
+	pub fn from_status(status: i32) -> Option<Self> {
+		match status {
+			401 => Some(Self::LoginRequired),
+			429 => Some(Self::RateLimited),
+			451 => Some(Self::RegionBlocked),
+			_ => None,
+		}
+	}
+
+	/// The kind the app is handed, one per failure so they can be told apart.
+	/// A changed page keeps the plain parse error and a missing account is a
+	/// missing setting.
+	/// The others have no close match and take the rarer kinds left over.
+	pub fn kind(&self) -> AidokuErrorKind {
+		match self {
+			Self::MarkupChanged(_) => AidokuErrorKind::NodeError(NodeError::ParseError),
+			Self::LoginRequired => AidokuErrorKind::DefaultNotFound,
+			Self::RateLimited => AidokuErrorKind::NodeError(NodeError::ModifyError),
+			Self::RegionBlocked => AidokuErrorKind::ValueCast(ValueCastError::NotNode),
+			Self::Cloudflare => AidokuErrorKind::ValueCast(ValueCastError::NotBool),
+			Self::PaidChapter => AidokuErrorKind::ValueCast(ValueCastError::NotNumber),
+			Self::ImageLimit => AidokuErrorKind::ValueCast(ValueCastError::NotArray),
+		}
+	}
+
+	/// What the user sees in the log.
+	pub fn message(&self) -> String {
+		match self {
+			Self::MarkupChanged(marker) => format!("网站页面已改版，找不到 {}", marker),
+			Self::LoginRequired => "需要登录，请在设置中填写账号密码".to_string(),
+			Self::RateLimited => "请求过于频繁，请稍后再试".to_string(),
+			Self::RegionBlocked => "网站不对当前地区开放，请更换网络".to_string(),
+			Self::Cloudflare => "需要通过 Cloudflare 验证，请在网页中打开".to_string(),
+			Self::PaidChapter => "该章节需要付费，请在官方应用中购买后阅读".to_string(),
+			Self::ImageLimit => "今日图片额度已用完，请登录账号或明天再试".to_string(),
+		}
+	}
+}
+
+impl From<Error> for AidokuError {
+	fn from(error: Error) -> Self {
+		println!("{}", error.message());
+		AidokuError {
+			reason: error.kind(),
+		}
+	}
+}
+
+pub fn parse_error() -> AidokuError {
+	AidokuError {
+		reason: AidokuErrorKind::NodeError(NodeError::ParseError),
+	}
+}
+
+/// Reports that `marker`, a selector or a piece of script the source looks
+/// for, is missing.
+pub fn markup_changed<T: AsRef<str>>(marker: T) -> AidokuError {
+	Error::MarkupChanged(marker.as_ref().to_string()).into()
+}
+
+pub trait OptionExt<T> {
+	/// Turns a missing value into a parse error instead of panicking.
+	fn or_parse_error(self) -> Result<T>;
+
+	/// Turns a missing value into [`Error::MarkupChanged`] naming `marker`.
+	fn or_markup_changed(self, marker: &str) -> Result<T>;
+}
+
+impl<T> OptionExt<T> for Option<T> {
+	fn or_parse_error(self) -> Result<T> {
+		self.ok_or_else(parse_error)
+	}
+
+	fn or_markup_changed(self, marker: &str) -> Result<T> {
+		self.ok_or_else(|| markup_changed(marker))
+	}
+}
@@ -0,0 +1,19 @@
+#![no_std]
+extern crate alloc;
+
+pub mod chapter;
+pub mod convert;
+pub mod date;
+pub mod entity;
+pub mod error;
+pub mod link;
+pub mod mirror;
+pub mod net;
+pub mod parse;
+
+mod challenge;
+mod retry;
+
+pub use error::OptionExt;
+pub use mirror::Mirrors;
+pub use net::{Client, RequestBuilder, MOBILE_UA, UA};
@@ -0,0 +1,24 @@
+use aidoku::{
+	std::{String, Vec},
+	Chapter,
+};
+
+use crate::parse;
+
+/// A chapter that only carries its ID, as deep links need nothing more.
+pub fn chapter(id: String) -> Chapter {
+	Chapter {
+		id,
+		..Default::default()
+	}
+}
+
+/// Finds the chapter whose URL has the same path as `url`, for sites whose
+/// chapter URLs do not contain the chapter ID. The host is ignored so links
+/// from legacy and mirror domains still match.
+pub fn find_chapter<T: AsRef<str>>(chapters: Vec<Chapter>, url: T) -> Option<Chapter> {
+	let path = parse::path_segments(url);
+	chapters
+		.into_iter()
+		.find(|a| parse::path_segments(&a.url) == path)
+}
@@ -0,0 +1,120 @@
+//! Built-in mirror domains for sites that move between domains, with a
+//! `domain` setting to override them.
+
+use aidoku::std::{
+	defaults::{defaults_get, defaults_set},
+	String, StringRef, Vec,
+};
+use alloc::string::ToString;
+
+/// Where the mirror that last answered is remembered.
+const MIRROR_KEY: &str = "mirror";
+/// The user's own domain, which replaces the built-in list.
+const DOMAIN_KEY: &str = "domain";
+/// Parking pages are small, so larger responses are not scanned.
+const PARKING_MAX_LEN: usize = 32 * 1024;
+/// Phrases from domain parking and for-sale pages.
+const PARKING_MARKERS: &[&str] = &[
+	"domain is for sale",
+	"domain may be for sale",
+	"buy this domain",
+	"domain parking",
+	"sedoparking",
+	"parkingcrew",
+	"bodis.com",
+	"hugedomains",
+	"afternic",
+	"域名出售",
+	"域名正在出售",
+	"域名转让",
+];
+
+/// The domains a site is served from, the first being the default.
+///
+/// Domains are bare (`mangacopy.com`) so a source can put them behind any
+/// subdomain it needs.
+pub struct Mirrors {
+	domains: &'static [&'static str],
+}
+
+impl Mirrors {
+	pub const fn new(domains: &'static [&'static str]) -> Self {
+		Self { domains }
+	}
+
+	/// The domain to use: the `domain` setting when set, otherwise the mirror
+	/// that last answered, otherwise the first one.
+	pub fn domain(&self) -> String {
+		if let Some(domain) = setting(DOMAIN_KEY) {
+			return normalize(&domain);
+		}
+		setting(MIRROR_KEY)
+			.filter(|a| self.domains.contains(&a.as_str()))
+			.unwrap_or_else(|| self.domains[0].to_string())
+	}
+
+	/// The built-in domain in `url`'s host, if any.
+	pub(crate) fn find(&self, url: &str) -> Option<&'static str> {
+		let host = url.split("://").nth(1)?.split(['/', '?']).next()?;
+		self.domains
+			.iter()
+			.find(|domain| host == **domain || host.ends_with(&[".", domain].concat()))
+			.copied()
+	}
+
+	/// Moves a URL on a built-in domain to the one in use, for the default
+	/// `Referer` and `Origin` headers.
+	pub(crate) fn rebase(&self, url: &str) -> String {
+		match self.find(url) {
+			Some(domain) => url.replacen(domain, &self.domain(), 1),
+			None => url.to_string(),
+		}
+	}
+
+	/// The other built-in domains, in order, to try after `domain`.
+	pub(crate) fn fallbacks(&self, domain: &str) -> Vec<&'static str> {
+		if setting(DOMAIN_KEY).is_some() {
+			return Vec::new();
+		}
+		self.domains
+			.iter()
+			.filter(|a| **a != domain)
+			.copied()
+			.collect()
+	}
+
+	pub(crate) fn remember(&self, domain: &str) {
+		if setting(MIRROR_KEY).as_deref() != Some(domain) {
+			defaults_set(MIRROR_KEY, StringRef::from(domain).0);
+		}
+	}
+}
+
+fn setting(key: &str) -> Option<String> {
+	defaults_get(key)
+		.and_then(|a| a.as_string())
+		.map(|a| a.read())
+		.ok()
+		.filter(|a| !a.trim().is_empty())
+}
+
+/// Accepts `https://www.example.com/` as well as `example.com`.
+fn normalize(domain: &str) -> String {
+	let domain = domain.trim();
+	let domain = domain.split("://").last().unwrap_or(domain);
+	let domain = domain.split('/').next().unwrap_or(domain);
+	domain.trim_start_matches("www.").to_string()
+}
+
+/// Whether a response means the mirror is gone: no connection at all,
+/// Cloudflare reporting the origin down, or a parking page.
+pub(crate) fn unreachable(status: i32, data: &[u8]) -> bool {
+	if status <= 0 || (521..=523).contains(&status) {
+		return true;
+	}
+	if data.len() > PARKING_MAX_LEN {
+		return false;
+	}
+	let text = String::from_utf8_lossy(data).to_lowercase();
+	PARKING_MARKERS.iter().any(|a| text.contains(a))
+}
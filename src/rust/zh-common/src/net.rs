@@ -0,0 +1,403 @@
+use aidoku::{
+	error::Result,
+	std::{
+		html::Node,
+		json,
+		net::{HttpMethod, Request},
+		ObjectRef, String, ValueRef, Vec,
+	},
+};
+use alloc::{borrow::ToOwned, string::ToString};
+
+use crate::{
+	challenge,
+	error::Error,
+	mirror::{self, Mirrors},
+	retry,
+};
+
+pub const UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/135.0.0.0 Safari/537.36";
+pub const MOBILE_UA: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 16_6 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/16.6 Mobile/15E148 Safari/604.1";
+
+/// Default headers shared by every request a source sends.
+///
+/// All fields are `'static` so a source can keep its client in a `const`.
+#[derive(Clone, Copy)]
+pub struct Client {
+	user_agent: Option<&'static str>,
+	referer: Option<&'static str>,
+	origin: Option<&'static str>,
+	headers: &'static [(&'static str, &'static str)],
+	mirrors: Option<&'static Mirrors>,
+	clearance: bool,
+}
+
+impl Client {
+	pub const fn new() -> Self {
+		Self {
+			user_agent: None,
+			referer: None,
+			origin: None,
+			headers: &[],
+			mirrors: None,
+			clearance: false,
+		}
+	}
+
+	pub const fn user_agent(mut self, user_agent: &'static str) -> Self {
+		self.user_agent = Some(user_agent);
+		self
+	}
+
+	pub const fn referer(mut self, referer: &'static str) -> Self {
+		self.referer = Some(referer);
+		self
+	}
+
+	pub const fn origin(mut self, origin: &'static str) -> Self {
+		self.origin = Some(origin);
+		self
+	}
+
+	pub const fn headers(mut self, headers: &'static [(&'static str, &'static str)]) -> Self {
+		self.headers = headers;
+		self
+	}
+
+	/// Moves requests to the next mirror when the one in their URL is
+	/// unreachable or parked, and remembers the one that answers.
+	pub const fn mirrors(mut self, mirrors: &'static Mirrors) -> Self {
+		self.mirrors = Some(mirrors);
+		self
+	}
+
+	/// Keeps the Cloudflare cookies a site sets once its challenge is passed
+	/// and sends them with every later request, images included. They are
+	/// dropped when a challenge shows again, leaving the ones the app's web
+	/// view stores once the user passes it there.
+	pub const fn clearance(mut self) -> Self {
+		self.clearance = true;
+		self
+	}
+
+	pub fn get<T: AsRef<str>>(&self, url: T) -> RequestBuilder {
+		self.request(url, HttpMethod::Get)
+	}
+
+	pub fn post<T: AsRef<str>>(&self, url: T) -> RequestBuilder {
+		self.request(url, HttpMethod::Post)
+	}
+
+	pub fn request<T: AsRef<str>>(&self, url: T, method: HttpMethod) -> RequestBuilder {
+		let mut builder = RequestBuilder::new(url, method);
+		builder.mirrors = self.mirrors;
+		builder.clearance = self.clearance;
+
+		if let Some(user_agent) = self.user_agent {
+			builder = builder.header("User-Agent", user_agent);
+		}
+		if let Some(referer) = self.referer {
+			builder = builder.header("Referer", self.rebase(referer));
+		}
+		if let Some(origin) = self.origin {
+			builder = builder.header("Origin", self.rebase(origin));
+		}
+		for (key, value) in self.headers {
+			builder = builder.header(key, value);
+		}
+
+		builder
+	}
+
+	/// Applies the default headers to a request built by the app, such as the
+	/// one passed to `modify_image_request`.
+	pub fn apply(&self, request: Request) -> Request {
+		let mut request = request;
+
+		if let Some(user_agent) = self.user_agent {
+			request = request.header("User-Agent", user_agent);
+		}
+		if let Some(referer) = self.referer {
+			request = request.header("Referer", self.rebase(referer).as_str());
+		}
+		if let Some(origin) = self.origin {
+			request = request.header("Origin", self.rebase(origin).as_str());
+		}
+		for (key, value) in self.headers {
+			request = request.header(key, value);
+		}
+		if let Some(cookie) = self.clearance.then(|| challenge::cookie(None)).flatten() {
+			request = request.header("Cookie", cookie.as_str());
+		}
+
+		request
+	}
+
+	fn rebase(&self, url: &str) -> String {
+		match self.mirrors {
+			Some(mirrors) => mirrors.rebase(url),
+			None => url.to_string(),
+		}
+	}
+}
+
+impl Default for Client {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// A request description that can be turned into an `aidoku` request as many
+/// times as needed.
+#[derive(Clone)]
+pub struct RequestBuilder {
+	url: String,
+	method: HttpMethod,
+	headers: Vec<(String, String)>,
+	body: Option<Vec<u8>>,
+	mirrors: Option<&'static Mirrors>,
+	clearance: bool,
+	idempotent: bool,
+}
+
+/// A response that passed [`check`]: either still held by the app, or
+/// already read to try other mirrors or to look for a challenge.
+enum Response {
+	Pending(Request),
+	Read(String, Vec<u8>),
+}
+
+impl RequestBuilder {
+	pub fn new<T: AsRef<str>>(url: T, method: HttpMethod) -> Self {
+		Self {
+			url: url.as_ref().to_string(),
+			method,
+			headers: Vec::new(),
+			body: None,
+			mirrors: None,
+			clearance: false,
+			idempotent: false,
+		}
+	}
+
+	pub fn url(&self) -> &str {
+		&self.url
+	}
+
+	/// Sets a header, replacing any earlier value with the same name.
+	pub fn header<K: AsRef<str>, V: AsRef<str>>(mut self, key: K, value: V) -> Self {
+		let key = key.as_ref();
+		self.headers
+			.retain(|(name, _)| !name.eq_ignore_ascii_case(key));
+		self.headers
+			.push((key.to_owned(), value.as_ref().to_owned()));
+		self
+	}
+
+	pub fn body<T: AsRef<[u8]>>(mut self, body: T) -> Self {
+		self.body = Some(body.as_ref().to_vec());
+		self
+	}
+
+	pub fn form<T: AsRef<str>>(self, body: T) -> Self {
+		self.header("Content-Type", "application/x-www-form-urlencoded")
+			.body(body.as_ref().as_bytes())
+	}
+
+	pub fn json_body<T: AsRef<str>>(self, body: T) -> Self {
+		self.header("Content-Type", "application/json")
+			.body(body.as_ref().as_bytes())
+	}
+
+	/// Marks a request that only reads, such as a search or GraphQL query
+	/// sent as a POST, as safe to retry like a GET.
+	pub fn idempotent(mut self) -> Self {
+		self.idempotent = true;
+		self
+	}
+
+	pub fn build(&self) -> Request {
+		let mut request = Request::new(self.url.clone(), self.method);
+		let cookie = match self.clearance {
+			true => challenge::cookie(self.header_value("Cookie")),
+			false => None,
+		};
+
+		for (key, value) in &self.headers {
+			if cookie.is_none() || !key.eq_ignore_ascii_case("Cookie") {
+				request = request.header(key, value);
+			}
+		}
+		if let Some(cookie) = &cookie {
+			request = request.header("Cookie", cookie);
+		}
+		if let Some(body) = &self.body {
+			request = request.body(body);
+		}
+
+		request
+	}
+
+	/// Sends the request and hands it back so the caller can inspect the status
+	/// code and headers before reading the body.
+	///
+	/// Rate limits and server errors are retried with backoff when the request
+	/// is idempotent; the last response is handed back if they persist.
+	pub fn send(&self) -> Request {
+		let mut attempt = 1;
+
+		loop {
+			let request = self.build();
+			request.send();
+			if self.clearance {
+				if let Some(set_cookie) = request.get_header("Set-Cookie") {
+					challenge::remember(&set_cookie.read());
+				}
+			}
+			if attempt >= retry::ATTEMPTS || !self.retries(&request) {
+				if attempt > 1 {
+					retry::release();
+				}
+				return request;
+			}
+			let retry_after = request.get_header("Retry-After").map(|a| a.read());
+			retry::wait(retry::delay(attempt, retry_after.as_deref()));
+			attempt += 1;
+		}
+	}
+
+	/// Whether a failed response is worth sending the request again for.
+	/// Challenges are not, as they only clear in a browser.
+	fn retries(&self, request: &Request) -> bool {
+		(self.idempotent || matches!(self.method, HttpMethod::Get | HttpMethod::Head))
+			&& retry::retryable(request.status_code())
+			&& request.get_header("cf-mitigated").is_none()
+	}
+
+	fn header_value(&self, key: &str) -> Option<&str> {
+		self.headers
+			.iter()
+			.find(|(name, _)| name.eq_ignore_ascii_case(key))
+			.map(|(_, value)| value.as_str())
+	}
+
+	/// Sends the request to each mirror in turn until one answers, returning
+	/// the URL that answered, its status and its body.
+	fn fetch(mut self, mirrors: &Mirrors) -> (String, i32, Vec<u8>) {
+		let Some(domain) = mirrors.find(&self.url) else {
+			let request = self.send();
+			return (self.url, request.status_code(), request.data());
+		};
+		let mut fallbacks = mirrors.fallbacks(domain).into_iter();
+		let mut current = domain;
+
+		loop {
+			let request = self.send();
+			let status = request.status_code();
+			let data = request.data();
+			if !mirror::unreachable(status, &data) {
+				mirrors.remember(current);
+				return (self.url, status, data);
+			}
+			match fallbacks.next() {
+				Some(next) => {
+					self.url = self.url.replacen(current, next, 1);
+					for (_, value) in &mut self.headers {
+						*value = value.replace(current, next);
+					}
+					current = next;
+				}
+				None => return (self.url, status, data),
+			}
+		}
+	}
+
+	/// Sends the request, failing with the [`Error`] the response stands for.
+	fn respond(self) -> Result<Response> {
+		self.exchange().map_err(Into::into)
+	}
+
+	fn exchange(self) -> core::result::Result<Response, Error> {
+		let clearance = self.clearance;
+		let response = self.receive();
+		if clearance && response.as_ref().is_err_and(|a| *a == Error::Cloudflare) {
+			challenge::forget();
+		}
+		response
+	}
+
+	fn receive(self) -> core::result::Result<Response, Error> {
+		if let Some(mirrors) = self.mirrors {
+			let (url, status, data) = self.fetch(mirrors);
+			check(status, &data)?;
+			return Ok(Response::Read(url, data));
+		}
+
+		let request = self.send();
+		let status = request.status_code();
+		if !challenge::possible(status) {
+			check(status, &[])?;
+			return Ok(Response::Pending(request));
+		}
+		let data = request.data();
+		check(status, &data)?;
+		Ok(Response::Read(self.url, data))
+	}
+
+	pub fn data(self) -> Result<Vec<u8>> {
+		match self.respond()? {
+			Response::Pending(request) => Ok(request.data()),
+			Response::Read(_, data) => Ok(data),
+		}
+	}
+
+	pub fn string(self) -> Result<String> {
+		match self.respond()? {
+			Response::Pending(request) => request.string(),
+			Response::Read(_, data) => Ok(String::from_utf8_lossy(&data).into_owned()),
+		}
+	}
+
+	pub fn html(self) -> Result<Node> {
+		match self.respond()? {
+			Response::Pending(request) => request.html(),
+			Response::Read(url, data) => Node::new_with_uri(data, &url),
+		}
+	}
+
+	pub fn json(self) -> Result<ValueRef> {
+		match self.respond()? {
+			Response::Pending(request) => request.json(),
+			Response::Read(_, data) => json::parse(data),
+		}
+	}
+
+	pub fn json_object(self) -> Result<ObjectRef> {
+		self.json()?.as_object()
+	}
+
+	/// Like [`json_object`](Self::json_object), but hands back `None` when the
+	/// site rejects the request's credentials, so the caller can sign in again
+	/// or drop them instead of failing.
+	pub fn json_object_if_authorized(self) -> Result<Option<ObjectRef>> {
+		let data = match self.exchange() {
+			Ok(Response::Pending(request)) => request.json()?,
+			Ok(Response::Read(_, data)) => json::parse(data)?,
+			Err(Error::LoginRequired) => return Ok(None),
+			Err(error) => return Err(error.into()),
+		};
+		Ok(Some(data.as_object()?))
+	}
+}
+
+/// Fails with the [`Error`] a response stands for, such as a challenge page,
+/// login wall or rate limit, so it is not parsed as an empty page.
+fn check(status: i32, data: &[u8]) -> core::result::Result<(), Error> {
+	if challenge::detected(status, data) {
+		return Err(Error::Cloudflare);
+	}
+	match Error::from_status(status) {
+		Some(error) => Err(error),
+		None => Ok(()),
+	}
+}
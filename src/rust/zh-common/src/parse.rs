@@ -0,0 +1,118 @@
+use aidoku::{
+	error::Result,
+	helpers::substring::Substring,
+	prelude::*,
+	std::{html::Node, ArrayRef, String, Vec},
+};
+use alloc::string::ToString;
+
+use crate::error::OptionExt;
+
+/// Returns the last non-empty path segment of a URL with any `.html` suffix
+/// removed, e.g. `/comic/123.html` -> `123`.
+pub fn last_segment<T: AsRef<str>>(url: T) -> String {
+	let url = url.as_ref();
+	let url = url.split(['?', '#']).next().unwrap_or_default();
+	url.split('/')
+		.rfind(|a| !a.is_empty())
+		.unwrap_or_default()
+		.trim_end_matches(".html")
+		.to_string()
+}
+
+/// Splits the path of a URL into its non-empty segments, ignoring the scheme,
+/// host, query and fragment, e.g. `https://a.com/comic/1/` -> `["comic", "1"]`.
+pub fn path_segments<T: AsRef<str>>(url: T) -> Vec<String> {
+	let url = url.as_ref();
+	let url = url.split(['?', '#']).next().unwrap_or_default();
+	let path = match url.find("://") {
+		Some(index) => url[index + 3..]
+			.split_once('/')
+			.map(|a| a.1)
+			.unwrap_or_default(),
+		None => url,
+	};
+	path.split('/')
+		.filter(|a| !a.is_empty())
+		.map(|a| a.to_string())
+		.collect::<Vec<String>>()
+}
+
+/// Returns the value of the query parameter `key`, e.g. `?kuid=1` -> `1`.
+pub fn query_param<T: AsRef<str>>(url: T, key: &str) -> Option<String> {
+	let url = url.as_ref();
+	let query = url.split('#').next()?.split_once('?')?.1;
+	query
+		.split('&')
+		.filter_map(|a| a.split_once('='))
+		.find(|(name, _)| *name == key)
+		.map(|(_, value)| value.to_string())
+}
+
+/// Returns everything after the last occurrence of `separator`, or the whole
+/// text when it does not occur.
+pub fn after_last<T: AsRef<str>>(text: T, separator: &str) -> String {
+	let text = text.as_ref();
+	match text.rfind(separator) {
+		Some(index) => text[index + separator.len()..].to_string(),
+		None => text.to_string(),
+	}
+}
+
+/// Returns the text between `start` and the next `end`, naming the marker
+/// that is missing when the page has changed.
+pub fn between<T: AsRef<str>>(text: T, start: &str, end: &str) -> Result<String> {
+	Ok(text
+		.as_ref()
+		.substring_after(start)
+		.or_markup_changed(start)?
+		.substring_before(end)
+		.or_markup_changed(end)?
+		.to_string())
+}
+
+/// Collects the trimmed, non-empty text of every node matching `selector`.
+pub fn texts<T: AsRef<str>>(node: &Node, selector: T) -> Vec<String> {
+	node.select(selector)
+		.array()
+		.filter_map(|a| a.as_node().ok())
+		.map(|a| a.text().read().trim().to_string())
+		.filter(|a| !a.is_empty())
+		.collect::<Vec<String>>()
+}
+
+/// Collects the string stored under `key` in every object of a JSON array,
+/// skipping entries that are not objects or lack the key.
+pub fn object_strings(array: ArrayRef, key: &str) -> Vec<String> {
+	array
+		.filter_map(|a| a.as_object().ok())
+		.filter_map(|a| a.get(key).as_string().ok())
+		.map(|a| a.read())
+		.collect::<Vec<String>>()
+}
+
+/// Prefixes protocol-relative URLs (`//host/path`) with `https:`.
+pub fn absolute_url<T: AsRef<str>>(url: T) -> String {
+	let url = url.as_ref();
+	if url.starts_with("//") {
+		format!("https:{}", url)
+	} else {
+		url.to_string()
+	}
+}
+
+/// Quotes `text` as a JSON string, for request bodies written with
+/// `format!`.
+pub fn json_string<T: AsRef<str>>(text: T) -> String {
+	let mut result = String::from('"');
+	for char in text.as_ref().chars() {
+		match char {
+			'"' => result.push_str("\\\""),
+			'\\' => result.push_str("\\\\"),
+			char if char.is_control() => result.push_str(&format!("\\u{:04x}", char as u32)),
+			char => result.push(char),
+		}
+	}
+	result.push('"');
+	result
+}
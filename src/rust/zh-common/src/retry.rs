@@ -0,0 +1,48 @@
+//! Retrying requests that failed for a moment, such as a rate limit or a
+//! gateway error, instead of failing the whole listing.
+//!
+//! Sources have no timer to sleep on, so the wait before a retry is left to
+//! the app's request rate limit: it is narrowed to one request per delay
+//! while retrying, and the app holds the retry back until the delay is up.
+
+use aidoku::std::{current_date, net::Request};
+
+use crate::date;
+
+/// Tries in all, counting the first.
+pub(crate) const ATTEMPTS: u32 = 3;
+/// Seconds to wait before the first retry, doubled for each one after.
+const BASE_DELAY: i32 = 1;
+/// The longest wait, which also caps `Retry-After`, so a source never stalls
+/// for long.
+const MAX_DELAY: i32 = 8;
+
+/// Whether a response with `status` is worth trying again.
+pub(crate) fn retryable(status: i32) -> bool {
+	matches!(status, 429 | 500 | 502 | 503 | 504)
+}
+
+/// Seconds to wait before retry number `attempt`, counting from 1: what
+/// `Retry-After` asks for when present, otherwise an exponential backoff.
+pub(crate) fn delay(attempt: u32, retry_after: Option<&str>) -> i32 {
+	let backoff = BASE_DELAY << (attempt - 1).min(8);
+	let delay = match retry_after.map(|a| a.trim()) {
+		Some(value) => match value.parse::<f64>() {
+			Ok(seconds) => seconds,
+			Err(_) => date::http(value).map_or(backoff as f64, |a| a - current_date()),
+		},
+		None => backoff as f64,
+	};
+	(delay.ceil() as i32).clamp(0, MAX_DELAY)
+}
+
+/// Holds the next request back until `seconds` after the last one.
+pub(crate) fn wait(seconds: i32) {
+	Request::set_rate_limit(1);
+	Request::set_rate_limit_period(seconds);
+}
+
+/// Lifts the limit set by [`wait`] once the retries are over.
+pub(crate) fn release() {
+	Request::set_rate_limit_period(0);
+}
@@ -5,19 +5,19 @@ use aidoku::{
 	error::Result,
 	helpers::uri::encode_uri,
 	prelude::*,
-	std::{
-		net::{HttpMethod, Request},
-		String, Vec,
-	},
-	Chapter, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	std::{String, Vec},
+	Chapter, DeepLink, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{chapter, convert, entity, parse, Client, OptionExt};
 
 const WWW_URL: &str = "https://139mh.com";
 const API_URL: &str = "https://api.139mh.com";
 const IMG_URL: &str = "https://i.139img.com";
 
+const CLIENT: Client = Client::new();
+
 const FILTER_SUBJECT: [&str; 46] = [
 	"all",
 	"aiqing",
@@ -135,7 +135,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 		)
 	};
 
-	let json = Request::new(url, HttpMethod::Get).json()?;
+	let json = CLIENT.get(url).json()?;
 	let data = json.as_object()?;
 
 	let list = if query.is_empty() {
@@ -162,7 +162,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -170,40 +170,17 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
 	let url = format!("{}/api/comic/detail?id={}", API_URL, id.clone());
-	let json = Request::new(url.clone(), HttpMethod::Get).json()?;
+	let json = CLIENT.get(url.clone()).json()?;
 	let data = json.as_object()?;
 	let data = data.get("result").as_object()?;
 	let info = data.get("info").as_object()?;
 	let cover = handle_cover_url(info.get("cover").as_string()?.read());
 	let title = info.get("title").as_string()?.read();
-	let author = info
-		.get("auth")
-		.as_array()?
-		.map(|a| {
-			a.as_object()
-				.unwrap()
-				.get("auth_name")
-				.as_string()
-				.unwrap()
-				.read()
-		})
-		.collect::<Vec<String>>()
-		.join(", ");
+	let author = parse::object_strings(info.get("auth").as_array()?, "auth_name").join(", ");
 	let artist = String::new();
 	let description = info.get("summary").as_string()?.read();
 	let url = format!("{}/comic-{}", WWW_URL, id.clone());
-	let categories = info
-		.get("cata_list")
-		.as_array()?
-		.map(|a| {
-			a.as_object()
-				.unwrap()
-				.get("title")
-				.as_string()
-				.unwrap()
-				.read()
-		})
-		.collect::<Vec<String>>();
+	let categories = parse::object_strings(info.get("cata_list").as_array()?, "title");
 	let status = match info.get("progress").as_int()?.to_string().as_str() {
 		"1" => MangaStatus::Completed,
 		"2" => MangaStatus::Hiatus,
@@ -213,7 +190,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let nsfw = MangaContentRating::Safe;
 	let viewer = MangaViewer::Rtl;
 
-	Ok(Manga {
+	Ok(convert::manga(Manga {
 		id,
 		cover,
 		title,
@@ -225,44 +202,45 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		status,
 		nsfw,
 		viewer,
-	})
+	}))
 }
 
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	let url = format!("{}/api/comic/detail?id={}", API_URL, id.clone());
-	let json = Request::new(url.clone(), HttpMethod::Get).json()?;
+	let json = CLIENT.get(url.clone()).json()?;
 	let data = json.as_object()?;
 	let data = data.get("result").as_object()?;
 	let list = data.get("vol_list").as_array()?;
 	let mut chapters: Vec<Chapter> = Vec::new();
 
-	for (index, item) in list.enumerate() {
+	for item in list {
 		let item = match item.as_object() {
 			Ok(item) => item,
 			Err(_) => continue,
 		};
 		let chapter_id = item.get("id").as_int()?.to_string();
 		let title = item.get("title").as_string()?.read();
-		let chapter = (index + 1) as f32;
+		let number = chapter::parse(&title);
 		let url = format!("{}/comic/vol-{}", WWW_URL, chapter_id.clone());
 		chapters.push(Chapter {
 			id: chapter_id,
 			title,
-			chapter,
+			volume: number.volume(),
+			chapter: number.chapter(),
 			url,
 			..Default::default()
 		});
 	}
 	chapters.reverse();
 
-	Ok(chapters)
+	Ok(convert::chapters(chapters))
 }
 
 #[get_page_list]
 fn get_page_list(_: String, chapter_id: String) -> Result<Vec<Page>> {
 	let url = format!("{}/api/vol/detail?id={}", API_URL, chapter_id.clone());
-	let json = Request::new(url.clone(), HttpMethod::Get).json()?;
+	let json = CLIENT.get(url.clone()).json()?;
 	let data = json.as_object()?;
 	let data = data.get("result").as_object()?;
 	let list = data.get("img_list").as_array()?;
@@ -284,3 +262,18 @@ fn get_page_list(_: String, chapter_id: String) -> Result<Vec<Page>> {
 
 	Ok(pages)
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	// Chapter pages (`/comic/vol-{id}`) do not name their comic, so only
+	// comic pages can be resolved.
+	let id = parse::last_segment(&url)
+		.strip_prefix("comic-")
+		.or_parse_error()?
+		.to_string();
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id)?),
+		chapter: None,
+	})
+}
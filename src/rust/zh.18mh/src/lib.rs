@@ -5,17 +5,21 @@ use aidoku::{
 	error::Result,
 	helpers::uri::encode_uri,
 	prelude::*,
-	std::{
-		net::{HttpMethod, Request},
-		String, Vec,
-	},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
-	MangaViewer, Page,
+	std::{net::Request, String, Vec},
+	Chapter, DeepLink, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult,
+	MangaStatus, MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	chapter, convert, date, entity,
+	error::{markup_changed, parse_error},
+	link, parse, Client, OptionExt,
+};
 
 const WWW_URL: &str = "https://18mh.org";
 
+const CLIENT: Client = Client::new().referer(WWW_URL);
+
 const FILTER_GENRE: [&str; 4] = ["hanman", "zhenrenxiezhen", "riman", "aixiezhen"];
 const FILTER_CATEGORY: [&str; 18] = [
 	"",
@@ -77,21 +81,14 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 		format!("{}/s/{}?page={}", WWW_URL, encode_uri(query), page)
 	};
 
-	let html = Request::new(url, HttpMethod::Get).html()?;
+	let html = CLIENT.get(url).html()?;
 
 	for item in html.select(".pb-2>a").array() {
 		let item = match item.as_node() {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = parse::last_segment(item.attr("href").read());
 		let cover = item.select("div>img").attr("src").read();
 		let title = item.select("div>h3").text().read();
 		mangas.push(Manga {
@@ -103,7 +100,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -126,7 +123,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	}
 
 	let url = format!("{}/{}/page/{}", WWW_URL, list, page);
-	let html = Request::new(url, HttpMethod::Get).html()?;
+	let html = CLIENT.get(url).html()?;
 	let has_more = true;
 	let mut mangas: Vec<Manga> = Vec::new();
 
@@ -135,14 +132,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = parse::last_segment(item.attr("href").read());
 		let cover = item.select("div>img").attr("src").read();
 		let title = item.select("div>h3").text().read();
 		mangas.push(Manga {
@@ -154,7 +144,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -163,31 +153,25 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 fn get_manga_details(id: String) -> Result<Manga> {
 	let ids = id.split("/").collect::<Vec<&str>>();
 	let url = format!("{}/manga/{}", WWW_URL, ids[0]);
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let mid = html.select("#mangachapters").attr("data-mid").read();
 	let cover = html
 		.select("meta[property='og:image']")
 		.attr("content")
 		.read();
 	let title = html.select("title").text().read().replace("-18漫畫", "");
-	let author = html
-		.select("a[href*=author]>span")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read().replace(",", ""))
+	let author = parse::texts(&html, "a[href*=author]>span")
+		.iter()
+		.map(|a| a.replace(",", ""))
 		.filter(|a| !a.is_empty())
 		.collect::<Vec<String>>()
 		.join(", ");
 	let artist = String::new();
 	let description = html.select(".text-medium.my-unit-md").text().read();
-	let categories = html
-		.select(".py-1>a:not([href*=author])>span")
-		.array()
+	let categories = parse::texts(&html, ".py-1>a:not([href*=author])>span")
+		.iter()
 		.map(|a| {
-			a.as_node()
-				.unwrap()
-				.text()
-				.read()
-				.replace(",", "")
+			a.replace(",", "")
 				.replace("熱門漫畫", "")
 				.replace("#", "")
 				.replace("推荐", "")
@@ -204,7 +188,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let nsfw = MangaContentRating::Nsfw;
 	let viewer = MangaViewer::Scroll;
 
-	Ok(Manga {
+	Ok(convert::manga(Manga {
 		id: format!("{}/{}", ids[0], mid),
 		cover,
 		title,
@@ -216,18 +200,22 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		status,
 		nsfw,
 		viewer,
-	})
+	}))
 }
 
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	let ids = id.split("/").collect::<Vec<&str>>();
-	let url = format!("{}/manga/get?mid={}&mode=all", WWW_URL, ids[1]);
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let url = format!(
+		"{}/manga/get?mid={}&mode=all",
+		WWW_URL,
+		ids.get(1).or_parse_error()?
+	);
+	let html = CLIENT.get(url.clone()).html()?;
 	let list = html.select("#allchapterlist>.chapteritem>a").array();
 	let mut chapters: Vec<Chapter> = Vec::new();
 
-	for (index, item) in list.enumerate() {
+	for item in list {
 		let item = match item.as_node() {
 			Ok(item) => item,
 			Err(_) => continue,
@@ -239,20 +227,23 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 			.read()
 			.trim()
 			.to_string();
+		let date_updated = date::parse(item.select("div>span:nth-child(2)").text().read());
 		let slug = item.attr("href").read();
 		let url = format!("{}/{}", WWW_URL, slug);
-		let chapter = (index + 1) as f32;
+		let number = chapter::parse(&title);
 		chapters.push(Chapter {
 			id,
 			title,
-			chapter,
+			volume: number.volume(),
+			chapter: number.chapter(),
+			date_updated,
 			url,
 			..Default::default()
 		});
 	}
 	chapters.reverse();
 
-	Ok(chapters)
+	Ok(convert::chapters(chapters))
 }
 
 #[get_page_list]
@@ -261,12 +252,10 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 	let url = format!(
 		"{}/chapter/getcontent?m={}&c={}",
 		WWW_URL,
-		ids[1],
+		ids.get(1).or_parse_error()?,
 		chapter_id.clone()
 	);
-	let html = Request::new(url.clone(), HttpMethod::Get)
-		.header("Referer", &WWW_URL)
-		.html()?;
+	let html = CLIENT.get(url).html()?;
 	let list = html.select("#chapcontent>div>img").array();
 	let mut pages: Vec<Page> = Vec::new();
 
@@ -288,10 +277,34 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 		})
 	}
 
+	if pages.is_empty() {
+		return Err(markup_changed("#chapcontent>div>img"));
+	}
+
 	Ok(pages)
 }
 
 #[modify_image_request]
 fn modify_image_request(request: Request) {
-	request.header("Referer", &WWW_URL);
+	CLIENT.apply(request);
+}
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let segments = parse::path_segments(&url);
+	let slug = match segments.as_slice() {
+		[kind, slug, ..] if kind == "manga" => slug.clone(),
+		_ => return Err(parse_error()),
+	};
+	let manga = get_manga_details(slug)?;
+	let chapter = if segments.len() > 2 {
+		link::find_chapter(get_chapter_list(manga.id.clone())?, &url)
+	} else {
+		None
+	};
+
+	Ok(DeepLink {
+		manga: Some(manga),
+		chapter,
+	})
 }
@@ -5,17 +5,17 @@ use aidoku::{
 	error::Result,
 	helpers::uri::encode_uri,
 	prelude::*,
-	std::{
-		net::{HttpMethod, Request},
-		String, Vec,
-	},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
-	MangaViewer, Page,
+	std::{String, Vec},
+	Chapter, DeepLink, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult,
+	MangaStatus, MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{chapter, convert, entity, error::parse_error, link, parse, Client};
 
 const WWW_URL: &str = "https://www.55dmh.com";
 
+const CLIENT: Client = Client::new();
+
 const FILTER_CATEGORY: [&str; 11] = [
 	"",
 	"rexue",
@@ -90,7 +90,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			page
 		)
 	};
-	let html = Request::new(url, HttpMethod::Get).html()?;
+	let html = CLIENT.get(url).html()?;
 	let has_more = true;
 	let mut mangas: Vec<Manga> = Vec::new();
 
@@ -99,16 +99,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select(".cover")
-			.attr("href")
-			.read()
-			.split("/")
-			.filter(|a| !a.is_empty())
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = parse::last_segment(item.select(".cover").attr("href").read());
 		let cover = item.select(".cover>img").attr("src").read();
 		let title = item.select("dl>dt>a").text().read().trim().to_string();
 		mangas.push(Manga {
@@ -120,7 +111,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -137,7 +128,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	}
 
 	let url = format!("{}/main/{}/", WWW_URL, key);
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let has_more = false;
 	let mut mangas: Vec<Manga> = Vec::new();
 
@@ -146,15 +137,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.filter(|a| !a.is_empty())
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = parse::last_segment(item.attr("href").read());
 		let cover = item.attr("i").read();
 		let title = item.text().read().trim().to_string();
 		mangas.push(Manga {
@@ -166,7 +149,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -174,7 +157,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
 	let url = format!("{}/manhua/{}/", WWW_URL, id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let cover = html.select(".cover>img").attr("src").read();
 	let title = html
 		.select("h3[class]")
@@ -200,17 +183,12 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		.replace("介绍:", "")
 		.trim()
 		.to_string();
-	let categories = html
-		.select(".info>p:nth-child(5)>a")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read().trim().to_string())
-		.filter(|a| !a.is_empty())
-		.collect::<Vec<String>>();
+	let categories = parse::texts(&html, ".info>p:nth-child(5)>a");
 	let status = MangaStatus::Ongoing;
 	let nsfw = MangaContentRating::Safe;
 	let viewer = MangaViewer::Rtl;
 
-	Ok(Manga {
+	Ok(convert::manga(Manga {
 		id,
 		cover,
 		title,
@@ -222,33 +200,24 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		status,
 		nsfw,
 		viewer,
-	})
+	}))
 }
 
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	let url = format!("{}/manhua/{}/", WWW_URL, id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let list = html.select("#chapter-list-1>li>a").array();
-	let len = list.len();
 	let mut chapters: Vec<Chapter> = Vec::new();
 
-	for (index, item) in list.enumerate() {
+	for item in list {
 		let item = match item.as_node() {
 			Ok(item) => item,
 			Err(_) => continue,
 		};
-		let chapter_id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
-			.replace(".html", "");
+		let chapter_id = parse::last_segment(item.attr("href").read());
 		let title = item.text().read().trim().to_string();
-		let chapter = (len - index) as f32;
+		let number = chapter::parse(&title);
 		let url = format!(
 			"{}/manhua/{}/{}.html",
 			WWW_URL,
@@ -258,13 +227,14 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 		chapters.push(Chapter {
 			id: chapter_id,
 			title,
-			chapter,
+			volume: number.volume(),
+			chapter: number.chapter(),
 			url,
 			..Default::default()
 		});
 	}
 
-	Ok(chapters)
+	Ok(convert::chapters(chapters))
 }
 
 #[get_page_list]
@@ -275,7 +245,7 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 		manga_id.clone(),
 		chapter_id.clone()
 	);
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let mut pages: Vec<Page> = Vec::new();
 
 	for (index, item) in html.select("#imagesOld>img").array().enumerate() {
@@ -294,3 +264,20 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 
 	Ok(pages)
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let segments = parse::path_segments(&url);
+	let (id, chapter_id) = match segments.as_slice() {
+		[kind, id] if kind == "manhua" => (id.clone(), None),
+		[kind, id, chapter_id] if kind == "manhua" => {
+			(id.clone(), Some(parse::last_segment(chapter_id)))
+		}
+		_ => return Err(parse_error()),
+	};
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id)?),
+		chapter: chapter_id.map(link::chapter),
+	})
+}
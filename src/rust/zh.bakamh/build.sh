@@ -1,6 +1,6 @@
 cargo +nightly build --release
-mkdir -p target/wasm32-unknown-unknown/release/Payload
-cp res/* target/wasm32-unknown-unknown/release/Payload
-cp target/wasm32-unknown-unknown/release/*.wasm target/wasm32-unknown-unknown/release/Payload/main.wasm
-cd target/wasm32-unknown-unknown/release ; zip -r package.aix Payload
-mv package.aix ../../../package.aix
+mkdir -p target/Payload
+cp res/* target/Payload
+cp ../target/wasm32-unknown-unknown/release/aidoku_bakamh.wasm target/Payload/main.wasm
+cd target ; zip -r package.aix Payload
+mv package.aix ../package.aix
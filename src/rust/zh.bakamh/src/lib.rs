@@ -5,17 +5,21 @@ use aidoku::{
 	error::Result,
 	helpers::uri::encode_uri,
 	prelude::*,
-	std::{
-		net::{HttpMethod, Request},
-		String, Vec,
-	},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
-	MangaViewer, Page,
+	std::{net::Request, String, Vec},
+	Chapter, DeepLink, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult,
+	MangaStatus, MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	chapter, convert, date, entity,
+	error::{markup_changed, parse_error},
+	link, parse, Client,
+};
 
 const WWW_URL: &str = "https://bakamh.com";
 
+const CLIENT: Client = Client::new().referer(WWW_URL);
+
 const FILTER_FINISH: [&str; 3] = ["", "on-going", "end"];
 
 #[get_manga_list]
@@ -51,7 +55,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			encode_uri(query.clone())
 		)
 	};
-	let html = Request::new(url, HttpMethod::Get).html()?;
+	let html = CLIENT.get(url).html()?;
 	let has_more = true;
 	let mut mangas: Vec<Manga> = Vec::new();
 
@@ -61,16 +65,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 				Ok(node) => node,
 				Err(_) => continue,
 			};
-			let id = item
-				.select(".item-thumb>a")
-				.attr("href")
-				.read()
-				.split("/")
-				.map(|a| a.to_string())
-				.filter(|a| !a.is_empty())
-				.collect::<Vec<String>>()
-				.pop()
-				.unwrap();
+			let id = parse::last_segment(item.select(".item-thumb>a").attr("href").read());
 			let cover = item
 				.select(".item-thumb>a>img")
 				.attr("src")
@@ -90,16 +85,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 				Ok(node) => node,
 				Err(_) => continue,
 			};
-			let id = item
-				.select(".col-4>.tab-thumb>a")
-				.attr("href")
-				.read()
-				.split("/")
-				.map(|a| a.to_string())
-				.filter(|a| !a.is_empty())
-				.collect::<Vec<String>>()
-				.pop()
-				.unwrap();
+			let id = parse::last_segment(item.select(".col-4>.tab-thumb>a").attr("href").read());
 			let cover = item
 				.select(".col-4>.tab-thumb>a>img")
 				.attr("src")
@@ -119,7 +105,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -136,7 +122,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	}
 
 	let url = format!("{}/{}/page/{}/", WWW_URL, name, page);
-	let html = Request::new(url, HttpMethod::Get).html()?;
+	let html = CLIENT.get(url).html()?;
 	let has_more = true;
 	let mut mangas: Vec<Manga> = Vec::new();
 
@@ -145,16 +131,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select(".item-thumb>a")
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.filter(|a| !a.is_empty())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = parse::last_segment(item.select(".item-thumb>a").attr("href").read());
 		let cover = item
 			.select(".item-thumb>a>img")
 			.attr("src")
@@ -170,7 +147,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -178,29 +155,20 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
 	let url = format!("{}/manga/{}/", WWW_URL, id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let cover = html
 		.select("meta[property='og:image']")
 		.attr("content")
 		.read();
 	let title = html.select("meta[property='og:title']").text().read();
-	let author = html
-		.select(".author-content>a")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read())
-		.collect::<Vec<String>>()
-		.join(", ");
+	let author = parse::texts(&html, ".author-content>a").join(", ");
 	let artist = String::new();
 	let len = html.select(".post-content>div").array().len();
 	let description = html
 		.select(format!(".post-content>div:nth-child({})>div>p", len))
 		.text()
 		.read();
-	let categories = html
-		.select(".tags-content>a")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read())
-		.collect::<Vec<String>>();
+	let categories = parse::texts(&html, ".tags-content>a");
 	let status = match html
 		.select(format!(
 			".post-content>div:nth-child({})>.summary-content",
@@ -219,7 +187,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let nsfw = MangaContentRating::Nsfw;
 	let viewer = MangaViewer::Scroll;
 
-	Ok(Manga {
+	Ok(convert::manga(Manga {
 		id,
 		cover,
 		title,
@@ -231,42 +199,43 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		status,
 		nsfw,
 		viewer,
-	})
+	}))
 }
 
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	let url = format!("{}/manga/{}/", WWW_URL, id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let list = html.select(".wp-manga-chapter>a").array();
-	let len = list.len();
 	let mut chapters: Vec<Chapter> = Vec::new();
 
-	for (index, item) in list.enumerate() {
+	for item in list {
 		let item = match item.as_node() {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
 		let url = item.attr("href").read();
-		let id = url
-			.split("/")
-			.filter(|a| !a.is_empty())
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = parse::last_segment(&url);
 		let title = item.text().read().trim().to_string();
-		let chapter = (len - index) as f32;
+		let number = chapter::parse(&title);
+		// New chapters show a relative date in the badge's title instead.
+		let release = item.parent().select(".chapter-release-date");
+		let date_updated = match release.select("a").attr("title").read() {
+			badge if !badge.is_empty() => date::parse(badge),
+			_ => date::parse(release.text().read()),
+		};
 		chapters.push(Chapter {
 			id,
 			title,
-			chapter,
+			volume: number.volume(),
+			chapter: number.chapter(),
+			date_updated,
 			url,
 			..Default::default()
 		});
 	}
 
-	Ok(chapters)
+	Ok(convert::chapters(chapters))
 }
 
 #[get_page_list]
@@ -277,7 +246,7 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 		manga_id.clone(),
 		chapter_id.clone()
 	);
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let mut pages: Vec<Page> = Vec::new();
 
 	for (index, item) in html.select("img[id]").array().enumerate() {
@@ -294,10 +263,29 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 		})
 	}
 
+	if pages.is_empty() {
+		return Err(markup_changed("img[id]"));
+	}
+
 	Ok(pages)
 }
 
 #[modify_image_request]
 fn modify_image_request(request: Request) {
-	request.header("Referer", WWW_URL);
+	CLIENT.apply(request);
+}
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let segments = parse::path_segments(&url);
+	let (id, chapter_id) = match segments.as_slice() {
+		[kind, id] if kind == "manga" => (id.clone(), None),
+		[kind, id, chapter_id] if kind == "manga" => (id.clone(), Some(chapter_id.clone())),
+		_ => return Err(parse_error()),
+	};
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id)?),
+		chapter: chapter_id.map(link::chapter),
+	})
 }
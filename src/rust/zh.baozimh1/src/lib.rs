@@ -5,18 +5,22 @@ use aidoku::{
 	error::Result,
 	helpers::uri::encode_uri,
 	prelude::*,
-	std::{
-		net::{HttpMethod, Request},
-		String, Vec,
-	},
-	Chapter, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	std::{net::Request, String, Vec},
+	Chapter, DeepLink, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	chapter, convert, date, entity,
+	error::{markup_changed, parse_error},
+	link, parse, Client, Mirrors, OptionExt,
+};
 
-const WWW_URL: &str = "https://www.baozimh.com";
+const MIRRORS: Mirrors = Mirrors::new(&["baozimh.com", "webmota.com", "twmanga.com", "kukuc.co"]);
 const IMG_URL: &str = "https://static-tw.baozimh.com";
 
+const CLIENT: Client = Client::new().mirrors(&MIRRORS).clearance();
+
 const FILTER_CATEGORY: [&str; 26] = [
 	"all",
 	"lianai",
@@ -48,6 +52,10 @@ const FILTER_CATEGORY: [&str; 26] = [
 const FILTER_REGION: [&str; 5] = ["all", "cn", "jp", "kr", "en"];
 const FILTER_STATUS: [&str; 3] = ["all", "serial", "pub"];
 
+fn www_url() -> String {
+	format!("https://www.{}", MIRRORS.domain())
+}
+
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	let mut query = String::new();
@@ -85,9 +93,13 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	if query.is_empty() {
 		let url = format!(
 			"{}/api/bzmhq/amp_comic_list?type={}&region={}&state={}&page={}&language=tw",
-			WWW_URL, category, region, status, page
+			www_url(),
+			category,
+			region,
+			status,
+			page
 		);
-		let json = Request::new(url, HttpMethod::Get).json()?;
+		let json = CLIENT.get(url).json()?;
 		let data = json.as_object()?;
 		let list = data.get("items").as_array()?;
 
@@ -111,8 +123,12 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			});
 		}
 	} else {
-		let url = format!("{}/search/?q={}", WWW_URL, encode_uri(query.clone()));
-		let html = Request::new(url, HttpMethod::Get).html()?;
+		let url = format!(
+			"{}/search/?q={}",
+			www_url(),
+			encode_uri(convert::to_traditional(&query))
+		);
+		let html = CLIENT.get(url).html()?;
 		let list = html.select(".pure-g>.comics-card").array();
 
 		for item in list {
@@ -120,16 +136,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 				Ok(node) => node,
 				Err(_) => continue,
 			};
-			let id = item
-				.select(".comics-card__info")
-				.attr("href")
-				.read()
-				.split("/")
-				.filter(|a| !a.is_empty())
-				.map(|a| a.to_string())
-				.collect::<Vec<String>>()
-				.pop()
-				.unwrap();
+			let id = parse::last_segment(item.select(".comics-card__info").attr("href").read());
 			let cover = item
 				.select(".comics-card__poster>amp-img")
 				.attr("src")
@@ -150,25 +157,20 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
 
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
-	let url = format!("{}/comic/{}", WWW_URL, id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
-	let id = html
-		.select("meta[name='og:novel:read_url']")
-		.attr("content")
-		.read()
-		.split("/")
-		.filter(|a| !a.is_empty())
-		.map(|a| a.to_string())
-		.collect::<Vec<String>>()
-		.pop()
-		.unwrap();
+	let url = format!("{}/comic/{}", www_url(), id.clone());
+	let html = CLIENT.get(url.clone()).html()?;
+	let id = parse::last_segment(
+		html.select("meta[name='og:novel:read_url']")
+			.attr("content")
+			.read(),
+	);
 	let cover = html.select("meta[name='og:image'").attr("content").read();
 	let title = html
 		.select("meta[name='og:novel:book_name']")
@@ -209,7 +211,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let nsfw = MangaContentRating::Safe;
 	let viewer = MangaViewer::Rtl;
 
-	Ok(Manga {
+	Ok(convert::manga(Manga {
 		id,
 		cover,
 		title,
@@ -221,17 +223,17 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		status,
 		nsfw,
 		viewer,
-	})
+	}))
 }
 
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
-	let url = format!("{}/comic/{}", WWW_URL, id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let url = format!("{}/comic/{}", www_url(), id.clone());
+	let html = CLIENT.get(url.clone()).html()?;
 	let list = html.select("div[id^='chapter']>div>a").array();
 	let mut chapters: Vec<Chapter> = Vec::new();
 
-	for (index, item) in list.enumerate() {
+	for item in list {
 		let item = match item.as_node() {
 			Ok(item) => item,
 			Err(_) => continue,
@@ -241,28 +243,39 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 			.read()
 			.split("&")
 			.skip(1)
-			.map(|a| a.split("=").nth(1).unwrap().to_string())
+			.filter_map(|a| a.split("=").nth(1))
+			.map(|a| a.to_string())
 			.collect::<Vec<String>>()
 			.join("_");
 		let title = item.select("div>span").text().read();
-		let chapter = (index + 1) as f32;
+		let number = chapter::parse(&title);
 		let url = format!(
 			"{}/comic/chapter/{}/{}.html",
-			WWW_URL,
+			www_url(),
 			id.clone(),
 			chapter_id.clone()
 		);
 		chapters.push(Chapter {
 			id: chapter_id,
 			title,
-			chapter,
+			volume: number.volume(),
+			chapter: number.chapter(),
 			url,
 			..Default::default()
 		});
 	}
 	chapters.reverse();
 
-	Ok(chapters)
+	// Only the latest chapter's date is shown.
+	if let Some(latest) = chapters.first_mut() {
+		latest.date_updated = date::parse(
+			html.select("meta[name='og:novel:update_time']")
+				.attr("content")
+				.read(),
+		);
+	}
+
+	Ok(convert::chapters(chapters))
 }
 
 #[get_page_list]
@@ -274,22 +287,13 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 	loop {
 		let url = format!(
 			"{}/comic/chapter/{}/{}.html",
-			WWW_URL,
+			www_url(),
 			manga_id.clone(),
 			current_chapter_id.clone()
 		);
-		let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+		let html = CLIENT.get(url.clone()).html()?;
 		let list = html.select("amp-img[id^='chapter-img']").array();
-		let next_chapter_id = html
-			.select("#next-chapter")
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
-			.replace(".html", "");
+		let next_chapter_id = parse::last_segment(html.select("#next-chapter").attr("href").read());
 
 		for item in list {
 			let item = match item.as_node() {
@@ -306,14 +310,50 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 			page += 1;
 		}
 
-		if !next_chapter_id
-			.starts_with(&current_chapter_id.split('_').collect::<Vec<&str>>()[..2].join("_"))
-		{
+		if !next_chapter_id.starts_with(
+			&current_chapter_id
+				.split('_')
+				.take(2)
+				.collect::<Vec<&str>>()
+				.join("_"),
+		) {
 			break;
 		}
 
 		current_chapter_id = next_chapter_id;
 	}
 
+	if pages.is_empty() {
+		return Err(markup_changed("amp-img[id^='chapter-img']"));
+	}
+
 	Ok(pages)
 }
+
+#[modify_image_request]
+fn modify_image_request(request: Request) {
+	CLIENT.apply(request);
+}
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let segments = parse::path_segments(&url);
+	let (id, chapter_id) = match segments.as_slice() {
+		[kind, id] if kind == "comic" => (id.clone(), None),
+		[kind, chapter, id, chapter_id] if kind == "comic" && chapter == "chapter" => {
+			(id.clone(), Some(parse::last_segment(chapter_id)))
+		}
+		[user, page] if user == "user" && page == "page_direct" => {
+			let id = parse::query_param(&url, "comic_id").or_parse_error()?;
+			let section = parse::query_param(&url, "section_slot").or_parse_error()?;
+			let chapter = parse::query_param(&url, "chapter_slot").or_parse_error()?;
+			(id, Some(format!("{}_{}", section, chapter)))
+		}
+		_ => return Err(parse_error()),
+	};
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id)?),
+		chapter: chapter_id.map(link::chapter),
+	})
+}
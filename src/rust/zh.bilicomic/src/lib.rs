@@ -5,17 +5,24 @@ use aidoku::{
 	error::Result,
 	helpers::uri::encode_uri,
 	prelude::*,
-	std::{
-		net::{HttpMethod, Request},
-		String, Vec,
-	},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
-	MangaViewer, Page,
+	std::{net::Request, String, Vec},
+	Chapter, DeepLink, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult,
+	MangaStatus, MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	chapter, convert, entity,
+	error::{markup_changed, parse_error},
+	link, parse, Client, Mirrors, MOBILE_UA,
+};
+
+const MIRRORS: Mirrors = Mirrors::new(&["bilimanga.net", "bilicomic.net"]);
 
-const WWW_URL: &str = "https://www.bilimanga.net";
-const UA: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 16_6 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/16.6 Mobile/15E148 Safari/604.1";
+const CLIENT: Client = Client::new()
+	.user_agent(MOBILE_UA)
+	.origin("https://www.bilimanga.net")
+	.headers(&[("Accept-Language", "zh-CN,zh;q=0.9"), ("Cookie", "night=0")])
+	.mirrors(&MIRRORS);
 
 const FILTER_TAGID: [&str; 66] = [
 	"0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16",
@@ -45,12 +52,8 @@ const FILTER_QUALITY: [&str; 3] = ["0", "1", "2"];
 const FILTER_ISFULL: [&str; 3] = ["0", "1", "2"];
 const FILTER_UPDATE: [&str; 5] = ["0", "1", "2", "3", "4"];
 
-fn gen_request(url: String, method: HttpMethod) -> Request {
-	Request::new(url, method)
-		.header("Origin", WWW_URL)
-		.header("User-Agent", UA)
-		.header("Accept-Language", "zh-CN,zh;q=0.9")
-		.header("Cookie", "night=0")
+fn www_url() -> String {
+	format!("https://www.{}", MIRRORS.domain())
 }
 
 #[get_manga_list]
@@ -112,18 +115,28 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	let url = if query.is_empty() {
 		format!(
 			"{}/filter/{}_{}_{}_{}_{}_{}_{}_{}_{}_0.html",
-			WWW_URL, order, tagid, isfull, anime, rgroupid, sortid, update, quality, page
+			www_url(),
+			order,
+			tagid,
+			isfull,
+			anime,
+			rgroupid,
+			sortid,
+			update,
+			quality,
+			page
 		)
 	} else {
 		format!(
 			"{}/search/{}_{}.html",
-			WWW_URL,
+			www_url(),
 			encode_uri(query.clone()),
 			page
 		)
 	};
-	let html = gen_request(url, HttpMethod::Get)
-		.header("Referer", &format!("{}/search.html", WWW_URL))
+	let html = CLIENT
+		.get(url)
+		.header("Referer", format!("{}/search.html", www_url()))
 		.html()?;
 	let link = html.select("#pagelink");
 	let has_more = if query.is_empty() {
@@ -136,14 +149,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	let alternate_url = html.select("link[rel='alternate']").attr("href").read();
 
 	if alternate_url.contains("detail") {
-		let id = alternate_url
-			.split("/")
-			.map(|a| a.to_string())
-			.filter(|a| !a.is_empty())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
-			.replace(".html", "");
+		let id = parse::last_segment(&alternate_url);
 		let cover = html.select(".book-cover").attr("src").read();
 		let title = html.select("h1.book-title").text().read();
 
@@ -159,16 +165,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 				Ok(node) => node,
 				Err(_) => continue,
 			};
-			let id = item
-				.attr("href")
-				.read()
-				.split("/")
-				.map(|a| a.to_string())
-				.filter(|a| !a.is_empty())
-				.collect::<Vec<String>>()
-				.pop()
-				.unwrap()
-				.replace(".html", "");
+			let id = parse::last_segment(item.attr("href").read());
 			let cover = item.select(".book-cover>img").attr("data-src").read();
 			let title = item.select(".book-title").text().read();
 			mangas.push(Manga {
@@ -181,7 +178,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -230,8 +227,8 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 		_ => return get_manga_list(Vec::new(), page),
 	}
 
-	let url = format!("{}/top/{}/1.html", WWW_URL, name);
-	let html = gen_request(url, HttpMethod::Get).html()?;
+	let url = format!("{}/top/{}/1.html", www_url(), name);
+	let html = CLIENT.get(url).html()?;
 	let has_more = false;
 	let mut mangas: Vec<Manga> = Vec::new();
 
@@ -240,16 +237,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.filter(|a| !a.is_empty())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
-			.replace(".html", "");
+		let id = parse::last_segment(item.attr("href").read());
 		let cover = item.select(".book-cover>img").attr("data-src").read();
 		let title = item.select(".book-title").text().read();
 		mangas.push(Manga {
@@ -261,41 +249,29 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
 
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
-	let url = format!("{}/detail/{}.html", WWW_URL, id.clone());
-	let html = gen_request(url.clone(), HttpMethod::Get).html()?;
+	let url = format!("{}/detail/{}.html", www_url(), id.clone());
+	let html = CLIENT.get(url.clone()).html()?;
 	let cover = html.select(".book-cover").attr("src").read();
 	let title = html.select("h1.book-title").text().read();
-	let author = html
-		.select(".authorname,.illname")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read())
-		.collect::<Vec<String>>()
-		.join(", ");
+	let author = parse::texts(&html, ".authorname,.illname").join(", ");
 	let artist = String::new();
 	let description = html.select(".book-summary>content").text().read();
-	let categories = html
-		.select(".tag-small-group>.tag-small>a")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read())
-		.collect::<Vec<String>>();
+	let categories = parse::texts(&html, ".tag-small-group>.tag-small>a");
 	let status = match html
 		.select(".book-layout-inline")
 		.text()
 		.read()
-		.trim()
 		.split("|")
-		.map(|a| a.trim().to_string())
-		.collect::<Vec<String>>()
-		.first()
-		.unwrap()
-		.as_str()
+		.next()
+		.unwrap_or_default()
+		.trim()
 	{
 		"連載" => MangaStatus::Ongoing,
 		"完結" => MangaStatus::Completed,
@@ -304,7 +280,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let nsfw = MangaContentRating::Safe;
 	let viewer = MangaViewer::Rtl;
 
-	Ok(Manga {
+	Ok(convert::manga(Manga {
 		id,
 		cover,
 		title,
@@ -316,61 +292,53 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		status,
 		nsfw,
 		viewer,
-	})
+	}))
 }
 
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
-	let url = format!("{}/read/{}/catalog", WWW_URL, id.clone());
-	let html = gen_request(url.clone(), HttpMethod::Get).html()?;
+	let url = format!("{}/read/{}/catalog", www_url(), id.clone());
+	let html = CLIENT.get(url.clone()).html()?;
 	let list = html.select(".catalog-volume .chapter-li-a").array();
 	let mut chapters: Vec<Chapter> = Vec::new();
 
-	for (index, item) in list.enumerate() {
+	for item in list {
 		let item = match item.as_node() {
 			Ok(item) => item,
 			Err(_) => continue,
 		};
-		let chapter_id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.filter(|a| !a.is_empty())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
-			.replace(".html", "");
+		let chapter_id = parse::last_segment(item.attr("href").read());
 		let title = item.select("span").text().read();
-		let chapter = (index + 1) as f32;
+		let number = chapter::parse(&title);
 		let url = format!(
 			"{}/read/{}/{}.html",
-			WWW_URL,
+			www_url(),
 			id.clone(),
 			chapter_id.clone()
 		);
 		chapters.push(Chapter {
 			id: chapter_id,
 			title,
-			chapter,
+			volume: number.volume(),
+			chapter: number.chapter(),
 			url,
 			..Default::default()
 		});
 	}
 	chapters.reverse();
 
-	Ok(chapters)
+	Ok(convert::chapters(chapters))
 }
 
 #[get_page_list]
 fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 	let url = format!(
 		"{}/read/{}/{}.html",
-		WWW_URL,
+		www_url(),
 		manga_id.clone(),
 		chapter_id.clone()
 	);
-	let html = gen_request(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let mut pages: Vec<Page> = Vec::new();
 
 	for (index, item) in html.select("#acontentz>img").array().enumerate() {
@@ -387,10 +355,34 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 		})
 	}
 
+	if pages.is_empty() {
+		return Err(markup_changed("#acontentz>img"));
+	}
+
 	Ok(pages)
 }
 
 #[modify_image_request]
 fn modify_image_request(request: Request) {
-	request.header("User-Agent", UA).header("Referer", WWW_URL);
+	request
+		.header("User-Agent", MOBILE_UA)
+		.header("Referer", www_url().as_str());
+}
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let segments = parse::path_segments(&url);
+	let (id, chapter_id) = match segments.as_slice() {
+		[kind, id] if kind == "detail" => (parse::last_segment(id), None),
+		[kind, id, chapter_id] if kind == "read" && chapter_id != "catalog" => {
+			(id.clone(), Some(parse::last_segment(chapter_id)))
+		}
+		[kind, id, _] if kind == "read" => (id.clone(), None),
+		_ => return Err(parse_error()),
+	};
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id)?),
+		chapter: chapter_id.map(link::chapter),
+	})
 }
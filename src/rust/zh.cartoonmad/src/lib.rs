@@ -7,22 +7,20 @@ use aidoku::{
 	error::Result,
 	helpers::uri::encode_uri,
 	prelude::*,
-	std::{
-		html::Node,
-		net::{HttpMethod, Request},
-		String, Vec,
-	},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
-	MangaViewer, Page,
+	std::{html::Node, String, Vec},
+	Chapter, DeepLink, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult,
+	MangaStatus, MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	chapter, convert, entity,
+	error::{markup_changed, parse_error},
+	link, parse, Client, UA,
+};
 
 const WWW_URL: &str = "https://www.cartoonmad.com";
-const UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/135.0.0.0 Safari/537.36";
 
-fn gen_request(url: String, method: HttpMethod) -> Request {
-	Request::new(url, method).header("User-Agent", UA)
-}
+const CLIENT: Client = Client::new().user_agent(UA);
 
 fn handle_img_url(url: String) -> String {
 	if url.starts_with("http") {
@@ -47,12 +45,16 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	let url = if query.is_empty() {
 		format!("{}/m/?page={}", WWW_URL, page)
 	} else {
-		format!("{}/m/?keyword={}", WWW_URL, encode_uri(query.clone()))
+		format!(
+			"{}/m/?keyword={}",
+			WWW_URL,
+			encode_uri(convert::to_traditional(&query))
+		)
 	};
 	let has_more = query.is_empty();
 	let mut mangas: Vec<Manga> = Vec::new();
 
-	let html = gen_request(url, HttpMethod::Get).html()?;
+	let html = CLIENT.get(url).html()?;
 	let list = html.select(".comic_prev").array();
 
 	for item in list {
@@ -60,17 +62,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select(".a1")
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.filter(|a| !a.is_empty())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
-			.replace(".html", "");
+		let id = parse::last_segment(item.select(".a1").attr("href").read());
 		let cover = format!("{}{}", WWW_URL, item.select("img").attr("src").read());
 		let title = item.select(".covertxt+a").attr("title").read();
 		mangas.push(Manga {
@@ -82,7 +74,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -105,7 +97,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	let has_more = true;
 	let mut mangas: Vec<Manga> = Vec::new();
 
-	let html = gen_request(url, HttpMethod::Get).html()?;
+	let html = CLIENT.get(url).html()?;
 	let list = html.select(".comic_prev").array();
 
 	for item in list {
@@ -113,17 +105,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select(".a1")
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.filter(|a| !a.is_empty())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
-			.replace(".html", "");
+		let id = parse::last_segment(item.select(".a1").attr("href").read());
 		let cover = format!("{}{}", WWW_URL, item.select("img").attr("src").read());
 		let title = item.select(".covertxt+a").attr("title").read();
 		mangas.push(Manga {
@@ -135,7 +117,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -143,7 +125,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
 	let url = format!("{}/m/comic/{}.html", WWW_URL, id.clone());
-	let data = gen_request(url.clone(), HttpMethod::Get).data();
+	let data = CLIENT.get(url.clone()).data()?;
 	let html = Node::new(BIG5.decode(&data).0.as_bytes())?;
 	let cover = format!(
 		"{}{}",
@@ -155,18 +137,15 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		.attr("content")
 		.read()
 		.split(",")
-		.map(|a| a.trim().to_string())
-		.filter(|a| !a.is_empty())
-		.collect::<Vec<String>>()
-		.first()
-		.unwrap()
+		.map(|a| a.trim())
+		.find(|a| !a.is_empty())
+		.unwrap_or_default()
 		.to_string();
 	let author = html
-		.select("td[height='24'")
+		.select("td[height='24']")
 		.array()
 		.get(1)
-		.as_node()
-		.unwrap()
+		.as_node()?
 		.text()
 		.read()
 		.trim()
@@ -176,20 +155,15 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		.select("td[style='font-size:11pt;']")
 		.array()
 		.get(2)
-		.as_node()
-		.unwrap()
+		.as_node()?
 		.text()
 		.read();
-	let categories = html
-		.select("a[href*='tkey']")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read())
-		.collect::<Vec<String>>();
+	let categories = parse::texts(&html, "a[href*='tkey']");
 	let status = MangaStatus::Unknown;
 	let nsfw = MangaContentRating::Safe;
 	let viewer = MangaViewer::Rtl;
 
-	Ok(Manga {
+	Ok(convert::manga(Manga {
 		id,
 		cover,
 		title,
@@ -201,61 +175,56 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		status,
 		nsfw,
 		viewer,
-	})
+	}))
 }
 
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	let url = format!("{}/m/comic/{}.html", WWW_URL, id.clone());
-	let data = gen_request(url.clone(), HttpMethod::Get).data();
+	let data = CLIENT.get(url.clone()).data()?;
 	let html = Node::new(BIG5.decode(&data).0.as_bytes())?;
 	let list = html
 		.select("td[style='font-size:11pt;']")
 		.array()
 		.get(3)
-		.as_node()
-		.unwrap()
+		.as_node()?
 		.select("td a")
 		.array();
 	let mut chapters: Vec<Chapter> = Vec::new();
 
-	for (index, item) in list.enumerate() {
+	for item in list {
 		let item = match item.as_node() {
 			Ok(item) => item,
 			Err(_) => continue,
 		};
-		let id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.filter(|a| !a.is_empty())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
-			.replace(".html", "");
+		let id = parse::last_segment(item.attr("href").read());
 		let title = item.text().read().trim().to_string();
-		let chapter = (index + 1) as f32;
+		let number = chapter::parse(&title);
 		let url = format!("{}/m/comic/{}.html", WWW_URL, id.clone());
 		chapters.push(Chapter {
 			id,
 			title,
-			chapter,
+			volume: number.volume(),
+			chapter: number.chapter(),
 			url,
 			..Default::default()
 		});
 	}
 	chapters.reverse();
 
-	Ok(chapters)
+	Ok(convert::chapters(chapters))
 }
 
 #[get_page_list]
 fn get_page_list(_: String, chapter_id: String) -> Result<Vec<Page>> {
 	let url = format!("{}/m/comic/{}.html", WWW_URL, chapter_id.clone());
-	let data = gen_request(url.clone(), HttpMethod::Get).data();
+	let data = CLIENT.get(url.clone()).data()?;
 	let html = Node::new(BIG5.decode(&data).0.as_bytes())?;
-	let img_url = handle_img_url(html.select("img[onload]").attr("src").read());
+	let img_url = html.select("img[onload]").attr("src").read();
+	if img_url.is_empty() {
+		return Err(markup_changed("img[onload]"));
+	}
+	let img_url = handle_img_url(img_url);
 	let length = html
 		.select(".pages:not(:has(img))")
 		.last()
@@ -278,3 +247,23 @@ fn get_page_list(_: String, chapter_id: String) -> Result<Vec<Page>> {
 
 	Ok(pages)
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	// Both `/comic/{id}.html` and `/m/comic/{id}.html` are in use. Chapter
+	// IDs are the four-digit comic ID followed by the volume and chapter.
+	let segments = parse::path_segments(&url);
+	let id = match segments.as_slice() {
+		[.., kind, id] if kind == "comic" => parse::last_segment(id),
+		_ => return Err(parse_error()),
+	};
+	let (id, chapter_id) = match id.get(..4) {
+		Some(manga_id) if id.len() > 4 => (manga_id.to_string(), Some(id.clone())),
+		_ => (id, None),
+	};
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id)?),
+		chapter: chapter_id.map(link::chapter),
+	})
+}
@@ -1,65 +1,174 @@
 use aidoku::{
+	error::Result,
 	helpers::uri::encode_uri,
 	prelude::*,
 	std::{
-		html::Node,
-		net::{HttpMethod, Request},
-		ObjectRef, String,
+		current_date,
+		defaults::{defaults_get, defaults_set},
+		ObjectRef, String, StringRef,
 	},
 };
+use base64::{engine::general_purpose, Engine};
+use zh_common::{error::Error, Client, Mirrors, OptionExt, RequestBuilder, UA};
 
 use crate::crypto;
 
-const WWW_URL: &str = "https://www.mangacopy.com";
-const API_URL: &str = "https://api.mangacopy.com/api/v3";
-const UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/135.0.0.0 Safari/537.36";
+const MIRRORS: Mirrors = Mirrors::new(&["mangacopy.com", "copy20.com", "2024manga.com"]);
 
-pub fn decrypt(text: String, key: String) -> String {
+const WWW_CLIENT: Client = Client::new().user_agent(UA).mirrors(&MIRRORS);
+const API_CLIENT: Client = Client::new()
+	.user_agent("COPY/2.3.1")
+	.headers(&[("version", "2.3.1"), ("platform", "3"), ("region", "1")])
+	.mirrors(&MIRRORS);
+
+fn www_url() -> String {
+	format!("https://www.{}", MIRRORS.domain())
+}
+
+fn api_url() -> String {
+	format!("https://api.{}/api/v3", MIRRORS.domain())
+}
+
+pub fn decrypt(text: String, key: String) -> Result<String> {
 	let text = text.as_bytes();
-	let key = key.as_bytes();
-	let key: &[u8; 16] = key.try_into().unwrap();
-	let iv = &text[..16];
-	let cipher = &text[16..];
-	let cipher = hex::decode(cipher).unwrap();
-	let pt = crypto::decrypt(&cipher, key, iv).unwrap();
-	String::from_utf8_lossy(&pt).replace("", "")
+	let key: &[u8; 16] = key.as_bytes().try_into().ok().or_parse_error()?;
+	let iv = text.get(..16).or_parse_error()?;
+	let cipher = hex::decode(&text[16..]).ok().or_parse_error()?;
+	let pt = crypto::decrypt(&cipher, key, iv).ok().or_parse_error()?;
+	Ok(String::from_utf8_lossy(&pt).into_owned())
+}
+
+pub fn get_text(url: String) -> Result<String> {
+	WWW_CLIENT.get(url).string()
+}
+
+fn get_default(key: &str) -> String {
+	defaults_get(key)
+		.and_then(|a| a.as_string())
+		.map(|a| a.read())
+		.unwrap_or_default()
+}
+
+/// The size the image server scales pages to, after the `image_quality`
+/// setting.
+fn image_size() -> &'static str {
+	match get_default("image_quality").as_str() {
+		"low" => "c480x",
+		"high" => "c1200x",
+		"highest" => "c1500x",
+		_ => "c800x",
+	}
+}
+
+fn webp() -> bool {
+	get_default("image_format") != "jpg"
 }
 
-pub fn get_text(url: String) -> String {
-	Request::new(url.clone(), HttpMethod::Get)
-		.header("User-Agent", UA)
-		.string()
-		.unwrap()
+/// Points a page at the size and format chosen in settings. Pages end in a
+/// suffix such as `.c800x.webp` that the server resizes by.
+pub fn gen_image_url(url: String) -> String {
+	let suffix = url.rmatch_indices(".c").find(|(index, _)| {
+		let rest = &url[index + 2..];
+		let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+		digits > 0 && rest[digits..].starts_with('x')
+	});
+	match suffix {
+		Some((index, _)) => format!(
+			"{}.{}.{}",
+			&url[..index],
+			image_size(),
+			if webp() { "webp" } else { "jpg" }
+		),
+		None => url,
+	}
 }
 
-pub fn get_html(url: String) -> Node {
-	Request::new(url.clone(), HttpMethod::Get)
-		.header("User-Agent", UA)
-		.html()
-		.unwrap()
+fn api_get(url: String) -> RequestBuilder {
+	API_CLIENT
+		.get(url)
+		.header("webp", if webp() { "1" } else { "0" })
 }
 
-pub fn get_json(url: String) -> ObjectRef {
-	let request = Request::new(url.clone(), HttpMethod::Get);
+fn authorize(request: RequestBuilder, token: &str) -> RequestBuilder {
+	request.header("authorization", format!("Token {}", token))
+}
+
+fn has_account() -> bool {
+	!get_default("username").is_empty() && !get_default("password").is_empty()
+}
+
+/// Signs in with the saved account and keeps the token for later requests.
+pub fn login() -> Result<String> {
+	if !has_account() {
+		return Err(Error::LoginRequired.into());
+	}
+
+	let username = get_default("username");
+	let password = get_default("password");
+
+	// The site sends the password salted and base64 encoded.
+	let salt = (current_date() as i64).rem_euclid(9000) + 1000;
+	let password = general_purpose::STANDARD.encode(format!("{}-{}", password, salt));
+	let body = format!(
+		"username={}&password={}&salt={}&source=freeSite&version=2.3.1&platform=3",
+		encode_uri(username),
+		encode_uri(password),
+		salt
+	);
+	let json = API_CLIENT.post(gen_login_url()).form(body).json_object()?;
+
+	if json.get("code").as_int().unwrap_or_default() != 200 {
+		return Err(Error::LoginRequired.into());
+	}
 
-	let request = if url.starts_with(WWW_URL) {
-		request.header("User-Agent", UA)
+	let token = json.get("results").as_object()?.get("token");
+	defaults_set("token", token.clone());
+
+	Ok(token.as_string()?.read())
+}
+
+/// Sends an API request with the saved token. A rejected token is renewed by
+/// signing in again once, or dropped for an anonymous request when no account
+/// is set.
+fn send(request: RequestBuilder, token: &str) -> Result<ObjectRef> {
+	if let Some(json) = authorize(request.clone(), token).json_object_if_authorized()? {
+		return Ok(json);
+	}
+
+	if has_account() {
+		authorize(request, &login()?).json_object()
 	} else {
-		request
-			.header("User-Agent", "COPY/2.3.1")
-			.header("version", "2.3.1")
-			.header("platform", "3")
-			.header("region", "1")
-			.header("webp", "1")
+		defaults_set("token", StringRef::from("").0);
+		request.json_object()
+	}
+}
+
+pub fn get_json(url: String) -> Result<ObjectRef> {
+	if url.starts_with(&www_url()) {
+		return WWW_CLIENT.get(url).json_object();
+	}
+
+	// Signed-in users also see comics hidden from guests.
+	match get_default("token") {
+		token if token.is_empty() => api_get(url).json_object(),
+		token => send(api_get(url), &token),
+	}
+}
+
+/// Fetches a page of the account's own data, signing in first if needed.
+pub fn get_member_json(url: String) -> Result<ObjectRef> {
+	let token = match get_default("token") {
+		token if token.is_empty() => login()?,
+		token => token,
 	};
 
-	request.json().unwrap().as_object().unwrap()
+	send(api_get(url), &token)
 }
 
 pub fn gen_explore_url(theme: String, top: String, ordering: String, page: i32) -> String {
 	format!(
 		"{}/comics?theme={}&top={}&ordering={}&limit={}&offset={}",
-		API_URL,
+		api_url(),
 		theme,
 		top,
 		ordering,
@@ -68,12 +177,23 @@ pub fn gen_explore_url(theme: String, top: String, ordering: String, page: i32)
 	)
 }
 
-pub fn gen_search_url(query: String, page: i32) -> String {
+pub fn gen_author_url(author: String, ordering: String, page: i32) -> String {
+	format!(
+		"{}/comics?author={}&ordering={}&limit={}&offset={}",
+		api_url(),
+		encode_uri(author),
+		ordering,
+		50,
+		(page - 1) * 50,
+	)
+}
+
+pub fn gen_search_url(query: String, q_type: String, page: i32) -> String {
 	format!(
 		"{}/search/comic?q={}&q_type={}&limit={}&offset={}",
-		API_URL,
+		api_url(),
 		encode_uri(query),
-		"",
+		q_type,
 		20,
 		(page - 1) * 20
 	)
@@ -82,7 +202,7 @@ pub fn gen_search_url(query: String, page: i32) -> String {
 pub fn gen_rank_url(date_type: String, page: i32) -> String {
 	format!(
 		"{}/ranks?date_type={}&limit={}&offset={}",
-		API_URL,
+		api_url(),
 		date_type,
 		30,
 		(page - 1) * 30,
@@ -92,7 +212,7 @@ pub fn gen_rank_url(date_type: String, page: i32) -> String {
 pub fn gen_recs_url(page: i32) -> String {
 	format!(
 		"{}/recs?pos={}&limit={}&offset={}",
-		API_URL,
+		api_url(),
 		"3200102",
 		30,
 		(page - 1) * 30,
@@ -102,28 +222,50 @@ pub fn gen_recs_url(page: i32) -> String {
 pub fn gen_newest_url(page: i32) -> String {
 	format!(
 		"{}/update/newest?limit={}&offset={}",
-		API_URL,
+		api_url(),
+		30,
+		(page - 1) * 30,
+	)
+}
+
+pub fn gen_login_url() -> String {
+	format!("{}/login", api_url())
+}
+
+pub fn gen_collect_url(page: i32) -> String {
+	format!(
+		"{}/member/collect/comics?limit={}&offset={}&free_type=1&ordering=-datetime_modifier",
+		api_url(),
+		30,
+		(page - 1) * 30,
+	)
+}
+
+pub fn gen_browse_url(page: i32) -> String {
+	format!(
+		"{}/member/browse/comics?limit={}&offset={}&free_type=1",
+		api_url(),
 		30,
 		(page - 1) * 30,
 	)
 }
 
 pub fn gen_manga_url(id: String) -> String {
-	format!("{}/comic/{}", WWW_URL, id)
+	format!("{}/comic/{}", www_url(), id)
 }
 
-// pub fn gen_manga_details_url(id: String) -> String {
-// 	format!("{}/comic2/{}", API_URL, id)
-// }
+pub fn gen_manga_details_url(id: String) -> String {
+	format!("{}/comic2/{}", api_url(), id)
+}
 
 pub fn gen_chapter_list_url(id: String) -> String {
-	format!("{}/comicdetail/{}/chapters", WWW_URL, id)
+	format!("{}/comicdetail/{}/chapters", www_url(), id)
 }
 
 pub fn gen_chapter_url(manga_id: String, chapter_id: String) -> String {
-	format!("{}/comic/{}/chapter/{}", WWW_URL, manga_id, chapter_id)
+	format!("{}/comic/{}/chapter/{}", www_url(), manga_id, chapter_id)
 }
 
 pub fn gen_page_list_url(manga_id: String, chapter_id: String) -> String {
-	format!("{}/comic/{}/chapter/{}", WWW_URL, manga_id, chapter_id)
+	format!("{}/comic/{}/chapter/{}", www_url(), manga_id, chapter_id)
 }
@@ -3,13 +3,12 @@ extern crate alloc;
 
 use aidoku::{
 	error::Result,
-	helpers::substring::Substring,
 	prelude::*,
 	std::{json, String, Vec},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaViewer,
-	Page,
+	Chapter, DeepLink, Filter, FilterType, Listing, Manga, MangaPageResult, Page,
 };
 use alloc::string::ToString;
+use zh_common::{convert, entity, error::parse_error, link, parse, OptionExt};
 
 mod crypto;
 mod helper;
@@ -80,10 +79,13 @@ const FILTER_THEME: [&str; 61] = [
 ];
 const FILTER_TOP: [&str; 4] = ["", "japan", "korea", "west"];
 const FILTER_ORDERING: [&str; 2] = ["popular", "datetime_updated"];
+const FILTER_Q_TYPE: [&str; 4] = ["", "name", "author", "local"];
 
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	let mut query = String::new();
+	let mut q_type = String::new();
+	let mut author = String::new();
 	let mut theme = String::new();
 	let mut top = String::new();
 	let mut ordering = String::new();
@@ -93,6 +95,9 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			FilterType::Title => {
 				query = filter.value.as_string()?.read();
 			}
+			FilterType::Author => {
+				author = filter.value.as_string()?.read();
+			}
 			FilterType::Select => {
 				let index = filter.value.as_int()? as usize;
 				match filter.name.as_str() {
@@ -102,6 +107,9 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 					"地区" => {
 						top = FILTER_TOP[index].to_string();
 					}
+					"搜索类型" => {
+						q_type = FILTER_Q_TYPE[index].to_string();
+					}
 					_ => continue,
 				}
 			}
@@ -119,19 +127,67 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 		}
 	}
 
-	let url = if query.is_empty() {
-		helper::gen_explore_url(theme, top, ordering, page)
-	} else {
-		helper::gen_search_url(query, page)
+	if !author.is_empty() {
+		return get_author_manga_list(author, ordering, page);
+	}
+
+	if !query.is_empty() {
+		return search(query, q_type, page);
+	}
+
+	let url = helper::gen_explore_url(theme, top, ordering, page);
+	let json = helper::get_json(url)?;
+	let data = json.get("results").as_object()?;
+	let list = data.get("list").as_array()?;
+
+	Ok(MangaPageResult {
+		manga: entity::titles(parser::parse_manga_list(list)?),
+		has_more: parser::has_more(data)?,
+	})
+}
+
+fn search(query: String, q_type: String, page: i32) -> Result<MangaPageResult> {
+	// Titles are indexed in Simplified. Searching a second script as well would
+	// page through two result lists and repeat works across pages.
+	let url = helper::gen_search_url(convert::to_simplified(query), q_type, page);
+	let json = helper::get_json(url)?;
+	let data = json.get("results").as_object()?;
+	let list = data.get("list").as_array()?;
+
+	Ok(MangaPageResult {
+		manga: entity::titles(parser::parse_manga_list(list)?),
+		has_more: parser::has_more(data)?,
+	})
+}
+
+/// Lists the works of the author named `author`. Comics are listed by the
+/// author's path word, so it is looked up with an author search first, in
+/// either script; the search results stand in when no author matches.
+fn get_author_manga_list(author: String, ordering: String, page: i32) -> Result<MangaPageResult> {
+	// Details join several authors into one line.
+	let name = author.split(", ").next().unwrap_or_default().to_string();
+	let mut path_word = None;
+	for variant in convert::variants(&name) {
+		let url = helper::gen_search_url(variant.clone(), String::from("author"), 1);
+		let json = helper::get_json(url)?;
+		let list = json.get("results").as_object()?.get("list").as_array()?;
+		path_word = parser::find_author(list, &variant);
+		if path_word.is_some() {
+			break;
+		}
+	}
+	let Some(path_word) = path_word else {
+		return search(name, String::from("author"), page);
 	};
 
-	let json = helper::get_json(url);
+	let url = helper::gen_author_url(path_word, ordering, page);
+	let json = helper::get_json(url)?;
 	let data = json.get("results").as_object()?;
 	let list = data.get("list").as_array()?;
 
 	Ok(MangaPageResult {
-		manga: parser::parse_manga_list(list),
-		has_more: parser::has_more(data),
+		manga: entity::titles(parser::parse_manga_list(list)?),
+		has_more: parser::has_more(data)?,
 	})
 }
 
@@ -140,6 +196,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	let mut date_type = String::new();
 	let mut is_recs = false;
 	let mut is_newest = false;
+	let mut member_url = None;
 
 	match listing.name.as_str() {
 		"日榜" => {
@@ -160,6 +217,12 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 		"全新上架" => {
 			is_newest = true;
 		}
+		"我的书架" => {
+			member_url = Some(helper::gen_collect_url(page));
+		}
+		"浏览历史" => {
+			member_url = Some(helper::gen_browse_url(page));
+		}
 		_ => return get_manga_list(Vec::new(), page),
 	}
 
@@ -173,101 +236,79 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 		String::new()
 	};
 
-	let json = helper::get_json(url);
+	let json = match member_url {
+		Some(url) => helper::get_member_json(url)?,
+		None => helper::get_json(url)?,
+	};
 	let data = json.get("results").as_object()?;
 	let list = data.get("list").as_array()?;
 
 	Ok(MangaPageResult {
-		manga: parser::parse_manga_list(list),
-		has_more: parser::has_more(data),
+		manga: entity::titles(parser::parse_manga_list(list)?),
+		has_more: parser::has_more(data)?,
 	})
 }
 
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
-	let url = helper::gen_manga_url(id.clone());
-	let html = helper::get_html(url.clone());
-	let cover = html
-		.select(".comicParticulars-left-img>img")
-		.attr("data-src")
-		.read();
-	let title = html.select("h6").text().read();
-	let author = html
-		.select(".comicParticulars-right-txt>a")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read())
-		.collect::<Vec<String>>()
-		.join(", ");
-	let artist = String::new();
-	let description = html.select(".intro").text().read();
-	let categories = html
-		.select(".comicParticulars-tag>a")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read().replace("#", ""))
-		.collect::<Vec<String>>();
-	let full_title = html.select("title").text().read();
-	let status = if full_title.contains("連載中") {
-		aidoku::MangaStatus::Ongoing
-	} else if full_title.contains("已完結") {
-		aidoku::MangaStatus::Completed
-	} else {
-		aidoku::MangaStatus::Unknown
-	};
-	let nsfw = MangaContentRating::Safe;
-	let viewer = MangaViewer::Rtl;
+	let url = helper::gen_manga_details_url(id);
+	let json = helper::get_json(url)?;
+	let comic = json
+		.get("results")
+		.as_object()?
+		.get("comic")
+		.as_object()
+		.ok()
+		.or_markup_changed("comic")?;
 
-	Ok(Manga {
-		id,
-		cover,
-		title,
-		author,
-		artist,
-		description,
-		url,
-		categories,
-		status,
-		nsfw,
-		viewer,
-	})
+	Ok(convert::manga(parser::parse_manga(comic)?))
 }
 
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	let manga_url = helper::gen_manga_url(id.clone());
-	let text = helper::get_text(manga_url);
-	let key = text
-		.substring_after("var ccx = '")
-		.unwrap()
-		.substring_before("'")
-		.unwrap()
-		.to_string();
+	let text = helper::get_text(manga_url)?;
+	let key = parse::between(&text, "var ccx = '", "'")?;
 	let url = helper::gen_chapter_list_url(id);
-	let json = helper::get_json(url);
+	let json = helper::get_json(url)?;
 	let data = json.get("results").as_string()?.read();
-	let data = helper::decrypt(data, key);
+	let data = helper::decrypt(data, key)?;
 	let data = json::parse(data)?.as_object()?;
 
-	Ok(parser::parse_chapter_list(data))
+	parser::parse_chapter_list(data).map(convert::chapters)
 }
 
 #[get_page_list]
 fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 	let url = helper::gen_page_list_url(manga_id, chapter_id);
-	let text = helper::get_text(url);
-	let key = text
-		.substring_after("var ccy = '")
-		.unwrap()
-		.substring_before("'")
-		.unwrap()
-		.to_string();
-	let data = text
-		.substring_after("contentKey=\"")
-		.unwrap()
-		.substring_before("\"")
-		.unwrap()
-		.to_string();
-	let data = helper::decrypt(data, key);
+	let text = helper::get_text(url)?;
+	let key = parse::between(&text, "var ccy = '", "'")?;
+	let data = parse::between(&text, "contentKey=\"", "\"")?;
+	let data = helper::decrypt(data, key)?;
 	let data = json::parse(data)?.as_array()?;
 
-	Ok(parser::parse_page_list(data))
+	parser::parse_page_list(data)
+}
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let segments = parse::path_segments(&url);
+	let (id, chapter_id) = match segments.as_slice() {
+		[kind, id] if kind == "comic" => (id.clone(), None),
+		[kind, id, chapter, chapter_id] if kind == "comic" && chapter == "chapter" => {
+			(id.clone(), Some(chapter_id.clone()))
+		}
+		[h5, details, kind, id] if h5 == "h5" && details == "details" && kind == "comic" => {
+			(id.clone(), None)
+		}
+		[h5, content, id, chapter_id] if h5 == "h5" && content == "comicContent" => {
+			(id.clone(), Some(chapter_id.clone()))
+		}
+		_ => return Err(parse_error()),
+	};
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id)?),
+		chapter: chapter_id.map(link::chapter),
+	})
 }
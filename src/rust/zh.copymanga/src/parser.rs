@@ -1,7 +1,7 @@
 use core::str::FromStr;
 
 use aidoku::{
-	prelude::*,
+	error::Result,
 	std::{ArrayRef, ObjectRef, String, Vec},
 	Chapter, Manga, MangaContentRating, MangaStatus, MangaViewer, Page,
 };
@@ -9,44 +9,67 @@ use alloc::string::ToString;
 
 use crate::helper;
 use uuid::Uuid;
+use zh_common::chapter;
 
-pub fn has_more(data: ObjectRef) -> bool {
-	let total = data.get("total").as_int().unwrap();
-	let limit = data.get("limit").as_int().unwrap();
-	let offset = data.get("offset").as_int().unwrap();
-	total > limit + offset
+pub fn has_more(data: ObjectRef) -> Result<bool> {
+	let total = data.get("total").as_int()?;
+	let limit = data.get("limit").as_int()?;
+	let offset = data.get("offset").as_int()?;
+	Ok(total > limit + offset)
 }
 
-pub fn parse_manga_list(manga_list: ArrayRef) -> Vec<Manga> {
+pub fn parse_manga_list(manga_list: ArrayRef) -> Result<Vec<Manga>> {
 	manga_list
-		.map(|manga| parse_manga(manga.as_object().unwrap()))
-		.collect::<Vec<Manga>>()
+		.map(|manga| parse_manga(manga.as_object()?))
+		.collect::<Result<Vec<Manga>>>()
 }
 
-pub fn parse_manga(manga: ObjectRef) -> Manga {
-	let manga = match manga.get("comic").as_object() {
-		Ok(value) => value,
-		Err(_) => manga,
-	};
-	let id = manga.get("path_word").as_string().unwrap().read();
-	let cover = manga.get("cover").as_string().unwrap().read();
-	let title = manga.get("name").as_string().unwrap().read();
-	let author = manga
-		.get("author")
-		.as_array()
-		.unwrap()
-		.map(|author| {
+/// The path word of the author called `name` among the authors of `list`.
+pub fn find_author(manga_list: ArrayRef, name: &str) -> Option<String> {
+	manga_list
+		.filter_map(|manga| manga.as_object().ok())
+		.filter_map(|manga| manga.get("author").as_array().ok())
+		.flatten()
+		.filter_map(|author| author.as_object().ok())
+		.find(|author| {
 			author
-				.as_object()
-				.unwrap()
 				.get("name")
 				.as_string()
-				.unwrap()
-				.read()
+				.is_ok_and(|a| a.read() == name)
 		})
-		.collect::<Vec<String>>()
-		.join(", ");
-	let artist = String::new();
+		.and_then(|author| author.get("path_word").as_string().ok())
+		.map(|path_word| path_word.read())
+}
+
+/// The names in the list of people under `key`, such as `author`.
+fn names(manga: &ObjectRef, key: &str) -> Vec<String> {
+	manga
+		.get(key)
+		.as_array()
+		.unwrap_or_default()
+		.filter_map(|person| person.as_object().ok())
+		.filter_map(|person| person.get("name").as_string().ok())
+		.map(|name| name.read())
+		.collect()
+}
+
+pub fn parse_manga(manga: ObjectRef) -> Result<Manga> {
+	let manga = match manga.get("comic").as_object() {
+		Ok(value) => value,
+		Err(_) => manga,
+	};
+	let id = manga.get("path_word").as_string()?.read();
+	let cover = manga.get("cover").as_string()?.read();
+	let title = manga.get("name").as_string()?.read();
+	let authors = names(&manga, "author");
+	// Comics credit an artist only when someone else draws them; a sole
+	// creator both writes and draws.
+	let artist = match names(&manga, "artist") {
+		artists if !artists.is_empty() => artists.join(", "),
+		_ if authors.len() == 1 => authors[0].clone(),
+		_ => String::new(),
+	};
+	let author = authors.join(", ");
 	let description = manga
 		.get("brief")
 		.as_string()
@@ -97,8 +120,23 @@ pub fn parse_manga(manga: ObjectRef) -> Manga {
 		4 => MangaContentRating::Nsfw,
 		_ => MangaContentRating::Safe,
 	};
-	let viewer = MangaViewer::Rtl;
-	Manga {
+	let region = manga
+		.get("region")
+		.as_object()
+		.unwrap_or_default()
+		.get("display")
+		.as_string()
+		.unwrap_or_default()
+		.read();
+	let viewer = if region.contains('韩')
+		|| region.contains('韓')
+		|| categories.iter().any(|a| a == "长条" || a == "長條")
+	{
+		MangaViewer::Scroll
+	} else {
+		MangaViewer::Rtl
+	};
+	Ok(Manga {
 		id,
 		cover,
 		title,
@@ -110,92 +148,90 @@ pub fn parse_manga(manga: ObjectRef) -> Manga {
 		status,
 		nsfw,
 		viewer,
-	}
+	})
 }
 
-pub fn parse_chapter_group(
-	manga_id: String,
-	group: ObjectRef,
-	name: String,
-	start: usize,
-) -> Vec<Chapter> {
+/// The `type` of chapters that are whole volumes (单行本).
+const VOLUME_TYPE: i64 = 2;
+
+pub fn parse_chapter_group(manga_id: String, group: ObjectRef) -> Result<Vec<Chapter>> {
+	let scanlator = group.get("name").as_string().unwrap_or_default().read();
 	let list = group.get("chapters").as_array();
 	let mut chapters: Vec<Chapter> = Vec::new();
 
-	if list.is_ok() {
-		for (index, item) in list.unwrap().enumerate() {
-			let chapter = item.as_object().unwrap();
-			let id = chapter.get("id").as_string().unwrap().read();
-			let title = format!(
-				"{} - {}",
-				name,
-				chapter.get("name").as_string().unwrap().read()
-			);
-			let chapter = (index + start + 1) as f32;
-			let (p1, p2) = Uuid::from_str(&id.clone())
-				.unwrap()
-				.get_timestamp()
-				.unwrap()
-				.to_unix();
-			let date_updated = (p1 as f64) + (p2 as f64 * 10e-10);
+	if let Ok(list) = list {
+		for (index, item) in list.enumerate() {
+			let item = item.as_object()?;
+			let id = item.get("id").as_string()?.read();
+			let title = item.get("name").as_string()?.read();
+			let number = chapter::parse(&title);
+			let is_volume = item.get("type").as_int().unwrap_or_default() == VOLUME_TYPE;
+			let (volume, chapter) = if is_volume {
+				let volume = number
+					.volume
+					.or(number.chapter)
+					.unwrap_or((index + 1) as f32);
+				(volume, -1.0)
+			} else {
+				(number.volume(), number.chapter())
+			};
+			let date_updated = match Uuid::from_str(&id)
+				.ok()
+				.and_then(|uuid| uuid.get_timestamp())
+			{
+				Some(timestamp) => {
+					let (p1, p2) = timestamp.to_unix();
+					(p1 as f64) + (p2 as f64 * 10e-10)
+				}
+				None => 0.0,
+			};
 			let url = helper::gen_chapter_url(manga_id.clone(), id.clone());
 			chapters.push(Chapter {
 				id,
 				title,
+				volume,
 				chapter,
 				date_updated,
 				url,
+				scanlator: scanlator.clone(),
 				..Default::default()
 			})
 		}
 	}
 
-	chapters
+	Ok(chapters)
 }
 
-pub fn parse_chapter_list(manga: ObjectRef) -> Vec<Chapter> {
-	let build = manga.get("build").as_object().unwrap();
-	let manga_id = build.get("path_word").as_string().unwrap().read();
-	let groups = manga.get("groups").as_object().unwrap();
-	let default_group = groups.get("default").as_object().unwrap_or_default();
-	let tankobon_group = groups.get("tankobon").as_object().unwrap_or_default();
-	let other_honyakuchimu_group = groups
-		.get("other_honyakuchimu")
-		.as_object()
-		.unwrap_or_default();
-	let karapeji_group = groups.get("karapeji").as_object().unwrap_or_default();
-	let default = parse_chapter_group(manga_id.clone(), default_group, String::from("默认"), 0);
-	let tankobon = parse_chapter_group(
-		manga_id.clone(),
-		tankobon_group,
-		String::from("单行本"),
-		default.len(),
-	);
-	let other_honyakuchimu = parse_chapter_group(
-		manga_id.clone(),
-		other_honyakuchimu_group,
-		String::from("其它汉化版"),
-		default.len() + tankobon.len(),
-	);
-	let karapeji = parse_chapter_group(
-		manga_id.clone(),
-		karapeji_group,
-		String::from("全彩版"),
-		default.len() + tankobon.len() + other_honyakuchimu.len(),
-	);
-	let mut chapters = [default, tankobon, other_honyakuchimu, karapeji].concat();
+/// Reads every group the site returns, the main one first, naming each
+/// chapter's scanlator after its group.
+pub fn parse_chapter_list(manga: ObjectRef) -> Result<Vec<Chapter>> {
+	let build = manga.get("build").as_object()?;
+	let manga_id = build.get("path_word").as_string()?.read();
+	let groups = manga.get("groups").as_object()?;
+	let mut keys = groups
+		.keys()
+		.filter_map(|a| a.as_string().ok())
+		.map(|a| a.read())
+		.collect::<Vec<String>>();
+	keys.sort_by_key(|a| (a != "default", a.clone()));
+
+	let mut chapters: Vec<Chapter> = Vec::new();
+	for key in keys {
+		let group = groups.get(&key).as_object()?;
+		chapters.extend(parse_chapter_group(manga_id.clone(), group)?);
+	}
 
 	chapters.reverse();
-	chapters
+	Ok(chapters)
 }
 
-pub fn parse_page_list(chapters: ArrayRef) -> Vec<Page> {
+pub fn parse_page_list(chapters: ArrayRef) -> Result<Vec<Page>> {
 	let mut pages: Vec<Page> = Vec::new();
 
 	for (index, item) in chapters.enumerate() {
-		let page = item.as_object().unwrap();
+		let page = item.as_object()?;
 		let index = index as i32;
-		let url = page.get("url").as_string().unwrap().read();
+		let url = helper::gen_image_url(page.get("url").as_string()?.read());
 		pages.push(Page {
 			index,
 			url,
@@ -203,5 +239,5 @@ pub fn parse_page_list(chapters: ArrayRef) -> Vec<Page> {
 		})
 	}
 
-	pages
+	Ok(pages)
 }
@@ -5,20 +5,26 @@ use aidoku::{
 	error::Result,
 	helpers::uri::encode_uri,
 	prelude::*,
-	std::{
-		net::{HttpMethod, Request},
-		String, Vec,
-	},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
-	MangaViewer, Page,
+	std::{net::Request, String, Vec},
+	Chapter, DeepLink, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult,
+	MangaStatus, MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	chapter, convert, date, entity, error::parse_error, link, parse, Client, Mirrors, OptionExt,
+};
 
-const WWW_URL: &str = "https://godamh.com";
+const MIRRORS: Mirrors = Mirrors::new(&["godamh.com", "g-mh.org"]);
 const API_URL: &str = "https://api-get-v2.mgsearcher.com";
 const IMG_URL: &str = "https://f40-1-4.g-mh.online";
 
-const FILTER_CATEGORY: [&str; 35] = [
+const CLIENT: Client = Client::new()
+	.referer("https://godamh.com")
+	.mirrors(&MIRRORS)
+	.clearance();
+const API_CLIENT: Client = CLIENT.origin("https://godamh.com");
+
+const FILTER_CATEGORY: [&str; 34] = [
 	"",
 	"cn",
 	"kr",
@@ -39,7 +45,6 @@ const FILTER_CATEGORY: [&str; 35] = [
 	"lingyi",
 	"danvzhu",
 	"juqing",
-	"lianai",
 	"xuanhuan",
 	"nvshen",
 	"kehuan",
@@ -58,11 +63,7 @@ const FILTER_CATEGORY: [&str; 35] = [
 
 fn handle_cover_url(url: String) -> String {
 	if url.contains("url=") {
-		url.split("url=")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
+		parse::after_last(&url, "url=")
 			.replace("%3A", ":")
 			.replace("%2F", "/")
 			.replace("&w=250&q=60", "")
@@ -71,6 +72,10 @@ fn handle_cover_url(url: String) -> String {
 	}
 }
 
+fn www_url() -> String {
+	format!("https://{}", MIRRORS.domain())
+}
+
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	let mut query = String::new();
@@ -105,26 +110,19 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 		} else {
 			format!("manga-tag/{}", category)
 		};
-		format!("{}/{}/page/{}", WWW_URL, caregory_str, page)
+		format!("{}/{}/page/{}", www_url(), caregory_str, page)
 	} else {
-		format!("{}/s/{}?page={}", WWW_URL, encode_uri(query), page)
+		format!("{}/s/{}?page={}", www_url(), encode_uri(query), page)
 	};
 
-	let html = Request::new(url, HttpMethod::Get).html()?;
+	let html = CLIENT.get(url).html()?;
 
 	for item in html.select(".pb-2>a").array() {
 		let item = match item.as_node() {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = parse::last_segment(item.attr("href").read());
 		let cover = handle_cover_url(item.select("div>img").attr("src").read());
 		let title = item.select("div>h3").text().read();
 		mangas.push(Manga {
@@ -136,7 +134,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -158,8 +156,8 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 		_ => return get_manga_list(Vec::new(), page),
 	}
 
-	let url = format!("{}/{}/page/{}", WWW_URL, list, page);
-	let html = Request::new(url, HttpMethod::Get).html()?;
+	let url = format!("{}/{}/page/{}", www_url(), list, page);
+	let html = CLIENT.get(url).html()?;
 	let has_more = true;
 	let mut mangas: Vec<Manga> = Vec::new();
 
@@ -168,14 +166,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = parse::last_segment(item.attr("href").read());
 		let cover = handle_cover_url(item.select("div>img").attr("src").read());
 		let title = item.select("div>h3").text().read();
 		mangas.push(Manga {
@@ -187,7 +178,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -195,8 +186,8 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
 	let ids = id.split("/").collect::<Vec<&str>>();
-	let url = format!("{}/manga/{}", WWW_URL, ids[0]);
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let url = format!("{}/manga/{}", www_url(), ids[0]);
+	let html = CLIENT.get(url.clone()).html()?;
 	let mid = html.select("#mangachapters").attr("data-mid").read();
 	let cover = handle_cover_url(
 		html.select("meta[property='og:image']")
@@ -204,24 +195,18 @@ fn get_manga_details(id: String) -> Result<Manga> {
 			.read(),
 	);
 	let title = html.select("title").text().read().replace("-G站漫畫", "");
-	let author = html
-		.select("a[href*=author]>span")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read().replace(",", ""))
+	let author = parse::texts(&html, "a[href*=author]>span")
+		.iter()
+		.map(|a| a.replace(",", ""))
 		.filter(|a| !a.is_empty())
 		.collect::<Vec<String>>()
 		.join(", ");
 	let artist = String::new();
 	let description = html.select(".text-medium.my-unit-md").text().read();
-	let categories = html
-		.select(".py-1>a:not([href*=author])>span")
-		.array()
+	let categories = parse::texts(&html, ".py-1>a:not([href*=author])>span")
+		.iter()
 		.map(|a| {
-			a.as_node()
-				.unwrap()
-				.text()
-				.read()
-				.replace(",", "")
+			a.replace(",", "")
 				.replace("热门漫画", "")
 				.replace("#", "")
 				.replace("热门推荐", "")
@@ -234,7 +219,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let nsfw = MangaContentRating::Safe;
 	let viewer = MangaViewer::Scroll;
 
-	Ok(Manga {
+	Ok(convert::manga(Manga {
 		id: format!("{}/{}", ids[0], mid),
 		cover,
 		title,
@@ -246,23 +231,24 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		status,
 		nsfw,
 		viewer,
-	})
+	}))
 }
 
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	let ids = id.split("/").collect::<Vec<&str>>();
-	let url = format!("{}/api/manga/get?mid={}&mode=all", API_URL, ids[1]);
-	let json = Request::new(url.clone(), HttpMethod::Get)
-		.header("Origin", &WWW_URL)
-		.header("Referer", &WWW_URL)
-		.json()?;
+	let url = format!(
+		"{}/api/manga/get?mid={}&mode=all",
+		API_URL,
+		ids.get(1).or_parse_error()?
+	);
+	let json = API_CLIENT.get(url).json()?;
 	let data = json.as_object()?;
 	let data = data.get("data").as_object()?;
 	let list = data.get("chapters").as_array()?;
 	let mut chapters: Vec<Chapter> = Vec::new();
 
-	for (index, item) in list.enumerate() {
+	for item in list {
 		let item = match item.as_object() {
 			Ok(item) => item,
 			Err(_) => continue,
@@ -271,19 +257,28 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 		let id = item.get("id").as_int()?.to_string();
 		let title = attributes.get("title").as_string()?.read();
 		let slug = attributes.get("slug").as_string()?.read();
-		let url = format!("{}/manga/{}/{}", WWW_URL, ids[0], slug);
-		let chapter = (index + 1) as f32;
+		let date_updated = date::parse(
+			attributes
+				.get("updatedAt")
+				.as_string()
+				.map(|a| a.read())
+				.unwrap_or_default(),
+		);
+		let url = format!("{}/manga/{}/{}", www_url(), ids[0], slug);
+		let number = chapter::parse(&title);
 		chapters.push(Chapter {
 			id,
 			title,
-			chapter,
+			volume: number.volume(),
+			chapter: number.chapter(),
+			date_updated,
 			url,
 			..Default::default()
 		});
 	}
 	chapters.reverse();
 
-	Ok(chapters)
+	Ok(convert::chapters(chapters))
 }
 
 #[get_page_list]
@@ -292,13 +287,10 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 	let url = format!(
 		"{}/api/chapter/getinfo?m={}&c={}",
 		API_URL,
-		ids[1],
+		ids.get(1).or_parse_error()?,
 		chapter_id.clone()
 	);
-	let json = Request::new(url.clone(), HttpMethod::Get)
-		.header("Origin", &WWW_URL)
-		.header("Referer", &WWW_URL)
-		.json()?;
+	let json = API_CLIENT.get(url).json()?;
 	let data = json.as_object()?;
 	let data = data.get("data").as_object()?;
 	let info = data.get("info").as_object()?;
@@ -328,5 +320,25 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 
 #[modify_image_request]
 fn modify_image_request(request: Request) {
-	request.header("Referer", &WWW_URL);
+	CLIENT.apply(request);
+}
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let segments = parse::path_segments(&url);
+	let slug = match segments.as_slice() {
+		[kind, slug, ..] if kind == "manga" => slug.clone(),
+		_ => return Err(parse_error()),
+	};
+	let manga = get_manga_details(slug)?;
+	let chapter = if segments.len() > 2 {
+		link::find_chapter(get_chapter_list(manga.id.clone())?, &url)
+	} else {
+		None
+	};
+
+	Ok(DeepLink {
+		manga: Some(manga),
+		chapter,
+	})
 }
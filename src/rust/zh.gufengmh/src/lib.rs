@@ -3,21 +3,20 @@ extern crate alloc;
 
 use aidoku::{
 	error::Result,
-	helpers::{substring::Substring, uri::encode_uri},
+	helpers::uri::encode_uri,
 	prelude::*,
-	std::{
-		json,
-		net::{HttpMethod, Request},
-		String, Vec,
-	},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
-	MangaViewer, Page,
+	std::{json, String, Vec},
+	Chapter, DeepLink, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult,
+	MangaStatus, MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{chapter, convert, date, entity, error::parse_error, link, parse, Client};
 
 const WWW_URL: &str = "https://www.gufengmh.com";
 const IMG_URL: &str = "https://res1.xiaoqinre.com";
 
+const CLIENT: Client = Client::new();
+
 const FILTER_GENRE: [&str; 5] = ["", "shaonian", "shaonv", "qingnian", "zhenrenmanhua"];
 const FILTER_REGION: [&str; 6] = [
 	"",
@@ -87,7 +86,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			page
 		)
 	};
-	let html = Request::new(url, HttpMethod::Get).html()?;
+	let html = CLIENT.get(url).html()?;
 	let has_more = true;
 	let mut mangas: Vec<Manga> = Vec::new();
 
@@ -96,16 +95,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select(".cover")
-			.attr("href")
-			.read()
-			.split("/")
-			.filter(|a| !a.is_empty())
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = parse::last_segment(item.select(".cover").attr("href").read());
 		let cover = item.select(".cover>img").attr("src").read();
 		let title = item.select(".ell>a").text().read().trim().to_string();
 		mangas.push(Manga {
@@ -117,7 +107,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -158,7 +148,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	}
 
 	let url = format!("{}/rank/{}/", WWW_URL, name);
-	let html = Request::new(url, HttpMethod::Get).html()?;
+	let html = CLIENT.get(url).html()?;
 	let has_more = false;
 	let mut mangas: Vec<Manga> = Vec::new();
 
@@ -167,16 +157,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select(".cover")
-			.attr("href")
-			.read()
-			.split("/")
-			.filter(|a| !a.is_empty())
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = parse::last_segment(item.select(".cover").attr("href").read());
 		let cover = item.select(".cover>img").attr("src").read();
 		let title = item.select(".ell>a").text().read().trim().to_string();
 		mangas.push(Manga {
@@ -188,7 +169,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -196,7 +177,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
 	let url = format!("{}/manhua/{}/", WWW_URL, id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let cover = html.select(".book-cover>.cover>img").attr("src").read();
 	let title = html.select(".book-title>h1>span").text().read();
 	let author = html.select("a[href*='author']").text().read();
@@ -209,12 +190,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		.replace("介绍:", "")
 		.trim()
 		.to_string();
-	let categories = html
-		.select(".detail-list>li:nth-child(2)>span:nth-child(1)>a")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read().trim().to_string())
-		.filter(|a| !a.is_empty())
-		.collect::<Vec<String>>();
+	let categories = parse::texts(&html, ".detail-list>li:nth-child(2)>span:nth-child(1)>a");
 	let status = match html
 		.select(".detail-list>li:nth-child(1)>span:nth-child(1)>a")
 		.text()
@@ -228,7 +204,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let nsfw = MangaContentRating::Safe;
 	let viewer = MangaViewer::Scroll;
 
-	Ok(Manga {
+	Ok(convert::manga(Manga {
 		id,
 		cover,
 		title,
@@ -240,32 +216,24 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		status,
 		nsfw,
 		viewer,
-	})
+	}))
 }
 
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	let url = format!("{}/manhua/{}/", WWW_URL, id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let list = html.select("#chapter-list-1>li>a").array();
 	let mut chapters: Vec<Chapter> = Vec::new();
 
-	for (index, item) in list.enumerate() {
+	for item in list {
 		let item = match item.as_node() {
 			Ok(item) => item,
 			Err(_) => continue,
 		};
-		let chapter_id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
-			.replace(".html", "");
+		let chapter_id = parse::last_segment(item.attr("href").read());
 		let title = item.select("span").text().read();
-		let chapter = (index + 1) as f32;
+		let number = chapter::parse(&title);
 		let url = format!(
 			"{}/manhua/{}/{}.html",
 			WWW_URL,
@@ -275,14 +243,23 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 		chapters.push(Chapter {
 			id: chapter_id,
 			title,
-			chapter,
+			volume: number.volume(),
+			chapter: number.chapter(),
 			url,
 			..Default::default()
 		});
 	}
 	chapters.reverse();
 
-	Ok(chapters)
+	// Only the latest chapter's date is shown, next to the status.
+	let updated = parse::texts(&html, ".detail-list span")
+		.into_iter()
+		.find(|a| a.contains("更新时间"));
+	if let (Some(latest), Some(updated)) = (chapters.first_mut(), updated) {
+		latest.date_updated = date::parse(updated);
+	}
+
+	Ok(convert::chapters(chapters))
 }
 
 #[get_page_list]
@@ -293,20 +270,11 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 		manga_id.clone(),
 		chapter_id.clone()
 	);
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let text = html.html().read();
-	let list = text
-		.substring_after("var chapterImages = ")
-		.unwrap()
-		.substring_before(";")
-		.unwrap();
-	let path = text
-		.substring_after("var chapterPath = ")
-		.unwrap()
-		.substring_before(";")
-		.unwrap()
-		.replace("\"", "");
-	let list = json::parse(list).unwrap().as_array()?;
+	let list = parse::between(&text, "var chapterImages = ", ";")?;
+	let path = parse::between(&text, "var chapterPath = ", ";")?.replace("\"", "");
+	let list = json::parse(list)?.as_array()?;
 	let mut pages: Vec<Page> = Vec::new();
 
 	for (index, item) in list.enumerate() {
@@ -325,3 +293,20 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 
 	Ok(pages)
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let segments = parse::path_segments(&url);
+	let (id, chapter_id) = match segments.as_slice() {
+		[kind, id] if kind == "manhua" => (id.clone(), None),
+		[kind, id, chapter_id] if kind == "manhua" => {
+			(id.clone(), Some(parse::last_segment(chapter_id)))
+		}
+		_ => return Err(parse_error()),
+	};
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id)?),
+		chapter: chapter_id.map(link::chapter),
+	})
+}
@@ -5,17 +5,21 @@ use aidoku::{
 	error::Result,
 	helpers::uri::encode_uri,
 	prelude::*,
-	std::{
-		net::{HttpMethod, Request},
-		String, Vec,
-	},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
-	MangaViewer, Page,
+	std::{String, Vec},
+	Chapter, DeepLink, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult,
+	MangaStatus, MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	convert, entity,
+	error::{markup_changed, parse_error},
+	link, parse, Client,
+};
 
 const WWW_URL: &str = "https://hanime1.me";
 
+const CLIENT: Client = Client::new();
+
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	let mut query = String::new();
@@ -35,7 +39,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 		encode_uri(query),
 		page
 	);
-	let html = Request::new(url, HttpMethod::Get).html()?;
+	let html = CLIENT.get(url).html()?;
 	let has_more = true;
 	let mut mangas: Vec<Manga> = Vec::new();
 
@@ -44,14 +48,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = parse::last_segment(item.attr("href").read());
 		let cover = item.select("img").attr("data-srcset").read();
 		let title = item.select("div>.comic-rows-videos-title").text().read();
 		mangas.push(Manga {
@@ -63,7 +60,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -89,7 +86,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 		"{}/comics/search?sort={}&query=&page={}",
 		WWW_URL, sort, page
 	);
-	let html = Request::new(url, HttpMethod::Get).html()?;
+	let html = CLIENT.get(url).html()?;
 	let has_more = true;
 	let mut mangas: Vec<Manga> = Vec::new();
 
@@ -98,14 +95,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = parse::last_segment(item.attr("href").read());
 		let cover = item.select("img").attr("data-srcset").read();
 		let title = item.select("div>.comic-rows-videos-title").text().read();
 		mangas.push(Manga {
@@ -117,7 +107,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -125,17 +115,12 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
 	let url = format!("{}/comic/{}", WWW_URL, id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let cover = html
 		.select("meta[property='og:image']")
 		.attr("content")
 		.read();
-	let title = html
-		.select("h3[class^=title]>span")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read().trim().to_string())
-		.collect::<Vec<String>>()
-		.join(" ");
+	let title = parse::texts(&html, "h3[class^=title]>span").join(" ");
 	let author = html.select("a[href*=artists]>div[style]").text().read();
 	let artist = String::new();
 	let description = html
@@ -144,16 +129,12 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		.read()
 		.trim()
 		.to_string();
-	let categories = html
-		.select("a[href*=tags]>div[style]")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read().trim().to_string())
-		.collect::<Vec<String>>();
+	let categories = parse::texts(&html, "a[href*=tags]>div[style]");
 	let status = MangaStatus::Unknown;
 	let nsfw = MangaContentRating::Nsfw;
 	let viewer = MangaViewer::Rtl;
 
-	Ok(Manga {
+	Ok(convert::manga(Manga {
 		id,
 		cover,
 		title,
@@ -165,7 +146,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		status,
 		nsfw,
 		viewer,
-	})
+	}))
 }
 
 #[get_chapter_list]
@@ -182,13 +163,13 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 		..Default::default()
 	});
 
-	Ok(chapters)
+	Ok(convert::chapters(chapters))
 }
 
 #[get_page_list]
 fn get_page_list(manga_id: String, _: String) -> Result<Vec<Page>> {
 	let url = format!("{}/comic/{}", WWW_URL, manga_id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let mut pages: Vec<Page> = Vec::new();
 
 	for (index, item) in html
@@ -213,5 +194,25 @@ fn get_page_list(manga_id: String, _: String) -> Result<Vec<Page>> {
 		})
 	}
 
+	if pages.is_empty() {
+		return Err(markup_changed(".comics-panel-margin>a>img"));
+	}
+
 	Ok(pages)
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	// Each comic is a single chapter sharing the comic's ID.
+	let segments = parse::path_segments(&url);
+	let (id, chapter_id) = match segments.as_slice() {
+		[kind, id] if kind == "comic" => (id.clone(), None),
+		[kind, id, _] if kind == "comic" => (id.clone(), Some(id.clone())),
+		_ => return Err(parse_error()),
+	};
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id)?),
+		chapter: chapter_id.map(link::chapter),
+	})
+}
@@ -4,19 +4,18 @@ extern crate alloc;
 use aidoku::{
 	error::Result,
 	prelude::*,
-	std::{
-		net::{HttpMethod, Request},
-		ObjectRef, String, Vec,
-	},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
-	MangaViewer, Page,
+	std::{net::Request, ObjectRef, String, Vec},
+	Chapter, DeepLink, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult,
+	MangaStatus, MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{chapter, convert, entity, error::parse_error, link, parse, Client, UA};
 
 const WWW_URL: &str = "https://m.happymh.com";
-const UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36";
 
-const FILTER_GENRE: [&str; 132] = [
+const CLIENT: Client = Client::new().user_agent(UA).origin(WWW_URL).clearance();
+
+const FILTER_GENRE: [&str; 130] = [
 	"",
 	"rexue",
 	"gedou",
@@ -112,7 +111,6 @@ const FILTER_GENRE: [&str; 132] = [
 	"jijia",
 	"gaotian",
 	"jiangshi",
-	"zhiyu",
 	"dianjing",
 	"shenmo",
 	"yineng",
@@ -134,7 +132,6 @@ const FILTER_GENRE: [&str; 132] = [
 	"ouhuang",
 	"shengcun",
 	"yishijie",
-	"qita",
 	"C99",
 	"jiecao",
 	"AA",
@@ -204,19 +201,19 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			"{}/apis/c/index?genre={}&area={}&audience={}&series_status={}&order={}&pn={}",
 			WWW_URL, genre, area, audience, status, order, page
 		);
-		Request::new(url, HttpMethod::Get).header("Referer", &format!("{}/latest", WWW_URL))
+		CLIENT
+			.get(url)
+			.header("Referer", format!("{}/latest", WWW_URL))
 	} else {
 		let url = format!("{}/v2.0/apis/manga/ssearch", WWW_URL);
 		let body = format!("searchkey={}&v=v2.13", query);
-		Request::new(url, HttpMethod::Post)
-			.header("Content-Type", "application/x-www-form-urlencoded")
-			.header("Referer", &format!("{}/sssearch", WWW_URL))
-			.body(body.as_bytes())
+		CLIENT
+			.post(url)
+			.header("Referer", format!("{}/sssearch", WWW_URL))
+			.form(body)
+			.idempotent()
 	};
-	let json = request
-		.header("User-Agent", &UA)
-		.header("Origin", &WWW_URL)
-		.json()?;
+	let json = request.json()?;
 	let data = json.as_object()?;
 	let data = data.get("data").as_object()?;
 	let list = data.get("items").as_array()?;
@@ -239,7 +236,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more: true,
 	})
 }
@@ -277,11 +274,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	}
 
 	let url = format!("{}/rank/{}", WWW_URL, name);
-	let html = Request::new(url.clone(), HttpMethod::Get)
-		.header("Referer", &url)
-		.header("User-Agent", &UA)
-		.header("Origin", &WWW_URL)
-		.html()?;
+	let html = CLIENT.get(url.clone()).header("Referer", &url).html()?;
 
 	let list = html.select(".manga-rank").array();
 	let mut mangas: Vec<Manga> = Vec::new();
@@ -291,16 +284,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select(".manga-rank-cover>a")
-			.attr("href")
-			.read()
-			.split("/")
-			.filter(|a| !a.is_empty())
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = parse::last_segment(item.select(".manga-rank-cover>a").attr("href").read());
 		let cover = item
 			.select(".manga-rank-cover>a>mip-img")
 			.attr("src")
@@ -315,7 +299,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more: false,
 	})
 }
@@ -323,31 +307,21 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
 	let url = format!("{}/manga/{}", WWW_URL, id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get)
-		.header("Referer", &format!("{}/latest", WWW_URL))
-		.header("User-Agent", &UA)
-		.header("Origin", &WWW_URL)
+	let html = CLIENT
+		.get(url.clone())
+		.header("Referer", format!("{}/latest", WWW_URL))
 		.html()?;
 	let cover = html.select(".mg-cover>mip-img").attr("src").read();
 	let title = html.select("h2.mg-title").text().read();
-	let author = html
-		.select(".mg-sub-title>a")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read())
-		.collect::<Vec<String>>()
-		.join(", ");
+	let author = parse::texts(&html, ".mg-sub-title>a").join(", ");
 	let artist = String::new();
 	let description = html.select("#showmore").text().read().trim().to_string();
-	let categories = html
-		.select(".mg-cate>a")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read())
-		.collect::<Vec<String>>();
+	let categories = parse::texts(&html, ".mg-cate>a");
 	let status = MangaStatus::Unknown;
 	let nsfw = MangaContentRating::Safe;
 	let viewer = MangaViewer::Scroll;
 
-	Ok(Manga {
+	Ok(convert::manga(Manga {
 		id,
 		cover,
 		title,
@@ -359,7 +333,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		status,
 		nsfw,
 		viewer,
-	})
+	}))
 }
 
 fn get_chapter_list_by_page(id: String, page: i32) -> Result<Vec<ObjectRef>> {
@@ -367,18 +341,17 @@ fn get_chapter_list_by_page(id: String, page: i32) -> Result<Vec<ObjectRef>> {
 		"{}/v2.0/apis/manga/chapterByPage?code={}&page={}&lang=cn&order=asc",
 		WWW_URL, id, page
 	);
-	let json = Request::new(url, HttpMethod::Get)
-		.header("Referer", &format!("{}/manga/{}", WWW_URL, id))
-		.header("User-Agent", &UA)
-		.header("Origin", &WWW_URL)
+	let json = CLIENT
+		.get(url)
+		.header("Referer", format!("{}/manga/{}", WWW_URL, id))
 		.json()?;
 	let data = json.as_object()?;
 	let data = data.get("data").as_object()?;
 	let is_end = data.get("isEnd").as_int()?;
 	let items = data.get("items").as_array()?;
 	let mut list = items
-		.map(|a| a.as_object().unwrap())
-		.collect::<Vec<ObjectRef>>();
+		.map(|a| a.as_object())
+		.collect::<Result<Vec<ObjectRef>>>()?;
 
 	if is_end == 1 {
 		return Ok(list);
@@ -395,46 +368,48 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	let list = get_chapter_list_by_page(id, 1)?;
 	let mut chapters: Vec<Chapter> = Vec::new();
 
-	for (index, item) in list.iter().enumerate() {
+	for item in list.iter() {
 		let item = match item.0.clone().as_object() {
 			Ok(item) => item,
 			Err(_) => continue,
 		};
 		let id = item.get("codes").as_string()?.read();
 		let title = item.get("chapterName").as_string()?.read();
-		let chapter = (index + 1) as f32;
+		let number = chapter::parse(&title);
 		let url = format!("{}/mangaread/{}", WWW_URL, id.clone());
 		chapters.push(Chapter {
 			id,
 			title,
-			chapter,
+			volume: number.volume(),
+			chapter: number.chapter(),
 			url,
 			..Default::default()
 		});
 	}
 	chapters.reverse();
 
-	Ok(chapters)
+	Ok(convert::chapters(chapters))
 }
 
-#[get_page_list]
-fn get_page_list(_: String, chapter_id: String) -> Result<Vec<Page>> {
+fn get_reading(chapter_id: String) -> Result<ObjectRef> {
 	let url = format!(
 		"{}/v2.0/apis/manga/reading?code={}&v=v3.1818134",
 		WWW_URL,
 		chapter_id.clone()
 	);
-	let json = Request::new(url, HttpMethod::Get)
-		.header(
-			"Referer",
-			&format!("{}/mangaread/{}", WWW_URL, chapter_id.clone()),
-		)
-		.header("User-Agent", &UA)
-		.header("Origin", &WWW_URL)
+	let json = CLIENT
+		.get(url)
+		.header("Referer", format!("{}/mangaread/{}", WWW_URL, chapter_id))
 		.header("X-Requested-With", "XMLHttpRequest")
 		.json()?;
 	let data = json.as_object()?;
-	let data = data.get("data").as_object()?;
+
+	data.get("data").as_object()
+}
+
+#[get_page_list]
+fn get_page_list(_: String, chapter_id: String) -> Result<Vec<Page>> {
+	let data = get_reading(chapter_id)?;
 	let list = data.get("scans").as_array()?;
 	let mut pages: Vec<Page> = Vec::new();
 
@@ -457,8 +432,24 @@ fn get_page_list(_: String, chapter_id: String) -> Result<Vec<Page>> {
 
 #[modify_image_request]
 fn modify_image_request(request: Request) {
-	request
-		.header("Referer", &WWW_URL)
-		.header("User-Agent", &UA)
-		.header("Origin", &WWW_URL);
+	CLIENT.referer(WWW_URL).apply(request);
+}
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let segments = parse::path_segments(&url);
+	let (id, chapter_id) = match segments.as_slice() {
+		[kind, id] if kind == "manga" => (id.clone(), None),
+		[kind, chapter_id] if kind == "mangaread" => {
+			let data = get_reading(chapter_id.clone())?;
+			let id = data.get("manga_code").as_string()?.read();
+			(id, Some(chapter_id.clone()))
+		}
+		_ => return Err(parse_error()),
+	};
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id)?),
+		chapter: chapter_id.map(link::chapter),
+	})
 }
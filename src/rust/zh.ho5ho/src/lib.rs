@@ -3,21 +3,20 @@ extern crate alloc;
 
 use aidoku::{
 	error::Result,
-	helpers::{substring::Substring, uri::encode_uri},
+	helpers::uri::encode_uri,
 	prelude::*,
-	std::{
-		json,
-		net::{HttpMethod, Request},
-		String, Vec,
-	},
-	Chapter, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	std::{json, net::Request, String, Vec},
+	Chapter, DeepLink, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{chapter, convert, date, entity, error::parse_error, link, parse, Client};
 
 const WWW_URL: &str = "https://www.ho5ho.com";
 const MANGA_URL: &str = "https://www.ho5ho.com/%E4%B8%AD%E5%AD%97h%E6%BC%AB";
 
+const CLIENT: Client = Client::new().referer(WWW_URL);
+
 const FILTER_CATEGORY: [&str; 31] = [
 	"",
 	"m女",
@@ -106,7 +105,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 		)
 	};
 
-	let html = Request::new(url, HttpMethod::Get).html()?;
+	let html = CLIENT.get(url).html()?;
 	let has_more = true;
 	let mut mangas: Vec<Manga> = Vec::new();
 
@@ -134,7 +133,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -142,31 +141,17 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
 	let url = format!("{}/{}/", MANGA_URL, id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let cover = html
 		.select(".summary_image>a>img")
 		.attr("data-src")
 		.read()
 		.replace("193x278", "175x238");
 	let title = html.select(".post-title>h1").text().read();
-	let author = html
-		.select(".author-content>a")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read())
-		.collect::<Vec<String>>()
-		.join(", ");
+	let author = parse::texts(&html, ".author-content>a").join(", ");
 	let artist = String::new();
-	let description = html
-		.select(".description-summary>div>p")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read())
-		.collect::<Vec<String>>()
-		.join("\n");
-	let categories = html
-		.select(".genres-content>a")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read())
-		.collect::<Vec<String>>();
+	let description = parse::texts(&html, ".description-summary>div>p").join("\n");
+	let categories = parse::texts(&html, ".genres-content>a");
 	let status = match html
 		.select(".post-status>div:nth-child(2)>.summary-content")
 		.text()
@@ -182,7 +167,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let nsfw = MangaContentRating::Nsfw;
 	let viewer = MangaViewer::Rtl;
 
-	Ok(Manga {
+	Ok(convert::manga(Manga {
 		id,
 		cover: encode_uri(cover),
 		title,
@@ -194,54 +179,56 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		status,
 		nsfw,
 		viewer,
-	})
+	}))
 }
 
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	let url = format!("{}/{}/", MANGA_URL, id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let html = html.select(".wp-manga-chapter>a");
 	let mut chapters: Vec<Chapter> = Vec::new();
 
-	for (index, item) in html.array().enumerate() {
+	for item in html.array() {
 		let item = match item.as_node() {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
 		let url = item.attr("href").read();
-		let id = url
-			.split("/")
-			.filter(|a| !a.is_empty())
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = parse::last_segment(&url);
 		let title = item.text().read();
-		let chapter = (index + 1) as f32;
+		let number = chapter::parse(&title);
+		// New chapters show a relative date in the badge's title instead.
+		let release = item.parent().select(".chapter-release-date");
+		let date_updated = match release.select("a").attr("title").read() {
+			badge if !badge.is_empty() => date::parse(badge),
+			_ => date::parse(release.text().read()),
+		};
 		chapters.push(Chapter {
 			id,
 			title,
-			chapter,
+			volume: number.volume(),
+			chapter: number.chapter(),
+			date_updated,
 			url,
 			..Default::default()
 		});
 	}
 	chapters.reverse();
 
-	Ok(chapters)
+	Ok(convert::chapters(chapters))
 }
 
 #[get_page_list]
 fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 	let url = format!("{}/{}/{}/", MANGA_URL, manga_id, chapter_id);
-	let text = Request::new(url.clone(), HttpMethod::Get).string()?;
-	let list = text
-		.substring_after("var chapter_preloaded_images = ")
-		.unwrap()
-		.substring_before(", chapter_images_per_page =")
-		.unwrap();
-	let list = json::parse(list).unwrap().as_array()?;
+	let text = CLIENT.get(url.clone()).string()?;
+	let list = parse::between(
+		&text,
+		"var chapter_preloaded_images = ",
+		", chapter_images_per_page =",
+	)?;
+	let list = json::parse(list)?.as_array()?;
 	let mut pages: Vec<Page> = Vec::new();
 
 	for (index, item) in list.enumerate() {
@@ -259,5 +246,21 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 
 #[modify_image_request]
 fn modify_image_request(request: Request) {
-	request.header("Referer", WWW_URL);
+	CLIENT.apply(request);
+}
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	// Comics live under `/中字h漫/`, which may arrive percent-encoded.
+	let segments = parse::path_segments(&url);
+	let (id, chapter_id) = match segments.as_slice() {
+		[_, id] => (id.clone(), None),
+		[_, id, chapter_id] => (id.clone(), Some(chapter_id.clone())),
+		_ => return Err(parse_error()),
+	};
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id)?),
+		chapter: chapter_id.map(link::chapter),
+	})
 }
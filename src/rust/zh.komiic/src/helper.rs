@@ -1,16 +1,19 @@
 use aidoku::{
-	helpers::substring::Substring,
+	error::Result,
 	prelude::*,
 	std::{
-		net::{HttpMethod, Request},
-		ObjectRef, String,
+		defaults::{defaults_get, defaults_set},
+		ObjectRef, String, StringRef,
 	},
 };
 use alloc::string::ToString;
+use zh_common::{error::Error, parse, Client, OptionExt, RequestBuilder};
 
 const WWW_URL: &str = "https://komiic.com";
 const API_URL: &str = "https://komiic.com/api/query";
 
+const CLIENT: Client = Client::new();
+
 pub fn gen_manga_url(id: String) -> String {
 	format!("{}/comic/{}", WWW_URL, id)
 }
@@ -30,29 +33,100 @@ pub fn gen_page_url(manga_id: String, chapter_id: String, page_id: String) -> St
 }
 
 pub fn gen_referer(image_url: String) -> String {
-	if image_url.starts_with(WWW_URL) {
-		let query = image_url.substring_after("?").unwrap();
-		let manga_id = query
-			.substring_after("mangaId=")
-			.unwrap()
-			.substring_before("&")
-			.unwrap()
-			.to_string();
-		let chapter_id = query.substring_after("chapterId=").unwrap().to_string();
-		gen_chapter_url(manga_id, chapter_id)
-	} else {
-		WWW_URL.to_string()
+	let manga_id = parse::query_param(&image_url, "mangaId");
+	let chapter_id = parse::query_param(&image_url, "chapterId");
+
+	match (manga_id, chapter_id) {
+		(Some(manga_id), Some(chapter_id)) if image_url.starts_with(WWW_URL) => {
+			gen_chapter_url(manga_id, chapter_id)
+		}
+		_ => WWW_URL.to_string(),
 	}
 }
 
-pub fn get_json(body: String) -> ObjectRef {
-	Request::new(API_URL, HttpMethod::Post)
-		.body(body.as_bytes())
-		.header("Content-Type", "application/json")
-		.json()
-		.unwrap()
+fn get_default(key: &str) -> String {
+	defaults_get(key)
+		.and_then(|a| a.as_string())
+		.map(|a| a.read())
+		.unwrap_or_default()
+}
+
+fn has_account() -> bool {
+	!get_default("username").is_empty() && !get_default("password").is_empty()
+}
+
+/// Signs in with the saved account and keeps the token for later requests.
+pub fn login() -> Result<String> {
+	if !has_account() {
+		return Err(Error::LoginRequired.into());
+	}
+
+	let body = format!(
+		r#"{{
+      "operationName": "login",
+      "query": "mutation login($email: String!, $password: String!) {{\n  login(email: $email, password: $password)\n}}\n",
+      "variables": {{
+        "email": {},
+        "password": {}
+      }}
+    }}"#,
+		parse::json_string(get_default("username")),
+		parse::json_string(get_default("password")),
+	);
+	// Signing in is not retried, so a rejected password is not sent again.
+	let json = CLIENT.post(API_URL).json_body(body).json_object()?;
+	let token = json
+		.get("data")
 		.as_object()
-		.unwrap()
+		.and_then(|a| a.get("login").as_string())
+		.map(|a| a.read())
+		.unwrap_or_default();
+
+	if token.is_empty() {
+		return Err(Error::LoginRequired.into());
+	}
+
+	defaults_set("token", StringRef::from(token.as_str()).0);
+
+	Ok(token)
+}
+
+/// The saved token, for requests the source cannot sign in from, such as
+/// images.
+pub fn token() -> Option<String> {
+	Some(get_default("token")).filter(|a| !a.is_empty())
+}
+
+fn authorize(request: RequestBuilder, token: &Option<String>) -> RequestBuilder {
+	match token {
+		Some(token) => request.header("Authorization", format!("Bearer {}", token)),
+		None => request,
+	}
+}
+
+/// Runs a GraphQL query and returns its `data`, which is missing when the
+/// query no longer matches the API.
+///
+/// Queries are sent signed in once an account is set, which lifts the daily
+/// image quota. A rejected token is renewed by signing in again once, or
+/// dropped for an anonymous query when no account is set.
+pub fn get_data(body: String) -> Result<ObjectRef> {
+	let token = match token() {
+		None if has_account() => Some(login()?),
+		token => token,
+	};
+	let request = CLIENT.post(API_URL).json_body(body).idempotent();
+
+	let json = match authorize(request.clone(), &token).json_object_if_authorized()? {
+		Some(json) => json,
+		None if token.is_none() => return Err(Error::LoginRequired.into()),
+		None if has_account() => authorize(request, &Some(login()?)).json_object()?,
+		None => {
+			defaults_set("token", StringRef::from("").0);
+			request.json_object()?
+		}
+	};
+	json.get("data").as_object().ok().or_markup_changed("data")
 }
 
 pub fn gen_category_body_string(
@@ -168,6 +242,16 @@ pub fn gen_chapter_body_string(id: String) -> String {
 	)
 }
 
+pub fn gen_image_limit_body_string() -> String {
+	String::from(
+		r#"{
+      "operationName": "reachedImageLimit",
+      "query": "query reachedImageLimit {\n  reachedImageLimit\n}\n",
+      "variables": {}
+    }"#,
+	)
+}
+
 pub fn gen_images_body_string(id: String) -> String {
 	format!(
 		r#"{{
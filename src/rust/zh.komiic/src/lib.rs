@@ -5,9 +5,14 @@ use aidoku::{
 	error::Result,
 	prelude::*,
 	std::{net::Request, String, Vec},
-	Chapter, Filter, FilterType, Listing, Manga, MangaPageResult, Page,
+	Chapter, DeepLink, Filter, FilterType, Listing, Manga, MangaPageResult, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	convert, entity,
+	error::{parse_error, Error},
+	link, parse,
+};
 
 mod helper;
 mod parser;
@@ -62,21 +67,20 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 		helper::gen_search_body_string(query.clone())
 	};
 
-	let json = helper::get_json(body.clone());
-	let data = json.get("data").as_object()?;
+	let data = helper::get_data(body.clone())?;
 	let mangas;
 
 	if query.is_empty() {
 		let list = data.get("comicByCategories").as_array()?;
-		mangas = parser::parse_manga_list(list);
+		mangas = parser::parse_manga_list(list)?;
 	} else {
 		let data = data.get("searchComicsAndAuthors").as_object()?;
 		let list = data.get("comics").as_array()?;
-		mangas = parser::parse_manga_list(list);
+		mangas = parser::parse_manga_list(list)?;
 	};
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more: query.is_empty(),
 	})
 }
@@ -105,20 +109,19 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 		helper::gen_hot_body_string(order_by, page)
 	};
 
-	let json = helper::get_json(body);
-	let data = json.get("data").as_object()?;
+	let data = helper::get_data(body)?;
 	let mangas;
 
 	if is_recent_update {
 		let list = data.get("recentUpdate").as_array()?;
-		mangas = parser::parse_manga_list(list);
+		mangas = parser::parse_manga_list(list)?;
 	} else {
 		let list = data.get("hotComics").as_array()?;
-		mangas = parser::parse_manga_list(list);
+		mangas = parser::parse_manga_list(list)?;
 	};
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more: true,
 	})
 }
@@ -126,35 +129,58 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
 	let body = helper::gen_id_body_string(id);
-	let json = helper::get_json(body);
-	let data = json.get("data").as_object()?;
+	let data = helper::get_data(body)?;
 	let data = data.get("comicById").as_object()?;
 
-	Ok(parser::parse_manga(data))
+	parser::parse_manga(data).map(convert::manga)
 }
 
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	let body = helper::gen_chapter_body_string(id.clone());
-	let json = helper::get_json(body);
-	let data = json.get("data").as_object()?;
+	let data = helper::get_data(body)?;
 	let list = data.get("chaptersByComicId").as_array()?;
 
-	Ok(parser::parse_chapter_list(id, list))
+	parser::parse_chapter_list(id, list).map(convert::chapters)
 }
 
 #[get_page_list]
 fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
+	// Past the quota images fail to load, so say why before the reader opens.
+	let data = helper::get_data(helper::gen_image_limit_body_string())?;
+	if data.get("reachedImageLimit").as_bool().unwrap_or(false) {
+		return Err(Error::ImageLimit.into());
+	}
+
 	let body = helper::gen_images_body_string(chapter_id.clone());
-	let json = helper::get_json(body);
-	let data = json.get("data").as_object()?;
+	let data = helper::get_data(body)?;
 	let list = data.get("imagesByChapterId").as_array()?;
 
-	Ok(parser::parse_page_list(manga_id, chapter_id, list))
+	parser::parse_page_list(manga_id, chapter_id, list)
 }
 
 #[modify_image_request]
 fn modify_image_request(request: Request) {
 	let url = request.url().read();
-	request.header("Referer", &helper::gen_referer(url));
+	let request = request.header("Referer", &helper::gen_referer(url));
+	if let Some(token) = helper::token() {
+		request.header("Authorization", &format!("Bearer {}", token));
+	}
+}
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let segments = parse::path_segments(&url);
+	let (id, chapter_id) = match segments.as_slice() {
+		[kind, id] if kind == "comic" => (id.clone(), None),
+		[kind, id, chapter, chapter_id, ..] if kind == "comic" && chapter == "chapter" => {
+			(id.clone(), Some(chapter_id.clone()))
+		}
+		_ => return Err(parse_error()),
+	};
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id)?),
+		chapter: chapter_id.map(link::chapter),
+	})
 }
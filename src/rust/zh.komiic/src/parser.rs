@@ -1,59 +1,34 @@
 use crate::helper;
 use aidoku::{
+	error::Result,
 	std::{ArrayRef, ObjectRef, String, Vec},
 	Chapter, Manga, MangaContentRating, MangaStatus, MangaViewer, Page,
 };
+use zh_common::{chapter, date, parse};
 
-pub fn parse_manga_list(manga_list: ArrayRef) -> Vec<Manga> {
+pub fn parse_manga_list(manga_list: ArrayRef) -> Result<Vec<Manga>> {
 	manga_list
-		.map(|manga| parse_manga(manga.as_object().unwrap()))
-		.collect::<Vec<Manga>>()
+		.map(|manga| parse_manga(manga.as_object()?))
+		.collect::<Result<Vec<Manga>>>()
 }
 
-pub fn parse_manga(manga: ObjectRef) -> Manga {
-	let id = manga.get("id").as_string().unwrap().read();
-	let cover = manga.get("imageUrl").as_string().unwrap().read();
-	let title = manga.get("title").as_string().unwrap().read();
-	let author = manga
-		.get("authors")
-		.as_array()
-		.unwrap()
-		.map(|author| {
-			author
-				.as_object()
-				.unwrap()
-				.get("name")
-				.as_string()
-				.unwrap()
-				.read()
-		})
-		.collect::<Vec<String>>()
-		.join(", ");
+pub fn parse_manga(manga: ObjectRef) -> Result<Manga> {
+	let id = manga.get("id").as_string()?.read();
+	let cover = manga.get("imageUrl").as_string()?.read();
+	let title = manga.get("title").as_string()?.read();
+	let author = parse::object_strings(manga.get("authors").as_array()?, "name").join(", ");
 	let artist = String::new();
 	let description = String::new();
 	let url = helper::gen_manga_url(id.clone());
-	let categories = manga
-		.get("categories")
-		.as_array()
-		.unwrap()
-		.map(|category| {
-			category
-				.as_object()
-				.unwrap()
-				.get("name")
-				.as_string()
-				.unwrap()
-				.read()
-		})
-		.collect::<Vec<String>>();
-	let status = match manga.get("status").as_string().unwrap().read().as_str() {
+	let categories = parse::object_strings(manga.get("categories").as_array()?, "name");
+	let status = match manga.get("status").as_string()?.read().as_str() {
 		"ONGOING" => MangaStatus::Ongoing,
 		"END" => MangaStatus::Completed,
 		_ => MangaStatus::Unknown,
 	};
 	let nsfw = MangaContentRating::Safe;
 	let viewer = MangaViewer::Rtl;
-	Manga {
+	Ok(Manga {
 		id,
 		cover,
 		title,
@@ -65,38 +40,50 @@ pub fn parse_manga(manga: ObjectRef) -> Manga {
 		status,
 		nsfw,
 		viewer,
-	}
+	})
 }
 
-pub fn parse_chapter_list(manga_id: String, chapter_list: ArrayRef) -> Vec<Chapter> {
+pub fn parse_chapter_list(manga_id: String, chapter_list: ArrayRef) -> Result<Vec<Chapter>> {
 	let mut chapters: Vec<Chapter> = Vec::new();
 
-	for (index, item) in chapter_list.enumerate() {
-		let chapter = item.as_object().unwrap();
-		let id = chapter.get("id").as_string().unwrap().read();
-		let title = chapter.get("serial").as_string().unwrap().read();
-		let chapter = (index + 1) as f32;
+	for item in chapter_list {
+		let item = item.as_object()?;
+		let id = item.get("id").as_string()?.read();
+		let title = item.get("serial").as_string()?.read();
+		let number = chapter::parse(&title);
+		// Serials of `book` entries count volumes, not chapters.
+		let (volume, chapter) = match item.get("type").as_string()?.read().as_str() {
+			"book" => (number.chapter.unwrap_or(-1.0), -1.0),
+			_ => (number.volume(), number.chapter()),
+		};
+		let date_updated = date::parse(item.get("dateCreated").as_string()?.read());
 		let url = helper::gen_chapter_url(manga_id.clone(), id.clone());
 		chapters.push(Chapter {
 			id,
 			title,
+			volume,
 			chapter,
+			date_updated,
 			url,
 			..Default::default()
 		})
 	}
 
 	chapters.reverse();
-	chapters
+	Ok(chapters)
 }
 
-pub fn parse_page_list(manga_id: String, chapter_id: String, page_list: ArrayRef) -> Vec<Page> {
+pub fn parse_page_list(
+	manga_id: String,
+	chapter_id: String,
+	page_list: ArrayRef,
+) -> Result<Vec<Page>> {
 	let mut pages: Vec<Page> = Vec::new();
 
 	for (index, item) in page_list.enumerate() {
-		let page = item.as_object().unwrap();
+		let page = item.as_object()?;
 		let index = index as i32;
-		let id = page.get("kid").as_string().unwrap().read();
+		let id = page.get("kid").as_string()?.read();
 		let url = helper::gen_page_url(manga_id.clone(), chapter_id.clone(), id);
 		pages.push(Page {
 			index,
@@ -105,5 +92,5 @@ pub fn parse_page_list(manga_id: String, chapter_id: String, page_list: ArrayRef
 		})
 	}
 
-	pages
+	Ok(pages)
 }
@@ -1,16 +1,17 @@
 use aidoku::{
-	error::{AidokuError, AidokuErrorKind},
+	error::AidokuError,
 	helpers::{substring::Substring, uri::encode_uri},
 	prelude::*,
 	std::{
 		current_date,
 		defaults::{defaults_get, defaults_set},
-		net::{HttpMethod, Request},
+		net::HttpMethod,
 		String, ValueRef,
 	},
 };
 use alloc::string::ToString;
 use md5::compute;
+use zh_common::{error::Error, parse, Client, RequestBuilder};
 
 use crate::crypto;
 
@@ -20,6 +21,17 @@ const API_KEY: &str = "C69BAF41DA5ABD1FFEDC6D2FEA56B";
 const WWW_URL: &str = "https://manhuabika.com";
 const API_URL: &str = "https://picaapi.picacomic.com";
 
+const CLIENT: Client = Client::new().user_agent("okhttp/3.8.1").headers(&[
+	("api-key", API_KEY),
+	("app-build-version", "45"),
+	("app-channel", "1"),
+	("app-platform", "android"),
+	("app-uuid", "defaultUuid"),
+	("app-version", "2.2.1.3.3.4"),
+	("Accept", "application/vnd.picacomic.com.v1+json"),
+	("Content-Type", "application/json; charset=UTF-8"),
+]);
+
 pub fn gen_time() -> String {
 	(current_date() as i64).to_string()
 }
@@ -29,47 +41,52 @@ pub fn gen_nonce() -> String {
 }
 
 pub fn gen_signature(url: &str, time: &str, nonce: &str, method: &str) -> String {
-	let url = url.substring_after(&format!("{}/", API_URL)).unwrap();
+	let url = url.substring_after(format!("{}/", API_URL)).unwrap_or(url);
 	let text = format!("{}{}{}{}{}", url, time, nonce, method, API_KEY).to_ascii_lowercase();
 	crypto::encrypt(text.as_bytes(), KEY)
 }
 
-pub fn gen_request(url: String, method: HttpMethod) -> Request {
+/// The `image_quality` setting, which the API takes as is.
+fn image_quality() -> String {
+	defaults_get("image_quality")
+		.and_then(|a| a.as_string())
+		.map(|a| a.read())
+		.unwrap_or_else(|_| String::from("original"))
+}
+
+/// The `favourite_sort` setting, as the API's sort value.
+pub fn favourite_sort() -> String {
+	defaults_get("favourite_sort")
+		.and_then(|a| a.as_string())
+		.map(|a| a.read())
+		.unwrap_or_else(|_| String::from("dd"))
+}
+
+pub fn gen_request(url: String, method: HttpMethod) -> Result<RequestBuilder, AidokuError> {
 	let time = gen_time();
 	let nonce = gen_nonce();
 	let signature = gen_signature(&url, &time, &nonce, &format!("{:?}", method));
-	let token = defaults_get("token").unwrap().as_string().unwrap().read();
+	let token = defaults_get("token")?.as_string()?.read();
 	let authorization = if !url.contains("sign-in") && token.is_empty() {
-		login().unwrap()
+		login()?
 	} else {
 		token
 	};
-	Request::new(url, method)
-		.header("api-key", API_KEY)
-		.header("app-build-version", "45")
-		.header("app-channel", "1")
-		.header("app-platform", "android")
-		.header("app-uuid", "defaultUuid")
-		.header("app-version", "2.2.1.3.3.4")
-		.header("image-quality", "original")
-		.header("time", &time)
-		.header("nonce", &nonce)
-		.header("signature", &signature)
-		.header("Accept", "application/vnd.picacomic.com.v1+json")
-		.header("Authorization", &authorization)
-		.header("Content-Type", "application/json; charset=UTF-8")
-		.header("User-Agent", "okhttp/3.8.1")
+	Ok(CLIENT
+		.request(url, method)
+		.header("time", time)
+		.header("nonce", nonce)
+		.header("signature", signature)
+		.header("image-quality", image_quality())
+		.header("Authorization", authorization))
 }
 
 pub fn login() -> Result<String, AidokuError> {
-	let request = gen_request(gen_login_url(), HttpMethod::Post).header("Authorization", "");
 	let username = defaults_get("username")?.as_string()?.read();
 	let password = defaults_get("password")?.as_string()?.read();
 
 	if username.is_empty() || password.is_empty() {
-		return Err(AidokuError {
-			reason: AidokuErrorKind::DefaultNotFound,
-		});
+		return Err(Error::LoginRequired.into());
 	}
 
 	let body = format!(
@@ -80,14 +97,13 @@ pub fn login() -> Result<String, AidokuError> {
 		username, password
 	);
 
-	let request = request.body(body.as_bytes());
-
-	request.send();
+	let request = gen_request(gen_login_url(), HttpMethod::Post)?
+		.header("Authorization", "")
+		.body(body.as_bytes())
+		.send();
 
 	if request.status_code() != 200 {
-		return Err(AidokuError {
-			reason: AidokuErrorKind::DefaultNotFound,
-		});
+		return Err(Error::LoginRequired.into());
 	}
 
 	let json = request.json()?;
@@ -100,42 +116,65 @@ pub fn login() -> Result<String, AidokuError> {
 	Ok(token.as_string()?.read())
 }
 
-pub fn search(keyword: String, page: i32) -> Result<ValueRef, AidokuError> {
-	let url = gen_search_url(page);
+/// Sends the request, signing in again once if the saved token was rejected.
+fn send(request: RequestBuilder) -> Result<ValueRef, AidokuError> {
+	let response = request.send();
+
+	if response.status_code() == 401 {
+		request.header("Authorization", login()?).json()
+	} else {
+		response.json()
+	}
+}
+
+pub fn search(
+	keyword: String,
+	category: String,
+	sort: String,
+	page: i32,
+) -> Result<ValueRef, AidokuError> {
+	let url = gen_search_url(sort.clone(), page);
+	let categories = if category.is_empty() {
+		String::new()
+	} else {
+		parse::json_string(category)
+	};
 	let body = format!(
 		r#"{{
-			"keyword": "{}",
-			"sort": "dd"
+			"keyword": {},
+			"categories": [{}],
+			"sort": "{}"
 		}}"#,
-		keyword,
+		parse::json_string(keyword),
+		categories,
+		sort,
 	);
-	let request = gen_request(url, HttpMethod::Post).body(body.as_bytes());
-
-	request.send();
 
-	if request.status_code() == 401 {
-		request.header("Authorization", &login()?).json()
-	} else {
-		request.json()
-	}
+	send(
+		gen_request(url, HttpMethod::Post)?
+			.body(body.as_bytes())
+			.idempotent(),
+	)
 }
 
 pub fn gen_login_url() -> String {
 	format!("{}/{}", API_URL, "auth/sign-in")
 }
 
-pub fn gen_explore_url(category: String, sort: String, page: i32) -> String {
-	if category.is_empty() {
-		format!("{}/comics?page={}&s={}", API_URL, page, sort,)
-	} else {
-		format!(
-			"{}/comics?page={}&c={}&s={}",
-			API_URL,
-			page,
-			encode_uri(category),
-			sort,
-		)
-	}
+/// The comic list narrowed by `filters`, pairs of a query key (`c` for
+/// category, `t` for tag, `a` for author, `ca` for uploader) and a value.
+/// Empty values are left out.
+pub fn gen_explore_url(filters: &[(&str, String)], sort: String, page: i32) -> String {
+	let query = filters
+		.iter()
+		.filter(|(_, value)| !value.is_empty())
+		.map(|(key, value)| format!("&{}={}", key, encode_uri(value)))
+		.collect::<String>();
+	format!("{}/comics?page={}{}&s={}", API_URL, page, query, sort)
+}
+
+pub fn gen_favourite_url(sort: String, page: i32) -> String {
+	format!("{}/users/favourite?s={}&page={}", API_URL, sort, page)
 }
 
 pub fn gen_rank_url(time: String) -> String {
@@ -146,8 +185,11 @@ pub fn gen_random_url() -> String {
 	format!("{}/comics/random", API_URL)
 }
 
-pub fn gen_search_url(page: i32) -> String {
-	format!("{}/comics/advanced-search?page={}&s=dd", API_URL, page)
+pub fn gen_search_url(sort: String, page: i32) -> String {
+	format!(
+		"{}/comics/advanced-search?page={}&s={}",
+		API_URL, page, sort
+	)
 }
 
 pub fn gen_manga_url(id: String) -> String {
@@ -177,13 +219,5 @@ pub fn gen_page_list_url(manga_id: String, chapter_id: String, page: i32) -> Str
 }
 
 pub fn get_json(url: String) -> Result<ValueRef, AidokuError> {
-	let request = gen_request(url, HttpMethod::Get);
-
-	request.send();
-
-	if request.status_code() == 401 {
-		request.header("Authorization", &login()?).json()
-	} else {
-		request.json()
-	}
+	send(gen_request(url, HttpMethod::Get)?)
 }
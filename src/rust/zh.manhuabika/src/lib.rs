@@ -5,9 +5,10 @@ use aidoku::{
 	error::Result,
 	prelude::*,
 	std::{String, Vec},
-	Chapter, Filter, FilterType, Listing, Manga, MangaPageResult, Page,
+	Chapter, DeepLink, Filter, FilterType, Listing, Manga, MangaPageResult, Page,
 };
 use alloc::string::ToString;
+use zh_common::{convert, entity, link, parse, OptionExt};
 
 mod crypto;
 mod helper;
@@ -58,6 +59,9 @@ const FILTER_SORT: [&str; 4] = ["dd", "da", "ld", "vd"];
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	let mut query = String::new();
 	let mut category = String::new();
+	let mut tag = String::new();
+	let mut author = String::new();
+	let mut uploader = String::new();
 	let mut sort = String::from("dd");
 
 	for filter in filters {
@@ -65,6 +69,17 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			FilterType::Title => {
 				query = filter.value.as_string()?.read();
 			}
+			FilterType::Author => {
+				author = filter.value.as_string()?.read();
+			}
+			FilterType::Text => {
+				let value = filter.value.as_string()?.read();
+				match filter.name.as_str() {
+					"标签" => tag = value,
+					"上传者" => uploader = value,
+					_ => continue,
+				}
+			}
 			FilterType::Select => {
 				let index = filter.value.as_int()? as usize;
 				match filter.name.as_str() {
@@ -86,20 +101,33 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 		}
 	}
 
-	let json = if query.is_empty() {
-		helper::get_json(helper::gen_explore_url(category, sort, page))?
+	// Tags, authors and uploaders are listed by endpoints of their own, which
+	// take no keyword, so a keyword given with them narrows the titles.
+	let explore = !(tag.is_empty() && author.is_empty() && uploader.is_empty());
+	let json = if query.is_empty() || explore {
+		let filters = [("c", category), ("t", tag), ("a", author), ("ca", uploader)];
+		helper::get_json(helper::gen_explore_url(&filters, sort, page))?
 	} else {
-		helper::search(query, page)?
+		helper::search(query.clone(), category, sort, page)?
 	};
 
 	let data = json.as_object()?;
 	let data = data.get("data").as_object()?;
 	let data = data.get("comics").as_object()?;
 	let list = data.get("docs").as_array()?;
+	let mut mangas = entity::titles(parser::parse_manga_list(list)?);
+	if explore && !query.is_empty() {
+		let keyword = convert::to_simplified(&query).to_lowercase();
+		mangas.retain(|manga| {
+			convert::to_simplified(&manga.title)
+				.to_lowercase()
+				.contains(&keyword)
+		});
+	}
 
 	Ok(MangaPageResult {
-		manga: parser::parse_manga_list(list),
-		has_more: parser::has_more(data),
+		manga: mangas,
+		has_more: parser::has_more(data)?,
 	})
 }
 
@@ -107,8 +135,9 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	let mut rank_time = String::new();
 	let mut is_random = false;
+	let mut is_favourite = false;
 	let mut category = String::new();
-	let sort = String::from("dd");
+	let mut sort = String::from("dd");
 
 	match listing.name.as_str() {
 		"日榜" => rank_time = String::from("H24"),
@@ -119,6 +148,10 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 		"那年今天" => category = String::from("那年今天"),
 		"大家都在看" => category = String::from("大家都在看"),
 		"官方都在看" => category = String::from("官方都在看"),
+		"我的收藏" => {
+			is_favourite = true;
+			sort = helper::favourite_sort();
+		}
 		_ => return get_manga_list(Vec::new(), page),
 	};
 
@@ -126,8 +159,10 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 		helper::gen_rank_url(rank_time.clone())
 	} else if is_random {
 		helper::gen_random_url()
+	} else if is_favourite {
+		helper::gen_favourite_url(sort, page)
 	} else if !category.is_empty() {
-		helper::gen_explore_url(category, sort, page)
+		helper::gen_explore_url(&[("c", category)], sort, page)
 	} else {
 		String::new()
 	};
@@ -141,17 +176,17 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 
 	if !rank_time.is_empty() || is_random {
 		let list = data.get("comics").as_array()?;
-		mangas = parser::parse_manga_list(list);
+		mangas = parser::parse_manga_list(list)?;
 		has_more = false;
 	} else {
 		let data = data.get("comics").as_object()?;
 		let list = data.get("docs").as_array()?;
-		mangas = parser::parse_manga_list(list);
-		has_more = parser::has_more(data);
+		mangas = parser::parse_manga_list(list)?;
+		has_more = parser::has_more(data)?;
 	};
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -163,7 +198,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let data = json.as_object()?;
 	let data = data.get("data").as_object()?;
 
-	Ok(parser::parse_manga(data))
+	parser::parse_manga(data).map(convert::manga)
 }
 
 #[get_chapter_list]
@@ -176,24 +211,24 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	let data = data.get("eps").as_object()?;
 	let list = data.get("docs").as_array()?;
 	let pages = data.get("pages").as_int()? as i32;
-	let mut chapters = parser::parse_chapter_list(id.clone(), list);
+	let mut chapters = parser::parse_chapter_list(id.clone(), list)?;
 
 	while page < pages {
 		page += 1;
-		let next_page_chapters = get_chapter_list_by_page(id.clone(), page);
+		let next_page_chapters = get_chapter_list_by_page(id.clone(), page)?;
 		chapters = [chapters, next_page_chapters].concat();
 	}
 
-	Ok(chapters)
+	Ok(convert::chapters(chapters))
 }
 
-fn get_chapter_list_by_page(id: String, page: i32) -> Vec<Chapter> {
+fn get_chapter_list_by_page(id: String, page: i32) -> Result<Vec<Chapter>> {
 	let url = helper::gen_chapter_list_url(id.clone(), page);
-	let json = helper::get_json(url).unwrap();
-	let data = json.as_object().unwrap();
-	let data = data.get("data").as_object().unwrap();
-	let data = data.get("eps").as_object().unwrap();
-	let list = data.get("docs").as_array().unwrap();
+	let json = helper::get_json(url)?;
+	let data = json.as_object()?;
+	let data = data.get("data").as_object()?;
+	let data = data.get("eps").as_object()?;
+	let list = data.get("docs").as_array()?;
 
 	parser::parse_chapter_list(id, list)
 }
@@ -208,25 +243,37 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 	let data = data.get("pages").as_object()?;
 	let list = data.get("docs").as_array()?;
 	let pages = data.get("pages").as_int()? as i32;
-	let mut page_list = parser::parse_page_list(list, 0);
+	let mut page_list = parser::parse_page_list(list, 0)?;
 
 	while page < pages {
 		page += 1;
-		let next_page_page_list = get_page_list_by_page(manga_id.clone(), chapter_id.clone(), page);
+		let next_page_page_list =
+			get_page_list_by_page(manga_id.clone(), chapter_id.clone(), page)?;
 		page_list = [page_list, next_page_page_list].concat();
 	}
 
 	Ok(page_list)
 }
 
-fn get_page_list_by_page(manga_id: String, chapter_id: String, page: i32) -> Vec<Page> {
+fn get_page_list_by_page(manga_id: String, chapter_id: String, page: i32) -> Result<Vec<Page>> {
 	let url = helper::gen_page_list_url(manga_id.clone(), chapter_id.clone(), page);
-	let json = helper::get_json(url).unwrap();
-	let data = json.as_object().unwrap();
-	let data = data.get("data").as_object().unwrap();
-	let data = data.get("pages").as_object().unwrap();
-	let list = data.get("docs").as_array().unwrap();
-	let limit = data.get("limit").as_int().unwrap() as i32;
+	let json = helper::get_json(url)?;
+	let data = json.as_object()?;
+	let data = data.get("data").as_object()?;
+	let data = data.get("pages").as_object()?;
+	let list = data.get("docs").as_array()?;
+	let limit = data.get("limit").as_int()? as i32;
 
 	parser::parse_page_list(list, (page - 1) * limit)
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let id = parse::query_param(&url, "cid").or_parse_error()?;
+	let chapter_id = parse::query_param(&url, "chapter");
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id)?),
+		chapter: chapter_id.map(link::chapter),
+	})
+}
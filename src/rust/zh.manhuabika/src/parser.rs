@@ -1,39 +1,59 @@
 use aidoku::{
+	error::Result,
 	prelude::*,
-	std::{ArrayRef, ObjectRef, String, Vec},
+	std::{defaults::defaults_get, ArrayRef, ObjectRef, String, Vec},
 	Chapter, Manga, MangaContentRating, MangaStatus, MangaViewer, Page,
 };
 use alloc::string::ToString;
 
 use crate::helper;
+use zh_common::{chapter, date};
 
-pub fn has_more(data: ObjectRef) -> bool {
-	let page = data.get("page").as_int().unwrap();
-	let pages = data.get("pages").as_int().unwrap();
-	pages > page
+pub fn has_more(data: ObjectRef) -> Result<bool> {
+	let page = data.get("page").as_int()?;
+	let pages = data.get("pages").as_int()?;
+	Ok(pages > page)
 }
 
-pub fn parse_manga_list(manga_list: ArrayRef) -> Vec<Manga> {
-	manga_list
-		.map(|manga| parse_manga(manga.as_object().unwrap()))
-		.collect::<Vec<Manga>>()
+/// The categories chosen in the `blocked_categories` setting.
+fn blocked_categories() -> Vec<String> {
+	defaults_get("blocked_categories")
+		.and_then(|a| a.as_array())
+		.map(|a| {
+			a.filter_map(|category| category.as_string().ok())
+				.map(|category| category.read())
+				.collect()
+		})
+		.unwrap_or_default()
+}
+
+/// Parses a page of comics, leaving out those in a blocked category.
+pub fn parse_manga_list(manga_list: ArrayRef) -> Result<Vec<Manga>> {
+	let blocked = blocked_categories();
+	let manga = manga_list
+		.map(|manga| parse_manga(manga.as_object()?))
+		.collect::<Result<Vec<Manga>>>()?;
+
+	Ok(manga
+		.into_iter()
+		.filter(|manga| !manga.categories.iter().any(|a| blocked.contains(a)))
+		.collect())
 }
 
-pub fn parse_manga(manga: ObjectRef) -> Manga {
+pub fn parse_manga(manga: ObjectRef) -> Result<Manga> {
 	let manga = match manga.get("comic").as_object() {
 		Ok(value) => value,
 		Err(_) => manga,
 	};
-	let id = manga.get("_id").as_string().unwrap().read();
-	let thumb = manga.get("thumb").as_object().unwrap();
-	let host = thumb.get("fileServer").as_string().unwrap().read();
-	let path = thumb.get("path").as_string().unwrap().read();
+	let id = manga.get("_id").as_string()?.read();
+	let thumb = manga.get("thumb").as_object()?;
+	let host = thumb.get("fileServer").as_string()?.read();
+	let path = thumb.get("path").as_string()?.read();
 	let cover = format!("{}/static/{}", host, path);
-	let title = manga.get("title").as_string().unwrap().read();
+	let title = manga.get("title").as_string()?.read();
 	let author = manga
 		.get("author")
-		.as_string()
-		.unwrap()
+		.as_string()?
 		.read()
 		.split("&")
 		.map(|a| a.trim().to_string())
@@ -48,9 +68,9 @@ pub fn parse_manga(manga: ObjectRef) -> Manga {
 	let url = helper::gen_manga_url(id.clone());
 	let categories = manga
 		.get("categories")
-		.as_array()
-		.unwrap()
-		.map(|category| category.as_string().unwrap().read())
+		.as_array()?
+		.filter_map(|category| category.as_string().ok())
+		.map(|category| category.read())
 		.collect::<Vec<String>>();
 	let status = if manga.get("finished").as_bool().unwrap_or_default() {
 		MangaStatus::Completed
@@ -63,7 +83,7 @@ pub fn parse_manga(manga: ObjectRef) -> Manga {
 	} else {
 		MangaViewer::Rtl
 	};
-	Manga {
+	Ok(Manga {
 		id,
 		cover,
 		title,
@@ -75,40 +95,49 @@ pub fn parse_manga(manga: ObjectRef) -> Manga {
 		status,
 		nsfw,
 		viewer,
-	}
+	})
 }
 
-pub fn parse_chapter_list(manga_id: String, chapter_list: ArrayRef) -> Vec<Chapter> {
+pub fn parse_chapter_list(manga_id: String, chapter_list: ArrayRef) -> Result<Vec<Chapter>> {
 	let mut chapters: Vec<Chapter> = Vec::new();
 
 	for item in chapter_list {
-		let item = item.as_object().unwrap();
-		let order = item.get("order").as_int().unwrap();
+		let item = item.as_object()?;
+		let order = item.get("order").as_int()?;
 		let id = order.to_string();
-		let title = item.get("title").as_string().unwrap().read();
-		let chapter = order as f32;
+		let title = item.get("title").as_string()?.read();
+		let number = chapter::parse(&title);
+		let chapter = number.chapter_or(order as f32);
+		let date_updated = date::parse(
+			item.get("updated_at")
+				.as_string()
+				.map(|a| a.read())
+				.unwrap_or_default(),
+		);
 		let url = helper::gen_chapter_url(manga_id.clone(), id.clone());
 		chapters.push(Chapter {
 			id,
 			title,
+			volume: number.volume(),
 			chapter,
+			date_updated,
 			url,
 			..Default::default()
 		});
 	}
 
-	chapters
+	Ok(chapters)
 }
 
-pub fn parse_page_list(page_list: ArrayRef, offset: i32) -> Vec<Page> {
+pub fn parse_page_list(page_list: ArrayRef, offset: i32) -> Result<Vec<Page>> {
 	let mut pages: Vec<Page> = Vec::new();
 
 	for (index, item) in page_list.enumerate() {
-		let item = item.as_object().unwrap();
+		let item = item.as_object()?;
 		let index = index as i32 + offset;
-		let media = item.get("media").as_object().unwrap();
-		let host = media.get("fileServer").as_string().unwrap().read();
-		let path = media.get("path").as_string().unwrap().read();
+		let media = item.get("media").as_object()?;
+		let host = media.get("fileServer").as_string()?.read();
+		let path = media.get("path").as_string()?.read();
 		let url = format!("{}/static/{}", host, path);
 		pages.push(Page {
 			index,
@@ -117,5 +146,5 @@ pub fn parse_page_list(page_list: ArrayRef, offset: i32) -> Vec<Page> {
 		});
 	}
 
-	pages
+	Ok(pages)
 }
@@ -3,22 +3,21 @@ extern crate alloc;
 
 use aidoku::{
 	error::Result,
-	helpers::{substring::Substring, uri::encode_uri},
+	helpers::uri::encode_uri,
 	prelude::*,
-	std::{
-		json,
-		net::{HttpMethod, Request},
-		String, Vec,
-	},
-	Chapter, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	std::{json, String, Vec},
+	Chapter, DeepLink, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
 use base64::{engine::general_purpose, Engine};
+use zh_common::{chapter, convert, entity, error::parse_error, link, parse, Client, OptionExt};
 
 const WWW_URL: &str = "https://www.manhuadb.com";
 const STATIC_URL: &str = "https://i2.manhuadb.com/static";
 
+const CLIENT: Client = Client::new();
+
 const FILTER_REGION: [&str; 7] = ["", "4", "5", "6", "7", "8", "9"];
 const FILTER_AUDIENCE: [&str; 10] = ["", "3", "4", "5", "6", "7", "9", "10", "11", "12"];
 const FILTER_STATUS: [&str; 3] = ["", "1", "2"];
@@ -90,7 +89,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	} else {
 		"comicbook"
 	};
-	let html = Request::new(url, HttpMethod::Get).html()?;
+	let html = CLIENT.get(url).html()?;
 	let has_more = true;
 	let mut mangas: Vec<Manga> = Vec::new();
 
@@ -99,14 +98,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = element
-			.select("a")
-			.attr("href")
-			.read()
-			.split("/")
-			.last()
-			.unwrap()
-			.to_string();
+		let id = parse::last_segment(element.select("a").attr("href").read());
 		let cover = handle_cover(element.select("a>img").attr("data-original").read());
 		let title = element.select("div>h2>a").text().read();
 		mangas.push(Manga {
@@ -118,7 +110,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -126,7 +118,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
 	let url = format!("{}/manhua/{}", WWW_URL, id);
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let cover = handle_cover(html.select(".comic-cover>img").attr("src").read());
 	let title = html.select(".comic-title").text().read();
 	let author = html
@@ -164,7 +156,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	};
 	let viewer = MangaViewer::Rtl;
 
-	Ok(Manga {
+	Ok(convert::manga(Manga {
 		id,
 		cover,
 		title,
@@ -176,54 +168,47 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		status,
 		nsfw,
 		viewer,
-	})
+	}))
 }
 
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	let url = format!("{}/manhua/{}", WWW_URL, id);
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let mut chapters: Vec<Chapter> = Vec::new();
 
-	for (index, item) in html.select(".links-of-books>li>a").array().enumerate() {
+	for item in html.select(".links-of-books>li>a").array() {
 		let element = match item.as_node() {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let chapter_id = element
-			.attr("href")
-			.read()
-			.split("/")
-			.last()
-			.unwrap()
-			.replace(".html", "");
+		let chapter_id = parse::last_segment(element.attr("href").read());
 		let title = element.text().read();
-		let chapter = (index + 1) as f32;
+		let number = chapter::parse(&title);
 		let url = format!("{}/manhua/{}/{}.html", WWW_URL, id, chapter_id);
 		chapters.push(Chapter {
 			id: chapter_id,
 			title,
-			chapter,
+			volume: number.volume(),
+			chapter: number.chapter(),
 			url,
 			..Default::default()
 		});
 	}
 
 	chapters.reverse();
-	Ok(chapters)
+	Ok(convert::chapters(chapters))
 }
 
 #[get_page_list]
 fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 	let url = format!("{}/manhua/{}/{}.html", WWW_URL, manga_id, chapter_id);
-	let string = Request::new(url, HttpMethod::Get).string()?;
-	let string = string
-		.substring_after("var img_data = ")
-		.unwrap()
-		.substring_before(";")
-		.unwrap()
-		.replace("'", "");
-	let data = general_purpose::STANDARD.decode(string).unwrap();
+	let string = CLIENT.get(url).string()?;
+	let string = parse::between(&string, "var img_data = ", ";")?.replace("'", "");
+	let data = general_purpose::STANDARD
+		.decode(string)
+		.ok()
+		.or_parse_error()?;
 	let list = json::parse(data)?.as_array()?;
 	let sub_path = chapter_id.replace("_", "/");
 	let mut pages: Vec<Page> = Vec::new();
@@ -242,3 +227,20 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 
 	Ok(pages)
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let segments = parse::path_segments(&url);
+	let (id, chapter_id) = match segments.as_slice() {
+		[kind, id] if kind == "manhua" => (id.clone(), None),
+		[kind, id, chapter_id] if kind == "manhua" => {
+			(id.clone(), Some(parse::last_segment(chapter_id)))
+		}
+		_ => return Err(parse_error()),
+	};
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id)?),
+		chapter: chapter_id.map(link::chapter),
+	})
+}
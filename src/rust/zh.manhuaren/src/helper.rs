@@ -3,37 +3,265 @@ use aidoku::{
 	helpers::uri::{encode_uri, QueryParameters},
 	prelude::*,
 	std::{
-		defaults::defaults_get,
-		net::{HttpMethod, Request},
-		String, ValueRef, Vec,
+		current_date,
+		defaults::{defaults_get, defaults_set},
+		net::HttpMethod,
+		ObjectRef, String, StringRef, Vec,
 	},
 };
 use alloc::string::ToString;
 use md5::compute;
+use spin::Mutex;
+use zh_common::{Client, OptionExt, RequestBuilder};
 
 const WWW_URL: &str = "https://www.manhuaren.com";
 const API_URL: &str = "https://mangaapi.manhuaren.com";
 
+const CLIENT: Client = Client::new();
+pub const IMAGE_CLIENT: Client = Client::new()
+	.user_agent("okhttp/3.11.0")
+	.referer("http://www.dm5.com/dm5api/")
+	.headers(&[
+		("X-Yq-Yqci", r#"{"le": "zh"}"#),
+		("ClubReferer", "http://mangaapi.manhuaren.com/"),
+	]);
+
+/// Where the image host that last took over from a failing one is
+/// remembered.
+const HOST_KEY: &str = "last_image_host";
+/// How many pages are kept in [`LOADED`], enough for the chapters being read.
+const LOADED_MAX: usize = 200;
+
+/// The pages loaded this session, each with the index of the host it was
+/// last loaded from, so a page asked for again can be checked on that host.
+static LOADED: Mutex<Vec<(String, usize)>> = Mutex::new(Vec::new());
+
+/// The `errorCode` the API answers with, in the body of an otherwise
+/// successful response, once a token has expired.
+const TOKEN_EXPIRED: i64 = 401;
+
 const GSN_KEY: &str = "4e0a48e1c0b54041bce9c8f0e036124d";
 
 pub fn md5(text: String) -> String {
 	format!("{:x}", compute(text))
 }
 
-pub fn get_json(url: String) -> Result<ValueRef, AidokuError> {
-	let token = defaults_get("token")?.as_string()?.read();
+/// A call to the API, signed for the current user when it is sent, since
+/// the user may only be registered or replaced at that point.
+pub struct ApiRequest {
+	path: &'static str,
+	params: Vec<(String, String)>,
+}
+
+impl ApiRequest {
+	fn new(path: &'static str, params: Vec<(String, String)>) -> Self {
+		Self { path, params }
+	}
+
+	fn builder(&self) -> RequestBuilder {
+		let query = gen_query_string(self.params.clone(), HttpMethod::Get);
+		CLIENT
+			.get(format!("{}{}?{}", API_URL, self.path, query))
+			.header(
+				"Authorization",
+				format!("YINGQISTS2 {}", get_default("token")),
+			)
+	}
+}
+
+fn get_default(key: &str) -> String {
+	defaults_get(key)
+		.and_then(|a| a.as_string())
+		.map(|a| a.read())
+		.unwrap_or_default()
+}
+
+/// The ID this install registers under, made up once and kept, so that the
+/// API sees the same device each time it hands out a token.
+fn device_id() -> String {
+	let device_id = get_default("device_id");
+	if !device_id.is_empty() {
+		return device_id;
+	}
+
+	let device_id = md5(current_date().to_string()).to_ascii_uppercase();
+	defaults_set("device_id", StringRef::from(device_id.as_str()).0);
+	device_id
+}
+
+/// Whether the API turned a request down because its token has expired,
+/// which it reports in the body rather than with a status.
+fn token_expired(json: &ObjectRef) -> bool {
+	json.get("errorResponse")
+		.as_object()
+		.and_then(|a| a.get("errorCode").as_int())
+		.is_ok_and(|code| code == TOKEN_EXPIRED)
+}
+
+/// The token in a response that hands one out.
+fn encoded_token(json: &ObjectRef) -> Option<String> {
+	json.get("response")
+		.as_object()
+		.and_then(|a| a.get("tokenResult").as_object())
+		.and_then(|a| a.get("token").as_object())
+		.and_then(|a| a.get("encodedToken").as_string())
+		.map(|a| a.read())
+		.ok()
+		.filter(|a| !a.is_empty())
+}
+
+/// Registers this device as a guest, saving the user ID and token the API
+/// hands out.
+pub fn register() -> Result<(), AidokuError> {
+	let params = Vec::from([(String::from("deviceId"), device_id())]);
+	let json = CLIENT
+		.post(format!("{}/v1/user/createAnonyUser2", API_URL))
+		.form(gen_query_string(params, HttpMethod::Post))
+		.json_object()?;
+	let data = json.get("response").as_object()?;
+	let uid = match data.get("userId").as_string() {
+		Ok(uid) => uid.read(),
+		Err(_) => data.get("userId").as_int()?.to_string(),
+	};
+	let token = encoded_token(&json).or_markup_changed("encodedToken")?;
+
+	defaults_set("uid", StringRef::from(uid).0);
+	defaults_set("token", StringRef::from(token).0);
+
+	Ok(())
+}
+
+/// Exchanges the saved token for a fresh one for the same guest, returning
+/// whether the API agreed.
+fn refresh() -> bool {
+	let params = Vec::from([(String::from("token"), get_default("token"))]);
+	let json = CLIENT
+		.post(format!("{}/v1/user/refreshToken", API_URL))
+		.form(gen_query_string(params, HttpMethod::Post))
+		.header(
+			"Authorization",
+			format!("YINGQISTS2 {}", get_default("token")),
+		)
+		.json_object_if_authorized();
+	let token = match json {
+		Ok(Some(json)) if !token_expired(&json) => encoded_token(&json),
+		_ => None,
+	};
+
+	match token {
+		Some(token) => {
+			defaults_set("token", StringRef::from(token).0);
+			true
+		}
+		None => false,
+	}
+}
+
+/// Sends `request`, registering first when there is no token yet. An expired
+/// token is refreshed, or replaced by registering again when the API will not
+/// refresh it.
+pub fn get_json(request: ApiRequest) -> Result<ObjectRef, AidokuError> {
+	if get_default("token").is_empty() {
+		register()?;
+	}
+
+	if let Some(json) = request.builder().json_object_if_authorized()? {
+		if !token_expired(&json) {
+			return Ok(json);
+		}
+	}
+
+	if !refresh() {
+		register()?;
+	}
+	request.builder().json_object()
+}
+
+/// The `hide_paid` setting: leave chapters that must be bought out of the
+/// list.
+pub fn hide_paid() -> bool {
+	defaults_get("hide_paid")
+		.and_then(|a| a.as_bool())
+		.unwrap_or(false)
+}
+
+/// The image hosts in the order to try them: the one named in the
+/// `image_host` setting, then the one that last took over from a failing
+/// one, then the rest as the API listed them.
+pub fn order_hosts(mut hosts: Vec<String>) -> Vec<String> {
+	let preferred = get_default("image_host");
+	let preferred = preferred.trim();
+	let last = get_default(HOST_KEY);
+	hosts.sort_by_key(|host| {
+		if !preferred.is_empty() && host.contains(preferred) {
+			0
+		} else if *host == last {
+			1
+		} else {
+			2
+		}
+	});
+	hosts
+}
+
+/// A page on the first of `hosts`, carrying all of them after `#` so that
+/// [`rotate_host`] can move it to the next.
+pub fn gen_page_url(hosts: &[String], path: &str) -> Option<String> {
+	let host = hosts.first()?;
+	Some(format!("{}{}#{}", host, path, hosts.join(",")))
+}
+
+/// The URL to load a page from. A page asked for again, as the app does when
+/// its image failed to load but also when it prefetched it or dropped it from
+/// its cache, stays on the host it was last loaded from unless that host no
+/// longer serves it, and moves on to the next one otherwise.
+pub fn rotate_host(url: &str) -> Option<String> {
+	let (url, hosts) = url.split_once('#')?;
+	let hosts = hosts.split(',').collect::<Vec<&str>>();
+	let path = url.strip_prefix(hosts.iter().find(|a| url.starts_with(**a))?)?;
+	let mut loaded = LOADED.lock();
 
-	Request::new(url, HttpMethod::Get)
-		.header("Authorization", &format!("YINGQISTS2 {}", token))
-		.json()
+	let index = match loaded.iter().position(|(a, _)| a == path) {
+		Some(position) => {
+			let (_, index) = &mut loaded[position];
+			if !serves(&format!("{}{}", hosts[*index % hosts.len()], path)) {
+				*index = (*index + 1) % hosts.len();
+				defaults_set(HOST_KEY, StringRef::from(hosts[*index]).0);
+			}
+			*index % hosts.len()
+		}
+		None => {
+			if loaded.len() >= LOADED_MAX {
+				loaded.remove(0);
+			}
+			loaded.push((path.to_string(), 0));
+			0
+		}
+	};
+
+	Some(format!("{}{}", hosts[index], path))
+}
+
+/// Whether the image at `url` can be loaded, asked once without retries.
+fn serves(url: &str) -> bool {
+	let request = IMAGE_CLIENT.request(url, HttpMethod::Head).build();
+	request.send();
+	(200..400).contains(&request.status_code())
 }
 
-pub fn gen_gsn_hash(mut params: Vec<(String, String)>) -> String {
+pub fn get_text(url: String) -> Result<String, AidokuError> {
+	CLIENT.get(url).string()
+}
+
+pub fn gen_gsn_hash(mut params: Vec<(String, String)>, method: HttpMethod) -> String {
 	let mut hash = String::new();
 
 	params.sort_by(|a, b| a.0.cmp(&b.0));
 	hash.push_str(GSN_KEY);
-	hash.push_str("GET");
+	hash.push_str(match method {
+		HttpMethod::Post => "POST",
+		_ => "GET",
+	});
 
 	for param in params {
 		hash.push_str(&param.0);
@@ -45,13 +273,11 @@ pub fn gen_gsn_hash(mut params: Vec<(String, String)>) -> String {
 	md5(hash)
 }
 
-pub fn gen_query_string(mut params: Vec<(String, String)>) -> String {
-	let uid = defaults_get("uid").unwrap().as_string().unwrap().read();
-
+pub fn gen_query_string(mut params: Vec<(String, String)>, method: HttpMethod) -> String {
 	params.push((String::from("gak"), String::from("ios_manhuaren2")));
 	params.push((String::from("gft"), String::from("json")));
-	params.push((String::from("gui"), uid));
-	params.push((String::from("gsn"), gen_gsn_hash(params.clone())));
+	params.push((String::from("gui"), get_default("uid")));
+	params.push((String::from("gsn"), gen_gsn_hash(params.clone(), method)));
 
 	let mut query_params = QueryParameters::new();
 
@@ -62,7 +288,12 @@ pub fn gen_query_string(mut params: Vec<(String, String)>) -> String {
 	query_params.to_string()
 }
 
-pub fn gen_explore_url(category: String, status: String, sort: String, page: i32) -> String {
+pub fn gen_explore_request(
+	category: String,
+	status: String,
+	sort: String,
+	page: i32,
+) -> ApiRequest {
 	let mut params: Vec<(String, String)> = Vec::new();
 
 	params.push((String::from("subCategoryType"), String::from("0")));
@@ -72,44 +303,32 @@ pub fn gen_explore_url(category: String, status: String, sort: String, page: i32
 	params.push((String::from("start"), ((page - 1) * 20).to_string()));
 	params.push((String::from("limit"), String::from("20")));
 
-	format!(
-		"{}/v2/manga/getCategoryMangas?{}",
-		API_URL,
-		gen_query_string(params)
-	)
+	ApiRequest::new("/v2/manga/getCategoryMangas", params)
 }
 
-pub fn gen_search_url(query: String, page: i32) -> String {
+pub fn gen_search_request(query: String, page: i32) -> ApiRequest {
 	let mut params: Vec<(String, String)> = Vec::new();
 
 	params.push((String::from("keywords"), query.clone()));
 	params.push((String::from("start"), ((page - 1) * 20).to_string()));
 	params.push((String::from("limit"), String::from("20")));
 
-	format!(
-		"{}/v1/search/getSearchManga?{}",
-		API_URL,
-		gen_query_string(params)
-	)
+	ApiRequest::new("/v1/search/getSearchManga", params)
 }
 
-pub fn gen_manga_details_url(id: String) -> String {
+pub fn gen_manga_details_request(id: String) -> ApiRequest {
 	let mut params: Vec<(String, String)> = Vec::new();
 
 	params.push((String::from("mangaId"), id.clone()));
 
-	format!(
-		"{}/v1/manga/getDetail?{}",
-		API_URL,
-		gen_query_string(params)
-	)
+	ApiRequest::new("/v1/manga/getDetail", params)
 }
 
 pub fn gen_chapter_url(chapter_id: String) -> String {
 	format!("{}/m{}/", WWW_URL, chapter_id)
 }
 
-pub fn gen_page_list_url(manga_id: String, chapter_id: String) -> String {
+pub fn gen_page_list_request(manga_id: String, chapter_id: String) -> ApiRequest {
 	let mut params: Vec<(String, String)> = Vec::new();
 
 	params.push((String::from("mangaId"), manga_id.clone()));
@@ -118,5 +337,5 @@ pub fn gen_page_list_url(manga_id: String, chapter_id: String) -> String {
 	params.push((String::from("loadreal"), String::from("1")));
 	params.push((String::from("imageQuality"), String::from("2")));
 
-	format!("{}/v1/manga/getRead?{}", API_URL, gen_query_string(params))
+	ApiRequest::new("/v1/manga/getRead", params)
 }
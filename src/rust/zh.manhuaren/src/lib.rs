@@ -5,9 +5,14 @@ use aidoku::{
 	error::Result,
 	prelude::*,
 	std::{net::Request, String, Vec},
-	Chapter, Filter, FilterType, Manga, MangaPageResult, Page,
+	Chapter, DeepLink, Filter, FilterType, Manga, MangaPageResult, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	convert, entity,
+	error::{markup_changed, parse_error, Error},
+	link, parse, OptionExt,
+};
 
 mod helper;
 mod parser;
@@ -55,70 +60,99 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 		}
 	}
 
-	let url = if query.is_empty() {
-		helper::gen_explore_url(category, status, sort, page)
+	let request = if query.is_empty() {
+		helper::gen_explore_request(category, status, sort, page)
 	} else {
-		helper::gen_search_url(query.clone(), page)
+		helper::gen_search_request(query.clone(), page)
 	};
 
-	let json = helper::get_json(url)?;
-	let data = json.as_object()?;
-	let data = data.get("response").as_object()?;
+	let json = helper::get_json(request)?;
+	let data = json.get("response").as_object()?;
 	let mangas;
 	let has_more;
 
 	if query.is_empty() {
 		let list = data.get("mangas").as_array()?;
-		mangas = parser::parse_manga_list(list);
+		mangas = parser::parse_manga_list(list)?;
 		has_more = true;
 	} else {
 		let list = data.get("result").as_array()?;
 		let total = data.get("total").as_int()? as i32;
-		mangas = parser::parse_manga_list(list);
+		mangas = parser::parse_manga_list(list)?;
 		has_more = page * 20 < total;
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
 
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
-	let url = helper::gen_manga_details_url(id);
-	let json = helper::get_json(url)?;
-	let data = json.as_object()?;
-	let data = data.get("response").as_object()?;
+	let request = helper::gen_manga_details_request(id);
+	let json = helper::get_json(request)?;
+	let data = json.get("response").as_object()?;
 
-	Ok(parser::parse_manga(data))
+	parser::parse_manga(data).map(convert::manga)
 }
 
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
-	let url = helper::gen_manga_details_url(id);
-	let json = helper::get_json(url)?;
-	let data = json.as_object()?;
-	let data = data.get("response").as_object()?;
+	let request = helper::gen_manga_details_request(id);
+	let json = helper::get_json(request)?;
+	let data = json.get("response").as_object()?;
 
-	Ok(parser::parse_chapter_list(data))
+	parser::parse_chapter_list(data).map(convert::chapters)
 }
 
 #[get_page_list]
 fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
-	let url = helper::gen_page_list_url(manga_id, chapter_id);
-	let json = helper::get_json(url)?;
-	let data = json.as_object()?;
-	let data = data.get("response").as_object()?;
+	let request = helper::gen_page_list_request(manga_id.clone(), chapter_id.clone());
+	let json = helper::get_json(request)?;
+	let data = json.get("response").as_object()?;
+	let pages = parser::parse_page_list(data)?;
+
+	if !pages.is_empty() {
+		return Ok(pages);
+	}
+
+	// Paid chapters come back without images, so look up why.
+	let request = helper::gen_manga_details_request(manga_id);
+	let json = helper::get_json(request)?;
+	let data = json.get("response").as_object()?;
+	if parser::is_paid_section(data, &chapter_id) {
+		return Err(Error::PaidChapter.into());
+	}
 
-	Ok(parser::parse_page_list(data))
+	Err(markup_changed("mangaSectionImages"))
 }
 
 #[modify_image_request]
 fn modify_image_request(request: Request) {
-	request
-		.header("X-Yq-Yqci", r#"{"le": "zh"}"#)
-		.header("User-Agent", "okhttp/3.11.0")
-		.header("Referer", "http://www.dm5.com/dm5api/")
-		.header("ClubReferer", "http://mangaapi.manhuaren.com/");
+	if let Some(url) = helper::rotate_host(&request.url().read()) {
+		request.set_url(url);
+	}
+	helper::IMAGE_CLIENT.apply(request);
+}
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	// Web pages are keyed by slug (`/manhua-{slug}/`) or section (`/m{id}/`),
+	// and only their scripts carry the numeric comic ID the API needs.
+	let segments = parse::path_segments(&url);
+	let chapter_id = match segments.as_slice() {
+		[slug] if slug.starts_with("manhua-") => None,
+		[slug] => Some(slug.strip_prefix('m').or_parse_error()?.to_string()),
+		_ => return Err(parse_error()),
+	};
+	let text = helper::get_text(url)?;
+	let id = parse::between(&text, "MANHUAREN_MID", ";")?
+		.trim_matches(|a: char| !a.is_ascii_digit())
+		.to_string();
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id)?),
+		chapter: chapter_id.map(link::chapter),
+	})
 }
@@ -1,4 +1,5 @@
 use aidoku::{
+	error::Result,
 	prelude::*,
 	std::{ArrayRef, ObjectRef, String, Vec},
 	Chapter, Manga, MangaContentRating, MangaStatus, MangaViewer, Page,
@@ -6,26 +7,26 @@ use aidoku::{
 use alloc::string::ToString;
 
 use crate::helper;
+use zh_common::{chapter, date, OptionExt};
 
-pub fn parse_manga_list(manga_list: ArrayRef) -> Vec<Manga> {
+pub fn parse_manga_list(manga_list: ArrayRef) -> Result<Vec<Manga>> {
 	manga_list
-		.map(|manga| parse_manga(manga.as_object().unwrap()))
-		.collect::<Vec<Manga>>()
+		.map(|manga| parse_manga(manga.as_object()?))
+		.collect::<Result<Vec<Manga>>>()
 }
 
-pub fn parse_manga(manga: ObjectRef) -> Manga {
-	let id = manga.get("mangaId").as_int().unwrap().to_string();
-	let cover = manga.get("mangaCoverimageUrl").as_string().unwrap();
+pub fn parse_manga(manga: ObjectRef) -> Result<Manga> {
+	let id = manga.get("mangaId").as_int()?.to_string();
+	let cover = manga.get("mangaCoverimageUrl").as_string()?;
 	let cover = manga
 		.get("mangaPicimageUrl")
 		.as_string()
 		.unwrap_or(cover)
 		.read();
-	let title = manga.get("mangaName").as_string().unwrap().read();
+	let title = manga.get("mangaName").as_string()?.read();
 	let author = manga
 		.get("mangaAuthor")
-		.as_string()
-		.unwrap()
+		.as_string()?
 		.read()
 		.trim()
 		.to_string();
@@ -38,8 +39,7 @@ pub fn parse_manga(manga: ObjectRef) -> Manga {
 	let url = manga.get("shareUrl").as_string().unwrap_or_default().read();
 	let categories = manga
 		.get("mangaTheme")
-		.as_string()
-		.unwrap()
+		.as_string()?
 		.read()
 		.split(" ")
 		.map(|category| category.to_string())
@@ -52,7 +52,7 @@ pub fn parse_manga(manga: ObjectRef) -> Manga {
 	};
 	let nsfw = MangaContentRating::Safe;
 	let viewer = MangaViewer::Rtl;
-	Manga {
+	Ok(Manga {
 		id,
 		cover,
 		title,
@@ -64,68 +64,139 @@ pub fn parse_manga(manga: ObjectRef) -> Manga {
 		status,
 		nsfw,
 		viewer,
-	}
+	})
 }
 
-pub fn parse_chapter_list(manga: ObjectRef) -> Vec<Chapter> {
-	let words = manga.get("mangaWords").as_array().unwrap();
-	let rolls = manga.get("mangaRolls").as_array().unwrap();
-	let episode = manga.get("mangaEpisode").as_array().unwrap();
-	let mut chapters: Vec<Chapter> = Vec::new();
-	chapters.append(&mut parse_chapters(words));
-	chapters.append(&mut parse_chapters(rolls));
-	chapters.append(&mut parse_chapters(episode));
-	chapters.sort_by_key(|a| a.chapter.to_bits());
-	chapters.reverse();
-	chapters
+/// The kinds of sections a comic is split into.
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+	/// `mangaWords`, the regular chapters.
+	Word,
+	/// `mangaRolls`, whole volumes (单行本).
+	Roll,
+	/// `mangaEpisode`, side stories (番外).
+	Episode,
 }
 
-pub fn parse_chapters(chapter_list: ArrayRef) -> Vec<Chapter> {
+pub fn parse_chapter_list(manga: ObjectRef) -> Result<Vec<Chapter>> {
+	let hide_paid = helper::hide_paid();
 	let mut chapters: Vec<Chapter> = Vec::new();
 
+	for (key, section) in [
+		("mangaWords", Section::Word),
+		("mangaRolls", Section::Roll),
+		("mangaEpisode", Section::Episode),
+	] {
+		let list = manga.get(key).as_array()?;
+		chapters.append(&mut parse_chapters(list, section, hide_paid)?);
+	}
+
+	Ok(chapters)
+}
+
+/// Parses one kind of section, newest first. Volumes and side stories are
+/// grouped under their own scanlator so they stay apart from the chapters.
+fn parse_chapters(
+	chapter_list: ArrayRef,
+	section: Section,
+	hide_paid: bool,
+) -> Result<Vec<Chapter>> {
+	let mut chapters: Vec<(f32, Chapter)> = Vec::new();
+
 	for item in chapter_list {
-		let item = item.as_object().unwrap();
-		let id = item.get("sectionId").as_int().unwrap().to_string();
-		let section_title = item.get("sectionTitle").as_string().unwrap().read();
-		let section_name = item.get("sectionName").as_string().unwrap().read();
-		let is_must_pay = item.get("isMustPay").as_int().unwrap_or_default();
+		let item = item.as_object()?;
+		if hide_paid && is_paid(&item) {
+			continue;
+		}
+		let id = item.get("sectionId").as_int()?.to_string();
+		let section_title = item.get("sectionTitle").as_string()?.read();
+		let section_name = item.get("sectionName").as_string()?.read();
+		let number = chapter::parse(&section_name);
 		let title = if section_title.is_empty() {
 			section_name
-		} else if is_must_pay == 0 {
+		} else {
 			format!("{} {}", section_name, section_title)
+		};
+		let title = if is_paid(&item) {
+			format!("{} {}", "🔒", title)
 		} else {
-			format!("{} {} {}", "🔒", section_name, section_title)
+			title
 		};
-		let chapter = item.get("sectionSort").as_float().unwrap_or_default() as f32;
+		let sort = item.get("sectionSort").as_float().unwrap_or_default() as f32;
+		let (volume, chapter, scanlator) = match section {
+			Section::Word => (number.volume(), number.chapter_or(sort), ""),
+			Section::Roll => (
+				number.volume.or(number.chapter).unwrap_or(sort),
+				-1.0,
+				"单行本",
+			),
+			Section::Episode => (number.volume(), -1.0, "番外"),
+		};
+		let date_updated = date::parse(
+			item.get("releaseTime")
+				.as_string()
+				.map(|a| a.read())
+				.unwrap_or_default(),
+		);
 		let url = helper::gen_chapter_url(id.clone());
-		chapters.push(Chapter {
-			id,
-			title,
-			chapter,
-			url,
-			..Default::default()
-		})
+		chapters.push((
+			sort,
+			Chapter {
+				id,
+				title,
+				volume,
+				chapter,
+				date_updated,
+				scanlator: scanlator.to_string(),
+				url,
+				..Default::default()
+			},
+		))
 	}
 
-	chapters
+	chapters.sort_by(|a, b| b.0.total_cmp(&a.0));
+	Ok(chapters.into_iter().map(|(_, chapter)| chapter).collect())
 }
 
-pub fn parse_page_list(chapter: ObjectRef) -> Vec<Page> {
-	let list = chapter.get("mangaSectionImages").as_array().unwrap();
-	let host_list = chapter.get("hostList").as_array().unwrap();
-	let query = chapter.get("query").as_string().unwrap();
-	let mut pages: Vec<Page> = Vec::new();
+/// Whether a section must be bought before it can be read.
+pub fn is_paid(section: &ObjectRef) -> bool {
+	section.get("isMustPay").as_int().unwrap_or_default() != 0
+}
 
-	if host_list.is_empty() {
-		return pages;
-	}
+/// Whether the section `id` of `manga` must be bought, in any of its kinds.
+pub fn is_paid_section(manga: ObjectRef, id: &str) -> bool {
+	["mangaWords", "mangaRolls", "mangaEpisode"]
+		.iter()
+		.filter_map(|key| manga.get(key).as_array().ok())
+		.flatten()
+		.filter_map(|item| item.as_object().ok())
+		.find(|item| {
+			item.get("sectionId")
+				.as_int()
+				.is_ok_and(|a| a.to_string() == id)
+		})
+		.is_some_and(|item| is_paid(&item))
+}
 
-	let host = host_list.get(0).as_string().unwrap().read();
+pub fn parse_page_list(chapter: ObjectRef) -> Result<Vec<Page>> {
+	let list = chapter.get("mangaSectionImages").as_array()?;
+	let hosts = chapter
+		.get("hostList")
+		.as_array()?
+		.filter_map(|host| host.as_string().ok())
+		.map(|host| host.read())
+		.collect::<Vec<String>>();
+	let query = chapter.get("query").as_string()?.read();
+	let images = list
+		.map(|item| item.as_string().map(|a| a.read()))
+		.collect::<Result<Vec<String>>>()?;
+	let hosts = helper::order_hosts(hosts);
+	let mut pages: Vec<Page> = Vec::new();
 
-	for (index, item) in list.enumerate() {
-		let item = item.as_string().unwrap().read();
+	for (index, item) in images.into_iter().enumerate() {
 		let index = index as i32;
-		let url = format!("{}{}{}", host, item, query);
+		let url = helper::gen_page_url(&hosts, &format!("{}{}", item, query))
+			.or_markup_changed("hostList")?;
 		pages.push(Page {
 			index,
 			url,
@@ -133,5 +204,5 @@ pub fn parse_page_list(chapter: ObjectRef) -> Vec<Page> {
 		});
 	}
 
-	pages
+	Ok(pages)
 }
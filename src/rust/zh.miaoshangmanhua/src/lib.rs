@@ -5,17 +5,17 @@ use aidoku::{
 	error::Result,
 	helpers::{substring::Substring, uri::encode_uri},
 	prelude::*,
-	std::{
-		net::{HttpMethod, Request},
-		String, Vec,
-	},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
-	MangaViewer, Page,
+	std::{net::Request, String, Vec},
+	Chapter, DeepLink, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult,
+	MangaStatus, MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{chapter, convert, entity, error::parse_error, link, parse, Client, OptionExt};
 
 const WWW_URL: &str = "https://www.miaoshangmanhua.cc";
 
+const CLIENT: Client = Client::new();
+
 const FILTER_TAG: [&str; 80] = [
 	"", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16", "17", "18",
 	"19", "20", "21", "22", "23", "24", "25", "26", "27", "28", "29", "30", "31", "32", "33", "34",
@@ -27,12 +27,7 @@ const FILTER_ORDER: [&str; 2] = ["hits", "addtime"];
 
 fn handle_cover_url(url: String) -> String {
 	if url.contains("url=") {
-		url.split("url=")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
-			.replace("&w=320&q=60", "")
+		parse::after_last(&url, "url=").replace("&w=320&q=60", "")
 	} else {
 		url
 	}
@@ -83,7 +78,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 		format!("{}/search/{}/{}", WWW_URL, encode_uri(query), page)
 	};
 
-	let html = Request::new(url, HttpMethod::Get).html()?;
+	let html = CLIENT.get(url).html()?;
 	let has_more = true;
 	let mut mangas: Vec<Manga> = Vec::new();
 
@@ -92,16 +87,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select("li:nth-child(1)>a")
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
-			.replace(".html", "");
+		let id = parse::last_segment(item.select("li:nth-child(1)>a").attr("href").read());
 		let cover = handle_cover_url(item.select("li:nth-child(1)>a>img").attr("src").read());
 		let title = item.select(".title>a").text().read();
 		mangas.push(Manga {
@@ -113,7 +99,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -142,7 +128,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	}
 
 	let url = format!("{}/custom/{}", WWW_URL, list);
-	let html = Request::new(url, HttpMethod::Get).html()?;
+	let html = CLIENT.get(url).html()?;
 	let has_more = false;
 	let mut mangas: Vec<Manga> = Vec::new();
 
@@ -151,16 +137,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select(".pic>a")
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
-			.replace(".html", "");
+		let id = parse::last_segment(item.select(".pic>a").attr("href").read());
 		let cover = handle_cover_url(item.select(".pic>a>img").attr("src").read());
 		let title = item.select(".title>a").text().read();
 		mangas.push(Manga {
@@ -172,7 +149,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -180,22 +157,20 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
 	let url = format!("{}/comic/{}.html", WWW_URL, id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let cover = handle_cover_url(html.select(".cy_info_cover>a>img").attr("src").read());
 	let title = html.select(".cy_title>h1").text().read();
-	let author = html
-		.select(".cy_intro_l>.cy_xinxi:nth-child(4)>span:nth-child(1)>a")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read())
-		.collect::<Vec<String>>()
-		.join(", ");
+	let author = parse::texts(
+		&html,
+		".cy_intro_l>.cy_xinxi:nth-child(4)>span:nth-child(1)>a",
+	)
+	.join(", ");
 	let artist = String::new();
 	let description = html.select("#comic-description").text().read();
-	let categories = html
-		.select(".cy_intro_l>.cy_xinxi:nth-child(5)>span:nth-child(1)>a")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read())
-		.collect::<Vec<String>>();
+	let categories = parse::texts(
+		&html,
+		".cy_intro_l>.cy_xinxi:nth-child(5)>span:nth-child(1)>a",
+	);
 	let status = match html
 		.select(".cy_intro_l>.cy_xinxi:nth-child(4)>span:nth-child(2)>font")
 		.text()
@@ -211,7 +186,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let nsfw = MangaContentRating::Safe;
 	let viewer = MangaViewer::Scroll;
 
-	Ok(Manga {
+	Ok(convert::manga(Manga {
 		id,
 		cover,
 		title,
@@ -223,33 +198,24 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		status,
 		nsfw,
 		viewer,
-	})
+	}))
 }
 
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	let url = format!("{}/comic/{}.html", WWW_URL, id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let list = html.select("#mh-chapter-list-ol-0>li>a").array();
-	let len = list.len();
 	let mut chapters: Vec<Chapter> = Vec::new();
 
-	for (index, item) in list.enumerate() {
+	for item in list {
 		let item = match item.as_node() {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let chapter_id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap()
-			.replace(".html", "");
+		let chapter_id = parse::last_segment(item.attr("href").read());
 		let title = item.select("p").text().read().trim().to_string();
-		let chapter = (len - index) as f32;
+		let number = chapter::parse(&title);
 		let url = format!(
 			"{}/comic/{}/{}.html",
 			WWW_URL,
@@ -259,13 +225,14 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 		chapters.push(Chapter {
 			id: chapter_id,
 			title,
-			chapter,
+			volume: number.volume(),
+			chapter: number.chapter(),
 			url,
 			..Default::default()
 		});
 	}
 
-	Ok(chapters)
+	Ok(convert::chapters(chapters))
 }
 
 #[get_page_list]
@@ -276,14 +243,14 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 		manga_id.clone(),
 		chapter_id.clone()
 	);
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let text = html
 		.select(".rd-article-wr")
 		.html()
 		.read()
 		.as_str()
 		.substring_before("rd-article__end")
-		.unwrap()
+		.or_parse_error()?
 		.to_string();
 	let list = text
 		.split("\"")
@@ -308,3 +275,20 @@ fn modify_image_request(request: Request) {
 	let url = request.url().read();
 	request.header("Referer", &url);
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let segments = parse::path_segments(&url);
+	let (id, chapter_id) = match segments.as_slice() {
+		[kind, id] if kind == "comic" => (parse::last_segment(id), None),
+		[kind, id, chapter_id] if kind == "comic" => {
+			(id.clone(), Some(parse::last_segment(chapter_id)))
+		}
+		_ => return Err(parse_error()),
+	};
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id)?),
+		chapter: chapter_id.map(link::chapter),
+	})
+}
@@ -5,19 +5,18 @@ use aidoku::{
 	error::Result,
 	helpers::uri::encode_uri,
 	prelude::*,
-	std::{
-		defaults::defaults_get,
-		net::{HttpMethod, Request},
-		String, Vec,
-	},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
-	MangaViewer, Page,
+	std::{defaults::defaults_get, String, Vec},
+	Chapter, DeepLink, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult,
+	MangaStatus, MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{chapter, convert, entity, error::parse_error, link, parse, Client};
 
 const WWW_URL: &str = "https://www.mkzhan.com";
 const API_URL: &str = "https://comic.mkzcdn.com";
 
+const CLIENT: Client = Client::new();
+
 const FILTER_THEME: [&str; 24] = [
 	"0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "12", "13", "14", "15", "16", "17",
 	"18", "19", "20", "21", "23", "24", "26",
@@ -103,7 +102,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 		)
 	};
 
-	let json = Request::new(url, HttpMethod::Get).json()?;
+	let json = CLIENT.get(url).json()?;
 	let data = json.as_object()?;
 	let data = data.get("data").as_object()?;
 	let list = data.get("list").as_array()?;
@@ -124,7 +123,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more: true,
 	})
 }
@@ -165,7 +164,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 		"{}/top/{}/?type=1&page_num={}&page_size=10",
 		API_URL, name, page
 	);
-	let json = Request::new(url, HttpMethod::Get).json()?;
+	let json = CLIENT.get(url).json()?;
 	let data = json.as_object()?;
 	let data = data.get("data").as_object()?;
 	let list = data.get("list").as_array()?;
@@ -186,7 +185,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more: true,
 	})
 }
@@ -194,19 +193,14 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
 	let url = format!("{}/{}/", WWW_URL, id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let cover = html
 		.select(".de-info__cover>img")
 		.attr("data-src")
 		.read()
 		.replace("!cover-400", "");
 	let title = html.select(".j-comic-title").text().read();
-	let author = html
-		.select(".comic-author>.name>a")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read())
-		.collect::<Vec<String>>()
-		.join(", ");
+	let author = parse::texts(&html, ".comic-author>.name>a").join(", ");
 	let artist = String::new();
 	let description = html.select(".intro-total").text().read();
 	let categories = html
@@ -229,7 +223,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let nsfw = MangaContentRating::Safe;
 	let viewer = MangaViewer::Scroll;
 
-	Ok(Manga {
+	Ok(convert::manga(Manga {
 		id,
 		cover,
 		title,
@@ -241,35 +235,42 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		status,
 		nsfw,
 		viewer,
-	})
+	}))
 }
 
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	let url = format!("{}/chapter/v1/?comic_id={}", API_URL, id.clone());
-	let json = Request::new(url.clone(), HttpMethod::Get).json()?;
+	let json = CLIENT.get(url.clone()).json()?;
 	let data = json.as_object()?;
 	let list = data.get("data").as_array()?;
 	let mut chapters: Vec<Chapter> = Vec::new();
 
-	for (index, item) in list.enumerate() {
+	for item in list {
 		let item = item.as_object()?;
 		let chapter_id = item.get("chapter_id").as_string()?.read();
 		let title = item.get("title").as_string()?.read();
-		let chapter = (index + 1) as f32;
+		let number = chapter::parse(&title);
+		let date_updated = item
+			.get("start_time")
+			.as_int()
+			.map(|a| a as f64)
+			.unwrap_or(-1.0);
 		let url = format!("{}/{}/{}.html", WWW_URL, id.clone(), chapter_id.clone());
 
 		chapters.push(Chapter {
 			id: chapter_id,
 			title,
-			chapter,
+			volume: number.volume(),
+			chapter: number.chapter(),
+			date_updated,
 			url,
 			..Default::default()
 		});
 	}
 	chapters.reverse();
 
-	Ok(chapters)
+	Ok(convert::chapters(chapters))
 }
 
 #[get_page_list]
@@ -284,7 +285,7 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 		uid,
 		sign
 	);
-	let json = Request::new(url.clone(), HttpMethod::Get).json()?;
+	let json = CLIENT.get(url.clone()).json()?;
 	let data = json.as_object()?;
 	let data = data.get("data").as_object()?;
 	let list = data.get("page").as_array()?;
@@ -304,3 +305,18 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 
 	Ok(pages)
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let segments = parse::path_segments(&url);
+	let (id, chapter_id) = match segments.as_slice() {
+		[id] => (id.clone(), None),
+		[id, chapter_id] => (id.clone(), Some(parse::last_segment(chapter_id))),
+		_ => return Err(parse_error()),
+	};
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id)?),
+		chapter: chapter_id.map(link::chapter),
+	})
+}
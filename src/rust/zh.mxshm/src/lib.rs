@@ -5,17 +5,18 @@ use aidoku::{
 	error::Result,
 	helpers::uri::encode_uri,
 	prelude::*,
-	std::{
-		defaults::defaults_get,
-		net::{HttpMethod, Request},
-		String, Vec,
-	},
-	Chapter, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	std::{defaults::defaults_get, String, Vec},
+	Chapter, DeepLink, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	chapter, convert, entity,
+	error::{markup_changed, parse_error},
+	link, parse, Client, UA,
+};
 
-const UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/135.0.0.0 Safari/537.36";
+const CLIENT: Client = Client::new().user_agent(UA);
 
 const FILTER_TAG: [&str; 23] = [
 	"全部", "青春", "性感", "长腿", "多人", "御姐", "巨乳", "新婚", "媳妇", "暧昧", "清纯", "调教",
@@ -24,12 +25,8 @@ const FILTER_TAG: [&str; 23] = [
 const FILTER_AREA: [&str; 4] = ["-1", "1", "2", "3"];
 const FILTER_END: [&str; 3] = ["-1", "0", "1"];
 
-fn get_url() -> String {
-	defaults_get("url").unwrap().as_string().unwrap().read()
-}
-
-fn gen_request(url: String, method: HttpMethod) -> Request {
-	Request::new(url, method).header("User-Agent", UA)
+fn get_url() -> Result<String> {
+	Ok(defaults_get("url")?.as_string()?.read())
 }
 
 #[get_manga_list]
@@ -66,16 +63,20 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	let url = if query.is_empty() {
 		format!(
 			"{}/booklist?tag={}&area={}&end={}&page={}",
-			get_url(),
+			get_url()?,
 			encode_uri(tag),
 			area,
 			end,
 			page
 		)
 	} else {
-		format!("{}/search?keyword={}", get_url(), encode_uri(query.clone()))
+		format!(
+			"{}/search?keyword={}",
+			get_url()?,
+			encode_uri(query.clone())
+		)
 	};
-	let html = gen_request(url, HttpMethod::Get).html()?;
+	let html = CLIENT.get(url).html()?;
 	let has_more = query.is_empty();
 	let mut mangas: Vec<Manga> = Vec::new();
 
@@ -84,15 +85,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select("a")
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = parse::last_segment(item.select("a").attr("href").read());
 		let cover = item
 			.select("a>p")
 			.attr("style")
@@ -114,15 +107,15 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
 
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
-	let url = format!("{}/book/{}", get_url(), id.clone());
-	let html = gen_request(url.clone(), HttpMethod::Get).html()?;
+	let url = format!("{}/book/{}", get_url()?, id.clone());
+	let html = CLIENT.get(url.clone()).html()?;
 	let cover = html
 		.select(".banner_detail_form>.cover>img")
 		.attr("src")
@@ -150,12 +143,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		.read()
 		.trim()
 		.to_string();
-	let categories = html
-		.select(".banner_detail_form>.info>p:nth-child(5)>span>a")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read().trim().to_string())
-		.filter(|a| !a.is_empty())
-		.collect::<Vec<String>>();
+	let categories = parse::texts(&html, ".banner_detail_form>.info>p:nth-child(5)>span>a");
 	let status = match html
 		.select(".banner_detail_form>.info>p:nth-child(4)>span:nth-child(1)>span")
 		.text()
@@ -171,7 +159,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let nsfw = MangaContentRating::Nsfw;
 	let viewer = MangaViewer::Scroll;
 
-	Ok(Manga {
+	Ok(convert::manga(Manga {
 		id,
 		cover,
 		title,
@@ -183,48 +171,42 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		status,
 		nsfw,
 		viewer,
-	})
+	}))
 }
 
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
-	let url = format!("{}/book/{}", get_url(), id.clone());
-	let html = gen_request(url.clone(), HttpMethod::Get).html()?;
+	let url = format!("{}/book/{}", get_url()?, id.clone());
+	let html = CLIENT.get(url.clone()).html()?;
 	let mut chapters: Vec<Chapter> = Vec::new();
 
-	for (index, item) in html.select("#detail-list-select>li>a").array().enumerate() {
+	for item in html.select("#detail-list-select>li>a").array() {
 		let item = match item.as_node() {
 			Ok(item) => item,
 			Err(_) => continue,
 		};
-		let id = item
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = parse::last_segment(item.attr("href").read());
 		let title = item.text().read().trim().to_string();
-		let chapter = (index + 1) as f32;
-		let url = format!("{}/chapter/{}", get_url(), id.clone());
+		let number = chapter::parse(&title);
+		let url = format!("{}/chapter/{}", get_url()?, id.clone());
 		chapters.push(Chapter {
 			id,
 			title,
-			chapter,
+			volume: number.volume(),
+			chapter: number.chapter(),
 			url,
 			..Default::default()
 		});
 	}
 	chapters.reverse();
 
-	Ok(chapters)
+	Ok(convert::chapters(chapters))
 }
 
 #[get_page_list]
 fn get_page_list(_: String, chapter_id: String) -> Result<Vec<Page>> {
-	let url = format!("{}/chapter/{}", get_url(), chapter_id.clone());
-	let html = gen_request(url.clone(), HttpMethod::Get).html()?;
+	let url = format!("{}/chapter/{}", get_url()?, chapter_id.clone());
+	let html = CLIENT.get(url.clone()).html()?;
 	let mut pages: Vec<Page> = Vec::new();
 
 	for (index, item) in html
@@ -245,5 +227,30 @@ fn get_page_list(_: String, chapter_id: String) -> Result<Vec<Page>> {
 		})
 	}
 
+	if pages.is_empty() {
+		return Err(markup_changed(".comicpage>div>img,#cp_img>img"));
+	}
+
 	Ok(pages)
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let segments = parse::path_segments(&url);
+	let (id, chapter_id) = match segments.as_slice() {
+		[kind, id] if kind == "book" => (id.clone(), None),
+		[kind, chapter_id] if kind == "chapter" => {
+			// Chapter URLs do not name their book, but the reader links back to it.
+			let url = format!("{}/chapter/{}", get_url()?, chapter_id);
+			let html = CLIENT.get(url).html()?;
+			let id = parse::last_segment(html.select("a[href*='/book/']").attr("href").read());
+			(id, Some(chapter_id.clone()))
+		}
+		_ => return Err(parse_error()),
+	};
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id)?),
+		chapter: chapter_id.map(link::chapter),
+	})
+}
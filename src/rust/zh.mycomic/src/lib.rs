@@ -3,20 +3,23 @@ extern crate alloc;
 
 use aidoku::{
 	error::Result,
-	helpers::{substring::Substring, uri::encode_uri},
+	helpers::uri::encode_uri,
 	prelude::*,
-	std::{
-		json,
-		net::{HttpMethod, Request},
-		String, Vec,
-	},
-	Chapter, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	std::{json, net::Request, String, Vec},
+	Chapter, DeepLink, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	chapter, convert, entity,
+	error::{markup_changed, parse_error},
+	link, parse, Client,
+};
 
 const WWW_URL: &str = "https://mycomic.com/cn";
 
+const CLIENT: Client = Client::new().referer(WWW_URL);
+
 const FILTER_TAG: [&str; 39] = [
 	"",
 	"mohuan",
@@ -142,7 +145,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			page
 		)
 	};
-	let html = Request::new(url, HttpMethod::Get).html()?;
+	let html = CLIENT.get(url).html()?;
 	let has_more = true;
 	let mut mangas: Vec<Manga> = Vec::new();
 
@@ -151,16 +154,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select("a")
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.filter(|a| !a.is_empty())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = parse::last_segment(item.select("a").attr("href").read());
 		let img = item.select("a>img");
 		let mut cover = img.attr("data-src").read();
 		if cover.is_empty() {
@@ -176,7 +170,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -184,7 +178,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
 	let url = format!("{}/comics/{}", WWW_URL, id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let cover = html.select("meta[name='og:image']").attr("content").read();
 	let title = html
 		.select("title")
@@ -207,11 +201,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 			.trim()
 			.to_string();
 	}
-	let categories = html
-		.select("a[href*='tag']")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read())
-		.collect::<Vec<String>>();
+	let categories = parse::texts(&html, "a[href*='tag']");
 	let status = match html.select("div[data-flux-badge]").text().read().trim() {
 		"连载中" => MangaStatus::Ongoing,
 		"已完结" => MangaStatus::Completed,
@@ -220,7 +210,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let nsfw = MangaContentRating::Safe;
 	let viewer = MangaViewer::Rtl;
 
-	Ok(Manga {
+	Ok(convert::manga(Manga {
 		id,
 		cover,
 		title,
@@ -232,52 +222,46 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		status,
 		nsfw,
 		viewer,
-	})
+	}))
 }
 
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	let url = format!("{}/comics/{}", WWW_URL, id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let data = html.select("div[x-data*='chapters']").attr("x-data").read();
-	let mut text = data
-		.substring_after("chapters:")
-		.unwrap()
-		.substring_before("],")
-		.unwrap()
-		.trim()
-		.to_string();
+	let mut text = parse::between(&data, "chapters:", "],")?.trim().to_string();
 	text.push_str("]");
 	let data = json::parse(&text)?;
 	let list = data.as_array()?;
-	let len = list.len();
 	let mut chapters: Vec<Chapter> = Vec::new();
 
-	for (index, item) in list.enumerate() {
+	for item in list {
 		let item = match item.as_object() {
 			Ok(item) => item,
 			Err(_) => continue,
 		};
-		let id = item.get("id").as_int().unwrap().to_string();
-		let title = item.get("title").as_string().unwrap().read();
-		let chapter = (len - index) as f32;
+		let id = item.get("id").as_int()?.to_string();
+		let title = item.get("title").as_string()?.read();
+		let number = chapter::parse(&title);
 		let url = format!("{}/chapters/{}", WWW_URL, id);
 		chapters.push(Chapter {
 			id,
 			title,
-			chapter,
+			volume: number.volume(),
+			chapter: number.chapter(),
 			url,
 			..Default::default()
 		});
 	}
 
-	Ok(chapters)
+	Ok(convert::chapters(chapters))
 }
 
 #[get_page_list]
 fn get_page_list(_: String, chapter_id: String) -> Result<Vec<Page>> {
 	let url = format!("{}/chapters/{}", WWW_URL, chapter_id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let mut pages: Vec<Page> = Vec::new();
 
 	for (index, item) in html.select("img.page").array().enumerate() {
@@ -298,10 +282,35 @@ fn get_page_list(_: String, chapter_id: String) -> Result<Vec<Page>> {
 		})
 	}
 
+	if pages.is_empty() {
+		return Err(markup_changed("img.page"));
+	}
+
 	Ok(pages)
 }
 
 #[modify_image_request]
 fn modify_image_request(request: Request) {
-	request.header("Referer", WWW_URL);
+	CLIENT.apply(request);
+}
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let segments = parse::path_segments(&url);
+	let (id, chapter_id) = match segments.as_slice() {
+		[.., kind, id] if kind == "comics" => (id.clone(), None),
+		[.., kind, chapter_id] if kind == "chapters" => {
+			// Chapter URLs do not name their comic, but the reader links back to it.
+			let url = format!("{}/chapters/{}", WWW_URL, chapter_id);
+			let html = CLIENT.get(url).html()?;
+			let id = parse::last_segment(html.select("a[href*='/comics/']").attr("href").read());
+			(id, Some(chapter_id.clone()))
+		}
+		_ => return Err(parse_error()),
+	};
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id)?),
+		chapter: chapter_id.map(link::chapter),
+	})
 }
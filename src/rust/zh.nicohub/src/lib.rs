@@ -5,16 +5,16 @@ use aidoku::{
 	error::Result,
 	helpers::uri::encode_uri_component,
 	prelude::*,
-	std::{
-		net::{HttpMethod, Request},
-		String, Vec,
-	},
-	Chapter, Filter, FilterType, Manga, MangaPageResult, Page,
+	std::{String, Vec},
+	Chapter, DeepLink, Filter, FilterType, Manga, MangaPageResult, Page,
 };
 use alloc::string::ToString;
+use zh_common::{convert, entity, error::parse_error, parse, Client};
 
 mod parser;
 
+const CLIENT: Client = Client::new();
+
 const FILTER_CATEGORY: [&str; 45] = [
 	"",
 	"649c1841ab4619df894c5009",
@@ -121,18 +121,15 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 		format!("board:漫画 {}", query)
 	};
 	let body = format!("query={}&page={}", encode_uri_component(query), page);
-	let json = Request::new(url, HttpMethod::Post)
-		.body(body)
-		.header("Content-Type", "application/x-www-form-urlencoded")
-		.json()?;
+	let json = CLIENT.post(url).form(body).json()?;
 	let data = json.as_object()?;
 	let list = data.get("data").as_array()?;
 	let page_data = data.get("page").as_object()?;
 	let total_page = page_data.get("total_page").as_int()? as i32;
-	let mangas = parser::parse_manga_list(list);
+	let mangas = parser::parse_manga_list(list)?;
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more: page < total_page,
 	})
 }
@@ -141,14 +138,11 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 fn get_manga_details(id: String) -> Result<Manga> {
 	let url = format!("{}/api/get/info/", parser::WWW_URL);
 	let body = format!("category=comic&id={}", id);
-	let json = Request::new(url, HttpMethod::Post)
-		.body(body)
-		.header("Content-Type", "application/x-www-form-urlencoded")
-		.json()?;
+	let json = CLIENT.post(url).form(body).json()?;
 	let data = json.as_object()?;
 	let data = data.get("data").as_object()?;
 
-	Ok(parser::parse_manga(data))
+	parser::parse_manga(data).map(convert::manga)
 }
 
 #[get_chapter_list]
@@ -159,15 +153,12 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 
 	loop {
 		let body = format!("category=comic&id={}&page={}&limit=20", id.clone(), page);
-		let json = Request::new(url.clone(), HttpMethod::Post)
-			.body(body)
-			.header("Content-Type", "application/x-www-form-urlencoded")
-			.json()?;
+		let json = CLIENT.post(url.clone()).form(body).json()?;
 		let data = json.as_object()?;
 		let list = data.get("data").as_array()?;
 		let page_data = data.get("page").as_object()?;
 		let total_page = page_data.get("total_page").as_int()? as i32;
-		let chapter_list = parser::parse_chapter_list(list);
+		let chapter_list = parser::parse_chapter_list(list)?;
 		chapters = [chapters, chapter_list].concat();
 
 		if page == total_page {
@@ -178,21 +169,32 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	}
 	chapters.reverse();
 
-	Ok(chapters)
+	Ok(convert::chapters(chapters))
 }
 
 #[get_page_list]
 fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 	let url = format!("{}/api/get/episode/", parser::WWW_URL);
 	let body = format!("category=comic&post_id={}&id={}", manga_id, chapter_id);
-	let json = Request::new(url, HttpMethod::Post)
-		.body(body)
-		.header("Content-Type", "application/x-www-form-urlencoded")
-		.json()?;
+	let json = CLIENT.post(url).form(body).json()?;
 	let data = json.as_object()?;
 	let data = data.get("data").as_object()?;
 	let data = data.get("param").as_object()?;
 	let list = data.get("img").as_array()?;
 
-	Ok(parser::parse_page_list(list))
+	parser::parse_page_list(list)
+}
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let segments = parse::path_segments(&url);
+	let id = match segments.as_slice() {
+		[kind, info, id] if kind == "comic" && info == "info" => id.clone(),
+		_ => return Err(parse_error()),
+	};
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id)?),
+		chapter: None,
+	})
 }
@@ -1,45 +1,49 @@
 use aidoku::{
+	error::Result,
 	prelude::*,
 	std::{ArrayRef, ObjectRef, String, Vec},
 	Chapter, Manga, MangaContentRating, MangaStatus, MangaViewer, Page,
 };
+use zh_common::chapter;
 
 pub const WWW_URL: &str = "https://nicohub.cc";
 
-pub fn parse_manga_list(manga_list: ArrayRef) -> Vec<Manga> {
+pub fn parse_manga_list(manga_list: ArrayRef) -> Result<Vec<Manga>> {
 	manga_list
-		.map(|manga| parse_manga(manga.as_object().unwrap()))
-		.collect::<Vec<Manga>>()
+		.map(|manga| parse_manga(manga.as_object()?))
+		.collect::<Result<Vec<Manga>>>()
 }
 
-pub fn parse_manga(manga: ObjectRef) -> Manga {
-	let id = manga.get("id").as_string().unwrap().read();
-	let cover = manga.get("cover").as_string().unwrap().read();
-	let title = manga.get("name").as_string().unwrap().read();
+pub fn parse_manga(manga: ObjectRef) -> Result<Manga> {
+	let id = manga.get("id").as_string()?.read();
+	let cover = manga.get("cover").as_string()?.read();
+	let title = manga.get("name").as_string()?.read();
 	let author = manga
 		.get("author")
-		.as_array()
-		.unwrap()
-		.map(|author| author.as_string().unwrap().read())
+		.as_array()?
+		.filter_map(|author| author.as_string().ok())
+		.map(|author| author.read())
 		.collect::<Vec<String>>()
 		.join(", ");
 	let artist = String::new();
-	let description = manga.get("introduction").as_string().unwrap().read();
+	let description = manga.get("introduction").as_string()?.read();
 	let url = format!("{}/comic/info/{}", WWW_URL, id.clone());
-	let filters = manga.get("type").as_object().unwrap();
+	let filters = manga.get("type").as_object()?;
 	let categories = filters
 		.get("题材")
 		.as_object()
 		.unwrap_or_default()
 		.keys()
-		.map(|category| category.as_string().unwrap().read())
+		.filter_map(|category| category.as_string().ok())
+		.map(|category| category.read())
 		.collect::<Vec<String>>();
 	let status = match filters
 		.get("连载")
 		.as_object()
 		.unwrap_or_default()
 		.keys()
-		.map(|status| status.as_string().unwrap().read())
+		.filter_map(|status| status.as_string().ok())
+		.map(|status| status.read())
 		.collect::<Vec<String>>()
 		.pop()
 		.unwrap_or_default()
@@ -51,7 +55,7 @@ pub fn parse_manga(manga: ObjectRef) -> Manga {
 	};
 	let nsfw = MangaContentRating::Safe;
 	let viewer = MangaViewer::Rtl;
-	Manga {
+	Ok(Manga {
 		id,
 		cover,
 		title,
@@ -63,37 +67,39 @@ pub fn parse_manga(manga: ObjectRef) -> Manga {
 		status,
 		nsfw,
 		viewer,
-	}
+	})
 }
 
-pub fn parse_chapter_list(chapter_list: ArrayRef) -> Vec<Chapter> {
+pub fn parse_chapter_list(chapter_list: ArrayRef) -> Result<Vec<Chapter>> {
 	let mut chapters: Vec<Chapter> = Vec::new();
 
 	for item in chapter_list {
-		let item = item.as_object().unwrap();
-		let id = item.get("id").as_string().unwrap().read();
-		let title = item.get("name").as_string().unwrap().read();
-		let chapter = item.get("sort").as_int().unwrap() as f32;
+		let item = item.as_object()?;
+		let id = item.get("id").as_string()?.read();
+		let title = item.get("name").as_string()?.read();
+		let number = chapter::parse(&title);
+		let chapter = number.chapter_or(item.get("sort").as_int()? as f32);
 		let url = String::new();
 		chapters.push(Chapter {
 			id,
 			title,
+			volume: number.volume(),
 			chapter,
 			url,
 			..Default::default()
 		})
 	}
 
-	chapters
+	Ok(chapters)
 }
 
-pub fn parse_page_list(page_list: ArrayRef) -> Vec<Page> {
+pub fn parse_page_list(page_list: ArrayRef) -> Result<Vec<Page>> {
 	let mut pages: Vec<Page> = Vec::new();
 
 	for (index, item) in page_list.enumerate() {
-		let item = item.as_object().unwrap();
+		let item = item.as_object()?;
 		let index = index as i32;
-		let url = item.get("path").as_string().unwrap().read();
+		let url = item.get("path").as_string()?.read();
 		pages.push(Page {
 			index,
 			url,
@@ -101,5 +107,5 @@ pub fn parse_page_list(page_list: ArrayRef) -> Vec<Page> {
 		})
 	}
 
-	pages
+	Ok(pages)
 }
@@ -1,17 +1,19 @@
 use aidoku::{
-	error::{AidokuError, AidokuErrorKind},
-	helpers::substring::Substring,
+	error::AidokuError,
 	std::{
 		defaults::{defaults_get, defaults_set},
-		net::{HttpMethod, Request},
+		net::HttpMethod,
 		String, StringRef, ValueRef,
 	},
 };
-use alloc::{format, string::ToString};
+use alloc::format;
+use zh_common::{error::Error, parse, Client, OptionExt, RequestBuilder};
 
 pub const WWW_URL: &str = "https://noy1.top";
 const PIC_URL: &str = "https://img.noy.asia";
 
+const CLIENT: Client = Client::new();
+
 pub fn explore(tag: String, sort: String, page: i32) -> Result<ValueRef, AidokuError> {
 	let url = if tag.is_empty() {
 		format!("{}/api/booklist_v2", WWW_URL)
@@ -52,76 +54,66 @@ pub fn details(manga_id: String) -> Result<ValueRef, AidokuError> {
 	get_json(url, body)
 }
 
-pub fn gen_request(url: String, method: HttpMethod) -> Request {
-	let session = defaults_get("session").unwrap().as_string().unwrap().read();
+pub fn gen_request(url: String, method: HttpMethod) -> Result<RequestBuilder, AidokuError> {
+	let session = defaults_get("session")?.as_string()?.read();
 	let session = if !url.contains("login") && session.is_empty() {
-		login().unwrap()
+		login()?
 	} else {
 		session
 	};
-	Request::new(url, method)
+	Ok(CLIENT
+		.request(url, method)
 		.header("Content-Type", "application/x-www-form-urlencoded")
-		.header("Cookie", &format!("NOY_SESSION={}", session))
+		.header("Cookie", format!("NOY_SESSION={}", session)))
 }
 
 pub fn login() -> Result<String, AidokuError> {
 	let url = format!("{}/api/login", WWW_URL);
-	let request = gen_request(url, HttpMethod::Post);
 	let username = defaults_get("username")?.as_string()?.read();
 	let password = defaults_get("password")?.as_string()?.read();
 
 	if username.is_empty() || password.is_empty() {
-		return Err(AidokuError {
-			reason: AidokuErrorKind::DefaultNotFound,
-		});
+		return Err(Error::LoginRequired.into());
 	}
 
 	let body = format!("user={}&pass={}", username, password);
-	let request = request.body(body.as_bytes());
-
-	request.send();
+	let request = gen_request(url, HttpMethod::Post)?
+		.body(body.as_bytes())
+		.send();
 
 	if request.status_code() != 200 {
-		return Err(AidokuError {
-			reason: AidokuErrorKind::DefaultNotFound,
-		});
+		return Err(Error::LoginRequired.into());
 	}
 
-	let cookie_header = request.get_header("set-cookie").unwrap().read();
-	let session = cookie_header
-		.substring_after("NOY_SESSION=")
-		.unwrap()
-		.substring_before(";")
-		.unwrap();
+	let cookie_header = request.get_header("set-cookie").or_parse_error()?.read();
+	let session = parse::between(&cookie_header, "NOY_SESSION=", ";")?;
 
-	defaults_set("session", StringRef::from(session).0);
+	defaults_set("session", StringRef::from(session.clone()).0);
 
-	Ok(session.to_string())
+	Ok(session)
 }
 
 pub fn get_json(url: String, body: String) -> Result<ValueRef, AidokuError> {
-	let mut request = gen_request(url.clone(), HttpMethod::Post).body(body.as_bytes());
-
-	request.send();
+	let request = gen_request(url, HttpMethod::Post)?
+		.body(body.as_bytes())
+		.idempotent();
+	let response = request.send();
+
+	if response.status_code() == 401 {
+		return request
+			.header("Cookie", format!("NOY_SESSION={}", login()?))
+			.json();
+	}
 
-	let status = request.status_code();
+	let json = response.json()?;
+	let list = json.clone().as_array().unwrap_or_default();
 
-	if status == 401 {
+	if list.get(0).as_string().unwrap_or_default().read() == "login" {
 		request
-			.header("Cookie", &format!("NOY_SESSION={}", &login()?))
+			.header("Cookie", format!("NOY_SESSION={}", login()?))
 			.json()
 	} else {
-		let json = request.json()?;
-		let list = json.clone().as_array().unwrap_or_default();
-
-		if list.get(0).as_string().unwrap_or_default().read() == "login" {
-			request = gen_request(url, HttpMethod::Post)
-				.body(body.as_bytes())
-				.header("Cookie", &format!("NOY_SESSION={}", &login()?));
-			return request.json();
-		} else {
-			return Ok(json);
-		}
+		Ok(json)
 	}
 }
 
@@ -5,9 +5,10 @@ use aidoku::{
 	error::Result,
 	prelude::*,
 	std::{net::Request, String, Vec},
-	Chapter, Filter, FilterType, Listing, Manga, MangaPageResult, Page,
+	Chapter, DeepLink, Filter, FilterType, Listing, Manga, MangaPageResult, Page,
 };
 use alloc::string::ToString;
+use zh_common::{convert, entity, error::parse_error, link, parse};
 
 mod helper;
 mod parser;
@@ -79,7 +80,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	let has_more = page * 20 < total;
 
 	Ok(MangaPageResult {
-		manga: parser::parse_manga_list(list),
+		manga: entity::titles(parser::parse_manga_list(list)?),
 		has_more,
 	})
 }
@@ -127,7 +128,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	let has_more = page * 20 < total;
 
 	Ok(MangaPageResult {
-		manga: parser::parse_manga_list(list),
+		manga: entity::titles(parser::parse_manga_list(list)?),
 		has_more,
 	})
 }
@@ -137,7 +138,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let json = helper::details(id)?;
 	let data = json.as_object()?;
 
-	Ok(parser::parse_manga(data))
+	parser::parse_manga(data).map(convert::manga)
 }
 
 #[get_chapter_list]
@@ -154,7 +155,7 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 		..Default::default()
 	});
 
-	Ok(chapters)
+	Ok(convert::chapters(chapters))
 }
 
 #[get_page_list]
@@ -182,3 +183,21 @@ fn get_page_list(manga_id: String, _: String) -> Result<Vec<Page>> {
 fn modify_image_request(request: Request) {
 	request.header("Referer", helper::WWW_URL);
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	// The site routes in the fragment, and each book is a single chapter
+	// sharing the book's ID.
+	let route = url.split_once('#').map(|a| a.1).unwrap_or_default();
+	let segments = parse::path_segments(route);
+	let (id, chapter_id) = match segments.as_slice() {
+		[kind, id, ..] if kind == "book" => (id.clone(), None),
+		[kind, id, ..] if kind == "read" => (id.clone(), Some(id.clone())),
+		_ => return Err(parse_error()),
+	};
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id)?),
+		chapter: chapter_id.map(link::chapter),
+	})
+}
@@ -1,4 +1,5 @@
 use aidoku::{
+	error::Result,
 	std::{ArrayRef, ObjectRef, String, Vec},
 	Manga, MangaContentRating, MangaStatus, MangaViewer,
 };
@@ -6,24 +7,23 @@ use alloc::string::ToString;
 
 use crate::helper;
 
-pub fn parse_manga_list(manga_list: ArrayRef) -> Vec<Manga> {
+pub fn parse_manga_list(manga_list: ArrayRef) -> Result<Vec<Manga>> {
 	manga_list
-		.map(|manga| parse_manga(manga.as_object().unwrap()))
-		.collect::<Vec<Manga>>()
+		.map(|manga| parse_manga(manga.as_object()?))
+		.collect::<Result<Vec<Manga>>>()
 }
 
-pub fn parse_manga(manga: ObjectRef) -> Manga {
-	let id = manga.get("Bid").as_int().unwrap().to_string();
+pub fn parse_manga(manga: ObjectRef) -> Result<Manga> {
+	let id = manga.get("Bid").as_int()?.to_string();
 	let cover = helper::gen_cover_url(id.clone());
-	let title = manga.get("Bookname").as_string().unwrap().read();
-	let author = manga.get("Author").as_string().unwrap().read();
+	let title = manga.get("Bookname").as_string()?.read();
+	let author = manga.get("Author").as_string()?.read();
 	let artist = String::new();
 	let description = String::new();
 	let url = helper::gen_manga_url(id.clone());
 	let categories = manga
 		.get("Ptag")
-		.as_string()
-		.unwrap()
+		.as_string()?
 		.read()
 		.split(" ")
 		.map(|category| category.to_string())
@@ -31,7 +31,7 @@ pub fn parse_manga(manga: ObjectRef) -> Manga {
 	let status = MangaStatus::Completed;
 	let nsfw = MangaContentRating::Nsfw;
 	let viewer = MangaViewer::Rtl;
-	Manga {
+	Ok(Manga {
 		id,
 		cover,
 		title,
@@ -43,5 +43,5 @@ pub fn parse_manga(manga: ObjectRef) -> Manga {
 		status,
 		nsfw,
 		viewer,
-	}
+	})
 }
@@ -5,17 +5,20 @@ use aidoku::{
 	error::Result,
 	helpers::{substring::Substring, uri::encode_uri},
 	prelude::*,
-	std::{
-		net::{HttpMethod, Request},
-		String, Vec,
-	},
-	Chapter, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	std::{String, Vec},
+	Chapter, DeepLink, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	chapter, convert, entity,
+	error::{markup_changed, parse_error},
+	link, parse, Client, OptionExt, MOBILE_UA,
+};
 
 const WWW_URL: &str = "https://se8.us/index.php";
-const UA: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 16_6 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/16.6 Mobile/15E148 Safari/604.1";
+
+const CLIENT: Client = Client::new().user_agent(MOBILE_UA);
 
 const FILTER_TAG: [&str; 22] = [
 	"", "61", "63", "62", "64", "11", "15", "17", "29", "31", "67", "68", "69", "75", "78", "84",
@@ -75,9 +78,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	} else {
 		format!("{}/search/{}/{}", WWW_URL, encode_uri(query.clone()), page)
 	};
-	let html = Request::new(url, HttpMethod::Get)
-		.header("User-Agent", UA)
-		.html()?;
+	let html = CLIENT.get(url).html()?;
 	let has_more = true;
 	let mut mangas: Vec<Manga> = Vec::new();
 
@@ -86,15 +87,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select("a")
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = parse::last_segment(item.select("a").attr("href").read());
 		let cover = if query.is_empty() {
 			item.select("a>img").attr("data-src").read()
 		} else {
@@ -110,7 +103,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -118,17 +111,8 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
 	let url = format!("{}/comic/{}", WWW_URL, id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get)
-		.header("User-Agent", UA)
-		.html()?;
-	let id = html
-		.html()
-		.read()
-		.substring_after("comic.show(")
-		.unwrap()
-		.substring_before(");")
-		.unwrap()
-		.to_string();
+	let html = CLIENT.get(url.clone()).html()?;
+	let id = parse::between(html.html().read(), "comic.show(", ");")?;
 	let cover = html
 		.select(".comic-info-box>.box-back")
 		.attr("style")
@@ -140,27 +124,22 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		.select(".comic-info-box>.comic-info>.au-name")
 		.text()
 		.read()
-		.replace("作者：", "")
-		.replace("&amp", "&")
+		.replace("作者：", "");
+	let author = entity::decode(author)
 		.split("&")
 		.filter(|a| !a.trim().is_empty())
 		.map(|a| a.to_string())
 		.collect::<Vec<String>>()
 		.join(", ");
 	let artist = String::new();
-	let description = html
-		.select(".comic-intro")
-		.text()
-		.read()
-		.trim()
-		.replace("&hellip", "…");
+	let description = html.select(".comic-intro").text().read().trim().to_string();
 	let categories = html
 		.select(".comic-info-box>.comic-info>.comic-tags")
 		.html()
 		.read()
 		.trim()
 		.substring_after("</span>")
-		.unwrap()
+		.or_parse_error()?
 		.trim()
 		.split(" ")
 		.filter(|a| !a.trim().is_empty())
@@ -170,7 +149,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let nsfw = MangaContentRating::Nsfw;
 	let viewer = MangaViewer::Scroll;
 
-	Ok(Manga {
+	Ok(convert::manga(Manga {
 		id,
 		cover,
 		title,
@@ -182,59 +161,44 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		status,
 		nsfw,
 		viewer,
-	})
+	}))
 }
 
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	let url = format!("{}/api/comic/chapter?mid={}", WWW_URL, id.clone());
-	let json = Request::new(url.clone(), HttpMethod::Get)
-		.header("User-Agent", UA)
-		.json()?;
+	let json = CLIENT.get(url.clone()).json()?;
 	let data = json.as_object()?;
 	let list = data.get("data").as_array()?;
 	let mut chapters: Vec<Chapter> = Vec::new();
 
-	for (index, item) in list.enumerate() {
+	for item in list {
 		let item = match item.as_object() {
 			Ok(item) => item,
 			Err(_) => continue,
 		};
 		let id = item.get("id").as_string()?.read();
-		let title = item
-			.get("name")
-			.as_string()?
-			.read()
-			.trim()
-			.replace("&lt;", "<")
-			.replace("&gt;", ">")
-			.replace("&#40;", "(")
-			.replace("&#41;", ")")
-			.replace("&ldquo;", "“")
-			.replace("&rdquo;", "”")
-			.replace("&hellip;", "…")
-			.replace("&hearts;", "♥");
-		let chapter = (index + 1) as f32;
+		let title = item.get("name").as_string()?.read().trim().to_string();
+		let number = chapter::parse(&title);
 		let url = item.get("link").as_string()?.read();
 		chapters.push(Chapter {
 			id,
 			title,
-			chapter,
+			volume: number.volume(),
+			chapter: number.chapter(),
 			url,
 			..Default::default()
 		});
 	}
 	chapters.reverse();
 
-	Ok(chapters)
+	Ok(convert::chapters(chapters))
 }
 
 #[get_page_list]
 fn get_page_list(_: String, chapter_id: String) -> Result<Vec<Page>> {
 	let url = format!("{}/chapter/{}", WWW_URL, chapter_id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get)
-		.header("User-Agent", UA)
-		.html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let mut pages: Vec<Page> = Vec::new();
 
 	for (index, item) in html.select(".comic-page>img").array().enumerate() {
@@ -251,5 +215,30 @@ fn get_page_list(_: String, chapter_id: String) -> Result<Vec<Page>> {
 		})
 	}
 
+	if pages.is_empty() {
+		return Err(markup_changed(".comic-page>img"));
+	}
+
 	Ok(pages)
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let segments = parse::path_segments(&url);
+	let (id, chapter_id) = match segments.as_slice() {
+		[.., kind, id] if kind == "comic" => (id.clone(), None),
+		[.., kind, chapter_id] if kind == "chapter" => {
+			// Chapter URLs do not name their comic, but the reader links back to it.
+			let url = format!("{}/chapter/{}", WWW_URL, chapter_id);
+			let html = CLIENT.get(url).html()?;
+			let id = parse::last_segment(html.select("a[href*='/comic/']").attr("href").read());
+			(id, Some(chapter_id.clone()))
+		}
+		_ => return Err(parse_error()),
+	};
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id)?),
+		chapter: chapter_id.map(link::chapter),
+	})
+}
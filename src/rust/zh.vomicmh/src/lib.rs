@@ -5,17 +5,17 @@ use aidoku::{
 	error::Result,
 	helpers::uri::encode_uri,
 	prelude::*,
-	std::{
-		net::{HttpMethod, Request},
-		String, Vec,
-	},
-	Chapter, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	std::{String, Vec},
+	Chapter, DeepLink, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
+use zh_common::{chapter, convert, entity, error::parse_error, link, parse, Client, OptionExt};
 
 const WWW_URL: &str = "http://www.vomicmh.com";
 const API_URL: &str = "http://api.vomicmh.com";
 
+const CLIENT: Client = Client::new();
+
 fn encode_img_url(url: String) -> String {
 	if !url.contains("%") {
 		encode_uri(url)
@@ -47,7 +47,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			page
 		)
 	};
-	let json = Request::new(url, HttpMethod::Get).json()?;
+	let json = CLIENT.get(url).json()?;
 	let data = json.as_object()?;
 	let data = data.get("data").as_object()?;
 	let list = data.get("result").as_array()?;
@@ -71,7 +71,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -83,7 +83,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		API_URL,
 		id.clone()
 	);
-	let json = Request::new(url, HttpMethod::Get).json()?;
+	let json = CLIENT.get(url).json()?;
 	let data = json.as_object()?;
 	let data = data.get("data").as_object()?;
 	let cover = encode_img_url(data.get("cover_img_url").as_string()?.read());
@@ -91,7 +91,8 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let author = data
 		.get("authors_name")
 		.as_array()?
-		.map(|a| a.as_string().unwrap().read())
+		.filter_map(|a| a.as_string().ok())
+		.map(|a| a.read())
 		.collect::<Vec<String>>()
 		.join(", ");
 	let artist = String::new();
@@ -100,7 +101,8 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let categories = data
 		.get("categories")
 		.as_array()?
-		.map(|a| a.as_string().unwrap().read())
+		.filter_map(|a| a.as_string().ok())
+		.map(|a| a.read())
 		.collect::<Vec<String>>();
 	let status = match data.get("status").as_string()?.read().as_str() {
 		"连载中" => MangaStatus::Ongoing,
@@ -110,7 +112,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let nsfw = MangaContentRating::Safe;
 	let viewer = MangaViewer::Scroll;
 
-	Ok(Manga {
+	Ok(convert::manga(Manga {
 		id,
 		cover,
 		title,
@@ -122,7 +124,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		status,
 		nsfw,
 		viewer,
-	})
+	}))
 }
 
 #[get_chapter_list]
@@ -132,31 +134,31 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 		API_URL,
 		id.clone()
 	);
-	let json = Request::new(url.clone(), HttpMethod::Get).json()?;
+	let json = CLIENT.get(url.clone()).json()?;
 	let data = json.as_object()?;
 	let list = data.get("data").as_array()?;
-	let len = list.len();
 	let mut chapters: Vec<Chapter> = Vec::new();
 
-	for (index, item) in list.enumerate() {
+	for item in list {
 		let item = match item.as_object() {
 			Ok(item) => item,
 			Err(_) => continue,
 		};
 		let chapter_id = item.get("cid").as_string()?.read();
 		let title = item.get("title").as_string()?.read();
-		let chapter = (len - index) as f32;
+		let number = chapter::parse(&title);
 		let url = format!("{}/#/page/{}/{}", WWW_URL, id.clone(), chapter_id.clone(),);
 		chapters.push(Chapter {
 			id: chapter_id,
 			title,
-			chapter,
+			volume: number.volume(),
+			chapter: number.chapter(),
 			url,
 			..Default::default()
 		});
 	}
 
-	Ok(chapters)
+	Ok(convert::chapters(chapters))
 }
 
 #[get_page_list]
@@ -167,7 +169,7 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 		chapter_id.clone(),
 		manga_id.clone()
 	);
-	let json = Request::new(url.clone(), HttpMethod::Get).json()?;
+	let json = CLIENT.get(url.clone()).json()?;
 	let data = json.as_object()?;
 	let list = data.get("img_url_list").as_array()?;
 	let mut pages: Vec<Page> = Vec::new();
@@ -188,3 +190,21 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 
 	Ok(pages)
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	// The site routes in the fragment: `/#/detail?id={id}` and
+	// `/#/page/{id}/{chapter_id}`.
+	let route = url.split_once('#').map(|a| a.1).unwrap_or_default();
+	let segments = parse::path_segments(route);
+	let (id, chapter_id) = match segments.as_slice() {
+		[kind] if kind == "detail" => (parse::query_param(route, "id").or_parse_error()?, None),
+		[kind, id, chapter_id] if kind == "page" => (id.clone(), Some(chapter_id.clone())),
+		_ => return Err(parse_error()),
+	};
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id)?),
+		chapter: chapter_id.map(link::chapter),
+	})
+}
@@ -0,0 +1,2 @@
+var imglist = [{ url: fast_img_host+\"//img5.qy0.ru/data/250001/01.jpg\", caption: \"[01]\"},{ url: fast_img_host+\"//img5.qy0.ru/data/250001/02.jpg\", caption: \"[02]\"}];
+document.writeln("<script>...</script>");
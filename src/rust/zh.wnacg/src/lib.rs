@@ -5,25 +5,26 @@ use aidoku::{
 	error::Result,
 	helpers::uri::encode_uri,
 	prelude::*,
-	std::{
-		net::{HttpMethod, Request},
-		String, Vec,
-	},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
-	MangaViewer, Page,
+	std::{net::Request, String, Vec},
+	Chapter, DeepLink, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult,
+	MangaStatus, MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	convert, entity, error::markup_changed, link, parse, Client, Mirrors, OptionExt, UA,
+};
+
+const MIRRORS: Mirrors = Mirrors::new(&["wnacg01.cc", "wnacg.com", "wnacg.ru"]);
 
-const WWW_URL: &str = "https://www.wnacg01.cc";
-const UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/135.0.0.0 Safari/537.36";
+const CLIENT: Client = Client::new().user_agent(UA).mirrors(&MIRRORS).clearance();
 
 const FILTER_CATEGORY: [&str; 4] = ["", "5", "6", "7"];
 const FILTER_CATEGORY_5: [&str; 4] = ["5", "1", "12", "16"];
 const FILTER_CATEGORY_6: [&str; 4] = ["6", "9", "13", "17"];
 const FILTER_CATEGORY_7: [&str; 4] = ["7", "10", "14", "18"];
 
-fn gen_request(url: String, method: HttpMethod) -> Request {
-	Request::new(url, method).header("User-Agent", UA)
+fn www_url() -> String {
+	format!("https://www.{}", MIRRORS.domain())
 }
 
 #[get_manga_list]
@@ -58,17 +59,19 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	let url = if query.is_empty() {
 		format!(
 			"{}/albums-index-page-{}-cate-{}.html",
-			WWW_URL, page, category
+			www_url(),
+			page,
+			category
 		)
 	} else {
 		format!(
 			"{}/search/index.php?q={}&s=create_time_DESC&syn=yes&p={}",
-			WWW_URL,
+			www_url(),
 			encode_uri(query),
 			page
 		)
 	};
-	let html = gen_request(url, HttpMethod::Get).html()?;
+	let html = CLIENT.get(url).html()?;
 	let has_more = true;
 	let mut mangas: Vec<Manga> = Vec::new();
 
@@ -77,15 +80,10 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select(".pic_box>a")
-			.attr("href")
-			.read()
-			.split("-")
-			.map(|a| a.replace(".html", ""))
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = parse::after_last(
+			parse::last_segment(item.select(".pic_box>a").attr("href").read()),
+			"-",
+		);
 		let cover = format!("https:{}", item.select(".pic_box>a>img").attr("src").read());
 		let title = item
 			.select(".info>.title>a")
@@ -102,7 +100,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -129,9 +127,11 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 
 	let url = format!(
 		"{}/albums-index-page-{}-cate-{}.html",
-		WWW_URL, page, category
+		www_url(),
+		page,
+		category
 	);
-	let html = gen_request(url, HttpMethod::Get).html()?;
+	let html = CLIENT.get(url).html()?;
 	let has_more = true;
 	let mut mangas: Vec<Manga> = Vec::new();
 
@@ -140,15 +140,10 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select(".pic_box>a")
-			.attr("href")
-			.read()
-			.split("-")
-			.map(|a| a.replace(".html", ""))
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = parse::after_last(
+			parse::last_segment(item.select(".pic_box>a").attr("href").read()),
+			"-",
+		);
 		let cover = format!("https:{}", item.select(".pic_box>a>img").attr("src").read());
 		let title = item
 			.select(".info>.title>a")
@@ -165,15 +160,15 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
 
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
-	let url = format!("{}/photos-index-aid-{}.html", WWW_URL, id.clone());
-	let html = gen_request(url.clone(), HttpMethod::Get).html()?;
+	let url = format!("{}/photos-index-aid-{}.html", www_url(), id.clone());
+	let html = CLIENT.get(url.clone()).html()?;
 	let cover = html
 		.select("#bodywrap>div>.uwthumb>img")
 		.attr("src")
@@ -194,17 +189,13 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		.flatten()
 		.map(|a| a.trim().to_string())
 		.collect::<Vec<String>>();
-	let tags = html
-		.select("#bodywrap>div>.uwconn>.addtags>.tagshow")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read().trim().to_string())
-		.collect::<Vec<String>>();
+	let tags = parse::texts(&html, "#bodywrap>div>.uwconn>.addtags>.tagshow");
 	let categories = [categories, tags].concat();
 	let status = MangaStatus::Unknown;
 	let nsfw = MangaContentRating::Nsfw;
 	let viewer = MangaViewer::Rtl;
 
-	Ok(Manga {
+	Ok(convert::manga(Manga {
 		id,
 		cover,
 		title,
@@ -216,12 +207,12 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		status,
 		nsfw,
 		viewer,
-	})
+	}))
 }
 
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
-	let url = format!("{}/photos-index-aid-{}.html", WWW_URL, id.clone());
+	let url = format!("{}/photos-index-aid-{}.html", www_url(), id.clone());
 	let mut chapters: Vec<Chapter> = Vec::new();
 	let title = String::from("第 1 话");
 	let chapter = 1 as f32;
@@ -233,13 +224,13 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 		..Default::default()
 	});
 
-	Ok(chapters)
+	Ok(convert::chapters(chapters))
 }
 
 #[get_page_list]
 fn get_page_list(manga_id: String, _: String) -> Result<Vec<Page>> {
-	let url = format!("{}/photos-gallery-aid-{}.html", WWW_URL, manga_id.clone());
-	let text = gen_request(url.clone(), HttpMethod::Get).string()?;
+	let url = format!("{}/photos-gallery-aid-{}.html", www_url(), manga_id.clone());
+	let text = CLIENT.get(url.clone()).string()?;
 	let urls = text
 		.split("\\\"")
 		.filter(|a| a.starts_with("//"))
@@ -256,10 +247,31 @@ fn get_page_list(manga_id: String, _: String) -> Result<Vec<Page>> {
 		})
 	}
 
+	if pages.is_empty() {
+		return Err(markup_changed("imglist"));
+	}
+
 	Ok(pages)
 }
 
 #[modify_image_request]
 fn modify_image_request(request: Request) {
-	request.header("Referer", WWW_URL);
+	CLIENT.apply(request).header("Referer", www_url().as_str());
+}
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	// Albums are `photos-{index,gallery,slide}-aid-{id}.html`, and each album
+	// is a single chapter sharing the album's ID.
+	let page = parse::last_segment(&url);
+	let id = page
+		.starts_with("photos-")
+		.then(|| parse::after_last(&page, "-"))
+		.or_parse_error()?;
+	let chapter_id = (!page.starts_with("photos-index-")).then(|| id.clone());
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id)?),
+		chapter: chapter_id.map(link::chapter),
+	})
 }
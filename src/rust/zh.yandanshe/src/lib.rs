@@ -5,17 +5,20 @@ use aidoku::{
 	error::Result,
 	helpers::uri::encode_uri,
 	prelude::*,
-	std::{
-		net::{HttpMethod, Request},
-		String, Vec,
-	},
-	Chapter, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	std::{net::Request, String, Vec},
+	Chapter, DeepLink, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{
+	chapter, convert, entity,
+	error::{markup_changed, parse_error},
+	link, parse, Client, UA,
+};
 
 const WWW_URL: &str = "https://yandanshe.com";
-const UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36";
+
+const CLIENT: Client = Client::new().user_agent(UA).referer(WWW_URL);
 
 const FILTER_CATEGORY: [&str; 3] = ["", "bl", "bg"];
 const FILTER_STATUS: [&str; 3] = ["", "lz", "wj"];
@@ -93,7 +96,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	} else {
 		format!("{}/page/{}/?s={}", WWW_URL, page, encode_uri(query.clone()))
 	};
-	let html = Request::new(url, HttpMethod::Get).html()?;
+	let html = CLIENT.get(url).html()?;
 	let has_more = true;
 	let mut mangas: Vec<Manga> = Vec::new();
 
@@ -102,16 +105,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select("h3>a")
-			.attr("href")
-			.read()
-			.split("/")
-			.map(|a| a.to_string())
-			.filter(|a| !a.is_empty())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = parse::last_segment(item.select("h3>a").attr("href").read());
 		let cover = item.select(".thumbnail>a>img").attr("src").read();
 		let title = item.select("h3>a").text().read();
 		mangas.push(Manga {
@@ -123,7 +117,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -131,14 +125,9 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
 	let url = format!("{}/{}", WWW_URL, id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let title = html.select("h1.article-title").text().read();
-	let author = html
-		.select(".article-meta>.item-author")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read())
-		.collect::<Vec<String>>()
-		.join(", ");
+	let author = parse::texts(&html, ".article-meta>.item-author").join(", ");
 	let artist = String::new();
 	let description = html
 		.select(".article-content>blockquote>p:nth-child(2)")
@@ -153,12 +142,8 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		.split("·")
 		.map(|a| a.to_string())
 		.collect::<Vec<String>>();
-	let mut tags = html
-		.select(".article-tags>.inner>a")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read())
-		.collect::<Vec<String>>();
-	let status = match categories.pop().unwrap().to_string().as_str() {
+	let mut tags = parse::texts(&html, ".article-tags>.inner>a");
+	let status = match categories.pop().unwrap_or_default().as_str() {
 		"連載" => MangaStatus::Ongoing,
 		"完結" => MangaStatus::Completed,
 		_ => MangaStatus::Unknown,
@@ -167,7 +152,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let viewer = MangaViewer::Rtl;
 	categories.append(&mut tags);
 
-	Ok(Manga {
+	Ok(convert::manga(Manga {
 		id,
 		cover: String::new(),
 		title,
@@ -179,13 +164,13 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		status,
 		nsfw,
 		viewer,
-	})
+	}))
 }
 
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	let url = format!("{}/{}", WWW_URL, id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let list = html.select(".list>*").array();
 	let mut chapters: Vec<Chapter> = Vec::new();
 
@@ -202,19 +187,20 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 			..Default::default()
 		});
 	} else {
-		for (index, item) in list.enumerate() {
+		for item in list {
 			let item = match item.as_node() {
 				Ok(item) => item,
 				Err(_) => continue,
 			};
 			let id = item.text().read().trim().to_string();
 			let title = format!("第 {} 话", id);
-			let chapter = (index + 1) as f32;
+			let number = chapter::parse(&title);
 			let url = format!("{}/{}/", url, id);
 			chapters.push(Chapter {
 				id,
 				title,
-				chapter,
+				volume: number.volume(),
+				chapter: number.chapter(),
 				url,
 				..Default::default()
 			});
@@ -222,13 +208,13 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 		chapters.reverse();
 	}
 
-	Ok(chapters)
+	Ok(convert::chapters(chapters))
 }
 
 #[get_page_list]
 fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 	let url = format!("{}/{}/{}/", WWW_URL, manga_id.clone(), chapter_id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let mut pages: Vec<Page> = Vec::new();
 
 	for (index, item) in html.select(".article-content>p>img").array().enumerate() {
@@ -245,10 +231,29 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 		})
 	}
 
+	if pages.is_empty() {
+		return Err(markup_changed(".article-content>p>img"));
+	}
+
 	Ok(pages)
 }
 
 #[modify_image_request]
 fn modify_image_request(request: Request) {
-	request.header("Referer", WWW_URL).header("User-Agent", UA);
+	CLIENT.apply(request);
+}
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let segments = parse::path_segments(&url);
+	let (id, chapter_id) = match segments.as_slice() {
+		[id] => (id.clone(), None),
+		[id, chapter_id] => (id.clone(), Some(chapter_id.clone())),
+		_ => return Err(parse_error()),
+	};
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id)?),
+		chapter: chapter_id.map(link::chapter),
+	})
 }
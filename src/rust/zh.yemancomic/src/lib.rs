@@ -5,17 +5,17 @@ use aidoku::{
 	error::Result,
 	helpers::uri::encode_uri,
 	prelude::*,
-	std::{
-		net::{HttpMethod, Request},
-		String, Vec,
-	},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
-	MangaViewer, Page,
+	std::{String, Vec},
+	Chapter, DeepLink, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult,
+	MangaStatus, MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{chapter, convert, entity, error::parse_error, link, parse, Client};
 
 const WWW_URL: &str = "https://yemancomic.com";
 
+const CLIENT: Client = Client::new();
+
 const FILTER_CATEGORY: [&str; 40] = [
 	"全部",
 	"长条",
@@ -104,7 +104,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			status,
 			page
 		);
-		let html = Request::new(url, HttpMethod::Get).html()?;
+		let html = CLIENT.get(url).html()?;
 		has_more = true;
 
 		for item in html.select(".acgn-item").array() {
@@ -112,16 +112,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 				Ok(node) => node,
 				Err(_) => continue,
 			};
-			let id = item
-				.select(".acgn-thumbnail")
-				.attr("href")
-				.read()
-				.split("/")
-				.filter(|a| !a.is_empty())
-				.map(|a| a.to_string())
-				.collect::<Vec<String>>()
-				.pop()
-				.unwrap();
+			let id = parse::last_segment(item.select(".acgn-thumbnail").attr("href").read());
 			let cover = item.select(".acgn-thumbnail>img").attr("src").read();
 			let title = item
 				.select(".acgn-info>.acgn-title>a")
@@ -139,10 +130,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	} else {
 		let url = format!("{}/api/front/index/search", WWW_URL);
 		let body = format!("key={}", query);
-		let json = Request::new(url, HttpMethod::Post)
-			.header("Content-Type", "application/x-www-form-urlencoded")
-			.body(body.as_bytes())
-			.json()?;
+		let json = CLIENT.post(url).form(body).json()?;
 		let data = json.as_object()?;
 		let list = data.get("data").as_array()?;
 
@@ -164,7 +152,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -193,7 +181,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	}
 
 	let url = format!("{}/top/{}.html", WWW_URL, name);
-	let html = Request::new(url, HttpMethod::Get).html()?;
+	let html = CLIENT.get(url).html()?;
 	let has_more = false;
 	let mut mangas: Vec<Manga> = Vec::new();
 
@@ -202,26 +190,10 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 			Ok(node) => node,
 			Err(_) => continue,
 		};
-		let id = item
-			.select(".acgn-thumbnail")
-			.attr("href")
-			.read()
-			.split("/")
-			.filter(|a| !a.is_empty())
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = parse::last_segment(item.select(".acgn-thumbnail").attr("href").read());
 		let cover = item.select(".acgn-thumbnail>img").attr("src").read();
-		let title = item
-			.select(".acgn-info>.acgn-title>a")
-			.attr("title")
-			.read()
-			.split(",")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let title = item.select(".acgn-info>.acgn-title>a").attr("title").read();
+		let title = parse::after_last(title, ",");
 		mangas.push(Manga {
 			id,
 			cover,
@@ -231,7 +203,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -239,7 +211,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
 	let url = format!("{}/book/{}/", WWW_URL, id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let cover = html
 		.select("meta[property='og:image']")
 		.attr("content")
@@ -282,7 +254,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let nsfw = MangaContentRating::Safe;
 	let viewer = MangaViewer::Scroll;
 
-	Ok(Manga {
+	Ok(convert::manga(Manga {
 		id,
 		cover,
 		title,
@@ -294,24 +266,24 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		status,
 		nsfw,
 		viewer,
-	})
+	}))
 }
 
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	let url = format!("{}/book/{}/", WWW_URL, id.clone());
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let list = html.select(".chapter-list>li").array();
 	let mut chapters: Vec<Chapter> = Vec::new();
 
-	for (index, item) in list.enumerate() {
+	for item in list {
 		let item = match item.as_node() {
 			Ok(item) => item,
 			Err(_) => continue,
 		};
 		let chapter_id = item.attr("data-chapter").read();
 		let title = item.select("a").attr("title").read();
-		let chapter = (index + 1) as f32;
+		let number = chapter::parse(&title);
 		let url = format!(
 			"{}/chapter/{}/{}.html",
 			WWW_URL,
@@ -321,14 +293,15 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 		chapters.push(Chapter {
 			id: chapter_id,
 			title,
-			chapter,
+			volume: number.volume(),
+			chapter: number.chapter(),
 			url,
 			..Default::default()
 		});
 	}
 	chapters.reverse();
 
-	Ok(chapters)
+	Ok(convert::chapters(chapters))
 }
 
 #[get_page_list]
@@ -339,7 +312,7 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 		manga_id.clone(),
 		chapter_id.clone()
 	);
-	let html = Request::new(url.clone(), HttpMethod::Get).html()?;
+	let html = CLIENT.get(url.clone()).html()?;
 	let mut pages: Vec<Page> = Vec::new();
 
 	for (index, item) in html
@@ -362,3 +335,20 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 
 	Ok(pages)
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let segments = parse::path_segments(&url);
+	let (id, chapter_id) = match segments.as_slice() {
+		[kind, id] if kind == "book" => (id.clone(), None),
+		[kind, id, chapter_id] if kind == "chapter" => {
+			(id.clone(), Some(parse::last_segment(chapter_id)))
+		}
+		_ => return Err(parse_error()),
+	};
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id)?),
+		chapter: chapter_id.map(link::chapter),
+	})
+}
@@ -5,18 +5,18 @@ use aidoku::{
 	error::Result,
 	helpers::uri::encode_uri,
 	prelude::*,
-	std::{
-		net::{HttpMethod, Request},
-		String, Vec,
-	},
-	Chapter, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
-	MangaViewer, Page,
+	std::{String, Vec},
+	Chapter, DeepLink, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult,
+	MangaStatus, MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{chapter, convert, entity, error::parse_error, link, parse, Client, OptionExt};
 
 const WWW_URL: &str = "https://m.zaimanhua.com";
 const API_URL: &str = "https://manhua.zaimanhua.com/api/v1";
 const APP_URL: &str = "https://manhua.zaimanhua.com/app/v1";
+
+const CLIENT: Client = Client::new();
 const V4_APP_URL: &str = "https://v4api.zaimanhua.com/app/v1";
 
 const FILTER_STATUS: [&str; 3] = ["0", "1", "2"];
@@ -92,7 +92,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 		)
 	};
 
-	let json = Request::new(url, HttpMethod::Get).json()?;
+	let json = CLIENT.get(url).json()?;
 	let data = json.as_object()?;
 	let data = data.get("data").as_object()?;
 	let mut mangas: Vec<Manga> = Vec::new();
@@ -136,7 +136,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more: true,
 	})
 }
@@ -176,7 +176,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 
 	let url = format!("{}/comic1/rank_list?channel=pc&app_name=zmh&version=1.0.0&page={}&size=10&duration={}&cate={}&tag=0&theme=0",
 		API_URL, page, duration, cate);
-	let json = Request::new(url, HttpMethod::Get).json()?;
+	let json = CLIENT.get(url).json()?;
 	let data = json.as_object()?;
 	let data = data.get("data").as_object()?;
 	let list = data.get("list").as_array()?;
@@ -199,7 +199,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more: true,
 	})
 }
@@ -207,39 +207,18 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
 	let url = format!("{}/comic/detail/{}", V4_APP_URL, id.clone());
-	let json = Request::new(url, HttpMethod::Get).json()?;
+	let json = CLIENT.get(url).json()?;
 	let data = json.as_object()?;
 	let data = data.get("data").as_object()?;
 	let data = data.get("data").as_object()?;
 	let cover = data.get("cover").as_string()?.read();
 	let title = data.get("title").as_string()?.read();
 	let authors = data.get("authors").as_array()?;
-	let author = authors
-		.map(|a| {
-			a.as_object()
-				.unwrap()
-				.get("tag_name")
-				.as_string()
-				.unwrap()
-				.read()
-		})
-		.collect::<Vec<String>>()
-		.join(", ");
+	let author = parse::object_strings(authors, "tag_name").join(", ");
 	let artist = String::new();
 	let description = data.get("description").as_string()?.read();
 	let url = format!("{}/pages/comic/detail?id={}", WWW_URL, id);
-	let categories = data
-		.get("types")
-		.as_array()?
-		.map(|a| {
-			a.as_object()
-				.unwrap()
-				.get("tag_name")
-				.as_string()
-				.unwrap()
-				.read()
-		})
-		.collect::<Vec<String>>();
+	let categories = parse::object_strings(data.get("types").as_array()?, "tag_name");
 	let status = match data
 		.get("status")
 		.as_array()?
@@ -257,7 +236,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let nsfw = MangaContentRating::Safe;
 	let viewer = MangaViewer::Rtl;
 
-	Ok(Manga {
+	Ok(convert::manga(Manga {
 		id,
 		cover,
 		title,
@@ -269,30 +248,34 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		status,
 		nsfw,
 		viewer,
-	})
+	}))
 }
 
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	let url = format!("{}/comic/detail/{}", V4_APP_URL, id.clone());
-	let json = Request::new(url, HttpMethod::Get).json()?;
+	let json = CLIENT.get(url).json()?;
 	let data = json.as_object()?;
 	let data = data.get("data").as_object()?;
 	let data = data.get("data").as_object()?;
 	let chapter_list = data.get("chapters").as_array()?;
 	let chapter = chapter_list.get(0).as_object()?;
 	let list = chapter.get("data").as_array()?;
-	let len = list.len();
 	let mut chapters: Vec<Chapter> = Vec::new();
 
-	for (index, item) in list.enumerate() {
+	for item in list {
 		let item = match item.as_object() {
 			Ok(item) => item,
 			Err(_) => continue,
 		};
 		let chapter_id = item.get("chapter_id").as_int()?.to_string();
 		let title = item.get("chapter_title").as_string()?.read();
-		let chapter = (len - index) as f32;
+		let number = chapter::parse(&title);
+		let date_updated = item
+			.get("updatetime")
+			.as_int()
+			.map(|a| a as f64)
+			.unwrap_or(-1.0);
 		let url = format!(
 			"{}/pages/comic/page?comic_id={}&chapter_id={}",
 			WWW_URL,
@@ -302,13 +285,15 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 		chapters.push(Chapter {
 			id: chapter_id,
 			title,
-			chapter,
+			volume: number.volume(),
+			chapter: number.chapter(),
+			date_updated,
 			url,
 			..Default::default()
 		});
 	}
 
-	Ok(chapters)
+	Ok(convert::chapters(chapters))
 }
 
 #[get_page_list]
@@ -318,7 +303,7 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 		"{}/comic1/chapter/detail?channel=pc&app_name=zmh&version=1.0.0&comic_id={}&chapter_id={}",
 		API_URL, manga_id.clone(), chapter_id.clone()
 	);
-	let json = Request::new(url, HttpMethod::Get).json()?;
+	let json = CLIENT.get(url).json()?;
 	let data = json.as_object()?;
 	let data = data.get("data").as_object()?;
 	let data = data.get("chapterInfo").as_object()?;
@@ -341,3 +326,23 @@ fn get_page_list(manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 
 	Ok(pages)
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let segments = parse::path_segments(&url);
+	let (id, chapter_id) = match segments.as_slice() {
+		[pages, comic, kind] if pages == "pages" && comic == "comic" && kind == "detail" => {
+			(parse::query_param(&url, "id"), None)
+		}
+		[pages, comic, kind] if pages == "pages" && comic == "comic" && kind == "page" => (
+			parse::query_param(&url, "comic_id"),
+			parse::query_param(&url, "chapter_id"),
+		),
+		_ => return Err(parse_error()),
+	};
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id.or_parse_error()?)?),
+		chapter: chapter_id.map(link::chapter),
+	})
+}
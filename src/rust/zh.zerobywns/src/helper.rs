@@ -1,20 +1,16 @@
 use aidoku::{
-	error::{AidokuError, AidokuErrorKind},
+	error::AidokuError,
 	prelude::format,
 	std::{
 		defaults::{defaults_get, defaults_set},
 		html::Node,
-		net::{HttpMethod, Request},
 		StringRef,
 	},
 };
 use alloc::{string::String, vec::Vec};
+use zh_common::{error::Error, Client, UA};
 
-const UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/135.0.0.0 Safari/537.36";
-
-fn gen_request(url: String, method: HttpMethod) -> Request {
-	Request::new(url, method).header("User-Agent", UA)
-}
+const CLIENT: Client = Client::new().user_agent(UA);
 
 fn handle_cookie_header(cookie_header: String) -> String {
 	return cookie_header
@@ -31,17 +27,18 @@ fn get_default(key: &str) -> Result<String, AidokuError> {
 }
 
 pub fn get_url() -> String {
-	get_default("url").unwrap()
+	get_default("url").unwrap_or_default()
 }
 
 pub fn get_html(url: String) -> Result<Node, AidokuError> {
 	let default_cookie = get_default("cookie")?;
-	let request = gen_request(url.clone(), HttpMethod::Get).header("Cookie", &default_cookie);
-
-	request.send();
+	let request = CLIENT
+		.get(url.clone())
+		.header("Cookie", &default_cookie)
+		.send();
 
 	let cookie_header = request.get_header("set-cookie").unwrap_or_default().read();
-	let html = request.html().unwrap();
+	let html = request.html()?;
 
 	if html
 		.select("#main_message #messagetext>p")
@@ -53,9 +50,7 @@ pub fn get_html(url: String) -> Result<Node, AidokuError> {
 		let password = get_default("password")?;
 
 		if username.is_empty() || password.is_empty() {
-			return Err(AidokuError {
-				reason: AidokuErrorKind::DefaultNotFound,
-			});
+			return Err(Error::LoginRequired.into());
 		}
 
 		let formhash = html.select("input[name=formhash]").attr("value").read();
@@ -65,12 +60,11 @@ pub fn get_html(url: String) -> Result<Node, AidokuError> {
 			username, password, formhash
 		);
 		let login_url = format!("{}/member.php?mod=logging&action=login&loginsubmit=yes&infloat=yes&lssubmit=yes&inajax=1", get_url());
-		let login_request = gen_request(login_url, HttpMethod::Post)
-			.header("Content-Type", "application/x-www-form-urlencoded")
+		let login_request = CLIENT
+			.post(login_url)
 			.header("Cookie", &login_cookie)
-			.body(body.as_bytes());
-
-		login_request.send();
+			.form(body)
+			.send();
 
 		let new_cookie_header = login_request
 			.get_header("set-cookie")
@@ -78,9 +72,7 @@ pub fn get_html(url: String) -> Result<Node, AidokuError> {
 			.read();
 
 		if !new_cookie_header.contains("auth") {
-			return Err(AidokuError {
-				reason: AidokuErrorKind::DefaultNotFound,
-			});
+			return Err(Error::LoginRequired.into());
 		}
 
 		let new_cookie = handle_cookie_header(new_cookie_header);
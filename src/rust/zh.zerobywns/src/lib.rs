@@ -3,13 +3,14 @@ extern crate alloc;
 
 use aidoku::{
 	error::Result,
-	helpers::{substring::Substring, uri::encode_uri},
+	helpers::uri::encode_uri,
 	prelude::*,
 	std::{json, net::Request, String, Vec},
-	Chapter, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
+	Chapter, DeepLink, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
 use alloc::string::ToString;
+use zh_common::{chapter, convert, entity, link, parse, OptionExt};
 
 mod helper;
 
@@ -105,15 +106,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 				Ok(node) => node,
 				Err(_) => continue,
 			};
-			let id = item
-				.select("div:nth-child(1)>a")
-				.attr("href")
-				.read()
-				.split("=")
-				.map(|a| a.to_string())
-				.collect::<Vec<String>>()
-				.pop()
-				.unwrap();
+			let id = parse::after_last(item.select("div:nth-child(1)>a").attr("href").read(), "=");
 			let cover = item.select("div:nth-child(1)>a>img").attr("src").read();
 			let title = item
 				.select("div:nth-child(2)>p>a")
@@ -142,14 +135,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 				Ok(node) => node,
 				Err(_) => continue,
 			};
-			let id = item
-				.attr("href")
-				.read()
-				.split("=")
-				.map(|a| a.to_string())
-				.collect::<Vec<String>>()
-				.pop()
-				.unwrap();
+			let id = parse::after_last(item.attr("href").read(), "=");
 			let cover = item.select("div:nth-child(1)>img").attr("src").read();
 			let title = item
 				.select("div:nth-child(2)>p")
@@ -167,7 +153,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	}
 
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: entity::titles(mangas),
 		has_more,
 	})
 }
@@ -198,11 +184,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		.read()
 		.trim()
 		.to_string();
-	let categories = html
-		.select(".uk-margin-left>ul>li>.cl>a[href*='category']")
-		.array()
-		.map(|a| a.as_node().unwrap().text().read())
-		.collect::<Vec<String>>();
+	let categories = parse::texts(&html, ".uk-margin-left>ul>li>.cl>a[href*='category']");
 	let status = match html
 		.select(".uk-margin-left>ul>li>.cl>span:nth-child(6)")
 		.text()
@@ -216,7 +198,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	let nsfw = MangaContentRating::Safe;
 	let viewer = MangaViewer::Rtl;
 
-	Ok(Manga {
+	Ok(convert::manga(Manga {
 		id,
 		cover,
 		title,
@@ -228,7 +210,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		status,
 		nsfw,
 		viewer,
-	})
+	}))
 }
 
 #[get_chapter_list]
@@ -242,21 +224,14 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	let list = html.select(".muludiv>a").array();
 	let mut chapters: Vec<Chapter> = Vec::new();
 
-	for (index, item) in list.enumerate() {
+	for item in list {
 		let item = match item.as_node() {
 			Ok(item) => item,
 			Err(_) => continue,
 		};
-		let id = item
-			.attr("href")
-			.read()
-			.split("=")
-			.map(|a| a.to_string())
-			.collect::<Vec<String>>()
-			.pop()
-			.unwrap();
+		let id = parse::after_last(item.attr("href").read(), "=");
 		let title = item.text().read();
-		let chapter = (index + 1) as f32;
+		let number = chapter::parse(&title);
 		let url = format!(
 			"{}/plugin.php?id=jameson_manhua&a=read&zjid={}",
 			helper::get_url(),
@@ -265,14 +240,15 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 		chapters.push(Chapter {
 			id,
 			title,
-			chapter,
+			volume: number.volume(),
+			chapter: number.chapter(),
 			url,
 			..Default::default()
 		});
 	}
 	chapters.reverse();
 
-	Ok(chapters)
+	Ok(convert::chapters(chapters))
 }
 
 #[get_page_list]
@@ -284,12 +260,8 @@ fn get_page_list(_: String, chapter_id: String) -> Result<Vec<Page>> {
 	);
 	let html = helper::get_html(url)?;
 	let text = html.html().read();
-	let list = text
-		.substring_after("let listimg=")
-		.unwrap()
-		.substring_before(";")
-		.unwrap();
-	let list = json::parse(list).unwrap().as_array()?;
+	let list = parse::between(&text, "let listimg=", ";")?;
+	let list = json::parse(list)?.as_array()?;
 	let mut pages: Vec<Page> = Vec::new();
 
 	for (index, item) in list.enumerate() {
@@ -313,3 +285,26 @@ fn get_page_list(_: String, chapter_id: String) -> Result<Vec<Page>> {
 fn modify_image_request(request: Request) {
 	request.header("Referer", &helper::get_url());
 }
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let (id, chapter_id) = match parse::query_param(&url, "zjid") {
+		Some(chapter_id) => {
+			// Chapter URLs do not name their comic, but the reader links back to it.
+			let url = format!(
+				"{}/plugin.php?id=jameson_manhua&a=read&zjid={}",
+				helper::get_url(),
+				chapter_id.clone()
+			);
+			let html = helper::get_html(url)?;
+			let href = html.select("a[href*='kuid=']").attr("href").read();
+			(parse::query_param(href, "kuid"), Some(chapter_id))
+		}
+		None => (parse::query_param(&url, "kuid"), None),
+	};
+
+	Ok(DeepLink {
+		manga: Some(get_manga_details(id.or_parse_error()?)?),
+		chapter: chapter_id.map(link::chapter),
+	})
+}